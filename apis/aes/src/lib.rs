@@ -0,0 +1,263 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform as platform;
+use libtock_platform::allow_ro::AllowRo;
+use libtock_platform::allow_rw::AllowRw;
+use libtock_platform::share;
+use libtock_platform::subscribe::Subscribe;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+/// The AES driver.
+///
+/// It encrypts and decrypts data with AES-128 using the kernel's (usually
+/// hardware-backed) implementation, in ECB, CBC or CTR mode, or with CCM
+/// authenticated encryption.
+///
+/// # Example
+/// ```ignore
+/// use libtock::aes::{Aes, Mode};
+///
+/// let mut ciphertext = [0; 32];
+/// Aes::encrypt(Mode::Cbc, &key, &iv, &plaintext, &mut ciphertext)?;
+///
+/// // Decrypt without a second buffer.
+/// Aes::decrypt_in_place(Mode::Cbc, &key, &iv, &mut ciphertext)?;
+/// ```
+pub struct Aes<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> Aes<S, C> {
+    /// Run a check against the AES capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Encrypts `source` into `dest`, which must be the same length. `iv` is
+    /// the initialization vector for CBC mode, or the initial counter block for
+    /// CTR mode, and is ignored in ECB mode.
+    ///
+    /// In ECB and CBC modes, the length must be a multiple of `BLOCK_SIZE`.
+    pub fn encrypt(
+        mode: Mode,
+        key: &[u8; KEY_SIZE],
+        iv: &[u8; BLOCK_SIZE],
+        source: &[u8],
+        dest: &mut [u8],
+    ) -> Result<(), ErrorCode> {
+        if source.len() != dest.len() {
+            return Err(ErrorCode::Size);
+        }
+        Self::crypt(mode as u32, true, key, iv, source, dest)
+    }
+
+    /// Decrypts `source` into `dest`. See `encrypt` for the requirements on
+    /// the arguments.
+    pub fn decrypt(
+        mode: Mode,
+        key: &[u8; KEY_SIZE],
+        iv: &[u8; BLOCK_SIZE],
+        source: &[u8],
+        dest: &mut [u8],
+    ) -> Result<(), ErrorCode> {
+        if source.len() != dest.len() {
+            return Err(ErrorCode::Size);
+        }
+        Self::crypt(mode as u32, false, key, iv, source, dest)
+    }
+
+    /// Encrypts `data` in place. See `encrypt` for the requirements on the
+    /// arguments.
+    pub fn encrypt_in_place(
+        mode: Mode,
+        key: &[u8; KEY_SIZE],
+        iv: &[u8; BLOCK_SIZE],
+        data: &mut [u8],
+    ) -> Result<(), ErrorCode> {
+        Self::crypt(mode as u32, true, key, iv, &[], data)
+    }
+
+    /// Decrypts `data` in place. See `encrypt` for the requirements on the
+    /// arguments.
+    pub fn decrypt_in_place(
+        mode: Mode,
+        key: &[u8; KEY_SIZE],
+        iv: &[u8; BLOCK_SIZE],
+        data: &mut [u8],
+    ) -> Result<(), ErrorCode> {
+        Self::crypt(mode as u32, false, key, iv, &[], data)
+    }
+
+    /// Encrypts and authenticates a message in place using AES-CCM.
+    ///
+    /// `buffer` holds, in order, `aad_len` bytes of additional data, which
+    /// are authenticated but not encrypted, the message, and `mic_len` bytes
+    /// of space for the message integrity code, which is written by this call.
+    /// `mic_len` must be 4, 8 or 16.
+    pub fn ccm_encrypt(
+        key: &[u8; KEY_SIZE],
+        nonce: &[u8; CCM_NONCE_SIZE],
+        buffer: &mut [u8],
+        aad_len: usize,
+        mic_len: usize,
+    ) -> Result<(), ErrorCode> {
+        Self::ccm(true, key, nonce, buffer, aad_len, mic_len)
+    }
+
+    /// Verifies and decrypts a message in place using AES-CCM. `buffer` is
+    /// laid out as for `ccm_encrypt`, with the message integrity code at the
+    /// end.
+    ///
+    /// Fails with `ErrorCode::Fail` if the message integrity code does not
+    /// match, in which case the contents of the message are unspecified.
+    pub fn ccm_decrypt(
+        key: &[u8; KEY_SIZE],
+        nonce: &[u8; CCM_NONCE_SIZE],
+        buffer: &mut [u8],
+        aad_len: usize,
+        mic_len: usize,
+    ) -> Result<(), ErrorCode> {
+        Self::ccm(false, key, nonce, buffer, aad_len, mic_len)
+    }
+}
+
+/// The size of an AES-128 key, in bytes.
+pub const KEY_SIZE: usize = 16;
+
+/// The size of an AES block, in bytes.
+pub const BLOCK_SIZE: usize = 16;
+
+/// The size of an AES-CCM nonce, in bytes.
+pub const CCM_NONCE_SIZE: usize = 13;
+
+/// A block cipher mode of operation.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Mode {
+    Ctr = 0,
+    Cbc = 1,
+    Ecb = 2,
+}
+
+/// System call configuration trait for `Aes`.
+pub trait Config:
+    platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config
+{
+}
+impl<T: platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config>
+    Config for T
+{
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls, C: Config> Aes<S, C> {
+    fn ccm(
+        encrypting: bool,
+        key: &[u8; KEY_SIZE],
+        nonce: &[u8; CCM_NONCE_SIZE],
+        buffer: &mut [u8],
+        aad_len: usize,
+        mic_len: usize,
+    ) -> Result<(), ErrorCode> {
+        if aad_len + mic_len > buffer.len() {
+            return Err(ErrorCode::Size);
+        }
+        S::command(DRIVER_NUM, command::CCM_SET_AAD_LEN, aad_len as u32, 0)
+            .to_result::<(), ErrorCode>()?;
+        S::command(DRIVER_NUM, command::CCM_SET_MIC_LEN, mic_len as u32, 0)
+            .to_result::<(), ErrorCode>()?;
+        Self::crypt(MODE_CCM, encrypting, key, nonce, &[], buffer)
+    }
+
+    // Runs an operation with `mode`. If `source` is empty, `dest` is
+    // processed in place.
+    fn crypt(
+        mode: u32,
+        encrypting: bool,
+        key: &[u8],
+        iv: &[u8],
+        source: &[u8],
+        dest: &mut [u8],
+    ) -> Result<(), ErrorCode> {
+        let called = Cell::new(Option::<(u32,)>::None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, { allow_ro::KEY }>,
+                AllowRo<_, DRIVER_NUM, { allow_ro::IV }>,
+                AllowRo<_, DRIVER_NUM, { allow_ro::SOURCE }>,
+                AllowRw<_, DRIVER_NUM, { allow_rw::DEST }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::DONE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_key, allow_iv, allow_source, allow_dest, subscribe) = handle.split();
+            S::allow_ro::<C, DRIVER_NUM, { allow_ro::KEY }>(allow_key, key)?;
+            S::allow_ro::<C, DRIVER_NUM, { allow_ro::IV }>(allow_iv, iv)?;
+            if !source.is_empty() {
+                S::allow_ro::<C, DRIVER_NUM, { allow_ro::SOURCE }>(allow_source, source)?;
+            }
+            S::allow_rw::<C, DRIVER_NUM, { allow_rw::DEST }>(allow_dest, dest)?;
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::DONE }>(subscribe, &called)?;
+
+            S::command(DRIVER_NUM, command::SET_ALGORITHM, mode, 0).to_result::<(), ErrorCode>()?;
+            S::command(DRIVER_NUM, command::SETUP, encrypting as u32, 0)
+                .to_result::<(), ErrorCode>()?;
+            // When this fails, `called` is guaranteed unmodified,
+            // because upcalls are never processed until we call `yield`.
+            S::command(DRIVER_NUM, command::CRYPT, 0, 0).to_result::<(), ErrorCode>()?;
+
+            loop {
+                S::yield_wait();
+                if let Some((status,)) = called.get() {
+                    return match status {
+                        0 => Ok(()),
+                        e_status => Err(e_status.try_into().unwrap_or(ErrorCode::Fail)),
+                    };
+                }
+            }
+        })
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x40006;
+
+// The algorithm number for AES-CCM, which is not a `Mode`.
+const MODE_CCM: u32 = 3;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const SET_ALGORITHM: u32 = 1;
+    pub const SETUP: u32 = 2;
+    pub const CRYPT: u32 = 3;
+    pub const CCM_SET_AAD_LEN: u32 = 5;
+    pub const CCM_SET_MIC_LEN: u32 = 6;
+}
+
+mod subscribe {
+    pub const DONE: u32 = 0;
+}
+
+mod allow_ro {
+    pub const KEY: u32 = 0;
+    pub const IV: u32 = 1;
+    pub const SOURCE: u32 = 2;
+}
+
+mod allow_rw {
+    pub const DEST: u32 = 0;
+}
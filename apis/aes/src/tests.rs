@@ -0,0 +1,209 @@
+use super::*;
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type Aes = super::Aes<fake::Syscalls>;
+
+// Test vectors from NIST SP 800-38A, appendix F.
+const KEY: [u8; 16] = [
+    0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c,
+];
+const PLAINTEXT: [u8; 32] = [
+    0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17, 0x2a,
+    0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c, 0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51,
+];
+const ECB_CIPHERTEXT: [u8; 32] = [
+    0x3a, 0xd7, 0x7b, 0xb4, 0x0d, 0x7a, 0x36, 0x60, 0xa8, 0x9e, 0xca, 0xf3, 0x24, 0x66, 0xef, 0x97,
+    0xf5, 0xd3, 0xd5, 0x85, 0x03, 0xb9, 0x69, 0x9d, 0xe7, 0x85, 0x89, 0x5a, 0x96, 0xfd, 0xba, 0xaf,
+];
+const CBC_IV: [u8; 16] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+];
+const CBC_CIPHERTEXT: [u8; 32] = [
+    0x76, 0x49, 0xab, 0xac, 0x81, 0x19, 0xb2, 0x46, 0xce, 0xe9, 0x8e, 0x9b, 0x12, 0xe9, 0x19, 0x7d,
+    0x50, 0x86, 0xcb, 0x9b, 0x50, 0x72, 0x19, 0xee, 0x95, 0xdb, 0x11, 0x3a, 0x91, 0x76, 0x78, 0xb2,
+];
+const CTR_IV: [u8; 16] = [
+    0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd, 0xfe, 0xff,
+];
+const CTR_CIPHERTEXT: [u8; 32] = [
+    0x87, 0x4d, 0x61, 0x91, 0xb6, 0x20, 0xe3, 0x26, 0x1b, 0xef, 0x68, 0x64, 0x99, 0x0d, 0xb6, 0xce,
+    0x98, 0x06, 0xf6, 0x6b, 0x79, 0x70, 0xfd, 0xff, 0x86, 0x17, 0x18, 0x7b, 0xb9, 0xff, 0xfd, 0xff,
+];
+
+// RFC 3610 packet vector #1: 8 bytes of additional data, a 23 byte message,
+// and an 8 byte MIC.
+const CCM_KEY: [u8; 16] = [
+    0xc0, 0xc1, 0xc2, 0xc3, 0xc4, 0xc5, 0xc6, 0xc7, 0xc8, 0xc9, 0xca, 0xcb, 0xcc, 0xcd, 0xce, 0xcf,
+];
+const CCM_NONCE: [u8; 13] = [
+    0x00, 0x00, 0x00, 0x03, 0x02, 0x01, 0x00, 0xa0, 0xa1, 0xa2, 0xa3, 0xa4, 0xa5,
+];
+const CCM_PLAINTEXT: [u8; 39] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+    0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+const CCM_CIPHERTEXT: [u8; 39] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x58, 0x8c, 0x97, 0x9a, 0x61, 0xc6, 0x63, 0xd2,
+    0xf0, 0x66, 0xd0, 0xc2, 0xc0, 0xf9, 0x89, 0x80, 0x6d, 0x5f, 0x6b, 0x61, 0xda, 0xc3, 0x84, 0x17,
+    0xe8, 0xd1, 0x2c, 0xfd, 0xf9, 0x26, 0xe0,
+];
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Aes::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Aes::new();
+    kernel.add_driver(&driver);
+
+    assert!(Aes::driver_check());
+}
+
+#[test]
+fn modes() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Aes::new();
+    kernel.add_driver(&driver);
+
+    for (mode, iv, ciphertext) in [
+        (Mode::Ecb, [0; 16], ECB_CIPHERTEXT),
+        (Mode::Cbc, CBC_IV, CBC_CIPHERTEXT),
+        (Mode::Ctr, CTR_IV, CTR_CIPHERTEXT),
+    ] {
+        let mut buffer = [0; 32];
+        assert_eq!(
+            Aes::encrypt(mode, &KEY, &iv, &PLAINTEXT, &mut buffer),
+            Ok(())
+        );
+        assert_eq!(buffer, ciphertext);
+        assert_eq!(
+            Aes::decrypt(mode, &KEY, &iv, &ciphertext, &mut buffer),
+            Ok(())
+        );
+        assert_eq!(buffer, PLAINTEXT);
+    }
+}
+
+#[test]
+fn in_place() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Aes::new();
+    kernel.add_driver(&driver);
+
+    let mut buffer = PLAINTEXT;
+    assert_eq!(
+        Aes::encrypt_in_place(Mode::Cbc, &KEY, &CBC_IV, &mut buffer),
+        Ok(())
+    );
+    assert_eq!(buffer, CBC_CIPHERTEXT);
+    assert_eq!(
+        Aes::decrypt_in_place(Mode::Cbc, &KEY, &CBC_IV, &mut buffer),
+        Ok(())
+    );
+    assert_eq!(buffer, PLAINTEXT);
+
+    // CTR mode does not need whole blocks.
+    let mut buffer = [0; 5];
+    buffer.copy_from_slice(&PLAINTEXT[..5]);
+    assert_eq!(
+        Aes::encrypt_in_place(Mode::Ctr, &KEY, &CTR_IV, &mut buffer),
+        Ok(())
+    );
+    assert_eq!(buffer, CTR_CIPHERTEXT[..5]);
+}
+
+#[test]
+fn invalid_lengths() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Aes::new();
+    kernel.add_driver(&driver);
+
+    let mut buffer = [0; 20];
+    assert_eq!(
+        Aes::encrypt(Mode::Ecb, &KEY, &[0; 16], &PLAINTEXT, &mut buffer),
+        Err(ErrorCode::Size)
+    );
+    assert_eq!(
+        Aes::encrypt_in_place(Mode::Ecb, &KEY, &[0; 16], &mut buffer),
+        Err(ErrorCode::Invalid)
+    );
+}
+
+#[test]
+fn ccm() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Aes::new();
+    kernel.add_driver(&driver);
+
+    let mut buffer = CCM_PLAINTEXT;
+    assert_eq!(
+        Aes::ccm_encrypt(&CCM_KEY, &CCM_NONCE, &mut buffer, 8, 8),
+        Ok(())
+    );
+    assert_eq!(buffer, CCM_CIPHERTEXT);
+    assert_eq!(
+        Aes::ccm_decrypt(&CCM_KEY, &CCM_NONCE, &mut buffer, 8, 8),
+        Ok(())
+    );
+    assert_eq!(buffer[..31], CCM_PLAINTEXT[..31]);
+
+    // Tampering with the additional data is detected.
+    let mut buffer = CCM_CIPHERTEXT;
+    buffer[0] ^= 1;
+    assert_eq!(
+        Aes::ccm_decrypt(&CCM_KEY, &CCM_NONCE, &mut buffer, 8, 8),
+        Err(ErrorCode::Fail)
+    );
+
+    assert_eq!(
+        Aes::ccm_encrypt(&CCM_KEY, &CCM_NONCE, &mut [0; 8], 4, 8),
+        Err(ErrorCode::Size)
+    );
+    assert_eq!(
+        Aes::ccm_encrypt(&CCM_KEY, &CCM_NONCE, &mut buffer, 8, 6),
+        Err(ErrorCode::Invalid)
+    );
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Aes::new();
+    kernel.add_driver(&driver);
+    for buffer_num in [allow_ro::KEY, allow_ro::IV] {
+        kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
+            driver_num: DRIVER_NUM,
+            buffer_num,
+            return_error: None,
+        });
+    }
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRw {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_rw::DEST,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::DONE,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::SET_ALGORITHM,
+        argument0: Mode::Cbc as u32,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::NoSupport)),
+    });
+
+    let mut buffer = PLAINTEXT;
+    assert_eq!(
+        Aes::encrypt_in_place(Mode::Cbc, &KEY, &CBC_IV, &mut buffer),
+        Err(ErrorCode::NoSupport)
+    );
+    assert_eq!(buffer, PLAINTEXT);
+}
@@ -0,0 +1,113 @@
+#![no_std]
+
+use libtock_platform as platform;
+use libtock_platform::share::Handle;
+use libtock_platform::subscribe::{OneId, Subscribe};
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls, Upcall};
+
+/// The analog comparator driver.
+///
+/// Each channel compares the voltages on a pair of pins. A channel can be
+/// read at any time with `compare`, or watched with `start_comparing`, which
+/// calls the registered listener whenever the positive input rises above the
+/// negative one.
+///
+/// # Example
+/// ```ignore
+/// use libtock::analog_comparator::{AnalogComparator, AnalogComparatorListener};
+///
+/// let positive_higher = AnalogComparator::compare(0)?;
+///
+/// let listener = AnalogComparatorListener(|channel| {
+///     // the positive input of `channel` went above the negative one
+/// });
+/// share::scope(|subscribe| {
+///     AnalogComparator::register_listener(&listener, subscribe)?;
+///     AnalogComparator::start_comparing(0)?;
+///     loop {
+///         TockSyscalls::yield_wait();
+///     }
+/// });
+/// ```
+pub struct AnalogComparator<S: Syscalls, C: platform::subscribe::Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: platform::subscribe::Config> AnalogComparator<S, C> {
+    /// Run a check against the analog comparator capsule to ensure it is
+    /// present.
+    ///
+    /// Returns `Ok(number_of_channels)` if the driver was present. This does
+    /// not necessarily mean that the driver is working.
+    pub fn count() -> Result<u32, ErrorCode> {
+        S::command(DRIVER_NUM, command::COUNT, 0, 0).to_result()
+    }
+
+    /// Returns `true` if the positive input of `channel` is currently higher
+    /// than the negative one.
+    pub fn compare(channel: u32) -> Result<bool, ErrorCode> {
+        let result: u32 = S::command(DRIVER_NUM, command::COMPARE, channel, 0).to_result()?;
+        Ok(result != 0)
+    }
+
+    /// Starts calling the listener each time the positive input of `channel`
+    /// rises above the negative one.
+    pub fn start_comparing(channel: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::START_COMPARING, channel, 0).to_result()
+    }
+
+    pub fn stop_comparing(channel: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::STOP_COMPARING, channel, 0).to_result()
+    }
+
+    /// Registers the listener for comparator interrupts.
+    ///
+    /// There can be only one listener registered at a time. Each time this
+    /// function is used, it will replace the previously registered listener.
+    pub fn register_listener<'share, F: Fn(u32)>(
+        listener: &'share AnalogComparatorListener<F>,
+        subscribe: Handle<Subscribe<'share, S, DRIVER_NUM, 0>>,
+    ) -> Result<(), ErrorCode> {
+        S::subscribe::<_, _, C, DRIVER_NUM, 0>(subscribe, listener)
+    }
+
+    /// Unregister the listener for comparator interrupts.
+    pub fn unregister_listener() {
+        S::unsubscribe(DRIVER_NUM, subscribe::INTERRUPT)
+    }
+}
+
+/// A wrapper around a closure to be registered and called with the channel
+/// whose positive input rose above its negative one.
+///
+/// ```ignore
+/// let listener = AnalogComparatorListener(|channel| {
+///     // react to the crossing
+/// });
+/// ```
+pub struct AnalogComparatorListener<F: Fn(u32)>(pub F);
+
+impl<F: Fn(u32)> Upcall<OneId<DRIVER_NUM, 0>> for AnalogComparatorListener<F> {
+    fn upcall(&self, channel: u32, _arg1: u32, _arg2: u32) {
+        self.0(channel)
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x7;
+
+// Command IDs
+mod command {
+    pub const COUNT: u32 = 0;
+    pub const COMPARE: u32 = 1;
+    pub const START_COMPARING: u32 = 2;
+    pub const STOP_COMPARING: u32 = 3;
+}
+
+mod subscribe {
+    pub const INTERRUPT: u32 = 0;
+}
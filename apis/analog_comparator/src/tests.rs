@@ -0,0 +1,73 @@
+use super::*;
+use core::cell::Cell;
+use libtock_platform::{share, ErrorCode, YieldNoWaitReturn};
+use libtock_unittest::fake;
+
+type AnalogComparator = super::AnalogComparator<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(AnalogComparator::count(), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn count() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AnalogComparator::<2>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(AnalogComparator::count(), Ok(2));
+}
+
+#[test]
+fn compare() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AnalogComparator::<2>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(AnalogComparator::compare(0), Ok(false));
+    driver.set_output(0, true);
+    assert_eq!(AnalogComparator::compare(0), Ok(true));
+    assert_eq!(AnalogComparator::compare(2), Err(ErrorCode::Invalid));
+}
+
+#[test]
+fn interrupts() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AnalogComparator::<2>::new();
+    kernel.add_driver(&driver);
+    driver.queue_crossings(&[1]);
+
+    let crossings = Cell::new(0);
+    let last_channel = Cell::new(None);
+    let listener = AnalogComparatorListener(|channel| {
+        crossings.set(crossings.get() + 1);
+        last_channel.set(Some(channel));
+    });
+    share::scope(|subscribe| {
+        assert_eq!(
+            AnalogComparator::register_listener(&listener, subscribe),
+            Ok(())
+        );
+        assert_eq!(AnalogComparator::start_comparing(0), Ok(()));
+        assert!(driver.is_comparing(0));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        assert!(driver.cross(0));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(last_channel.get(), Some(0));
+
+        assert_eq!(AnalogComparator::start_comparing(1), Ok(()));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(last_channel.get(), Some(1));
+
+        assert_eq!(AnalogComparator::stop_comparing(0), Ok(()));
+        assert!(!driver.cross(0));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+    });
+    assert_eq!(crossings.get(), 2);
+    assert_eq!(
+        AnalogComparator::start_comparing(2),
+        Err(ErrorCode::Invalid)
+    );
+}
@@ -0,0 +1,202 @@
+#![no_std]
+
+use core::cell::{Cell, UnsafeCell};
+use libtock_platform as platform;
+use libtock_platform::allow_ro::AllowRo;
+use libtock_platform::share;
+use libtock_platform::subscribe::Subscribe;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+/// The app flash driver.
+///
+/// It allows a process to rewrite regions of its own flash, which are declared
+/// with [`app_flash_region!`]. Flash is written a whole page
+/// ([`PAGE_SIZE`] bytes) at a time.
+///
+/// # Example
+/// ```ignore
+/// use libtock::app_flash::{app_flash_region, AppFlash, PAGE_SIZE};
+///
+/// app_flash_region! {CONFIG, 2 * PAGE_SIZE}
+///
+/// let mut page = [0xff; PAGE_SIZE];
+/// page[..5].copy_from_slice(b"hello");
+/// AppFlash::write_page(&CONFIG, 1, &page)?;
+///
+/// let mut hello = [0; 5];
+/// CONFIG.read(PAGE_SIZE, &mut hello);
+/// ```
+pub struct AppFlash<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> AppFlash<S, C> {
+    /// Run a check against the app flash capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Replaces the contents of page `index` of `region` with `page`.
+    ///
+    /// Fails with `ErrorCode::Invalid` if `region` does not have that many
+    /// pages.
+    pub fn write_page<const SIZE: usize>(
+        region: &AppFlashRegion<SIZE>,
+        index: usize,
+        page: &[u8; PAGE_SIZE],
+    ) -> Result<(), ErrorCode> {
+        if index >= region.pages() {
+            return Err(ErrorCode::Invalid);
+        }
+        Self::write(region.address() + index * PAGE_SIZE, page)
+    }
+
+    /// Replaces the page of flash at `address` with `page`. `address` must be
+    /// page-aligned and inside one of the process' writeable flash regions.
+    ///
+    /// Prefer `write_page`, which computes the address from a region declared
+    /// with `app_flash_region!`.
+    pub fn write(address: usize, page: &[u8; PAGE_SIZE]) -> Result<(), ErrorCode> {
+        let address: u32 = address.try_into().map_err(|_| ErrorCode::Invalid)?;
+        if address as usize % PAGE_SIZE != 0 {
+            return Err(ErrorCode::Invalid);
+        }
+        let called = Cell::new(Option::<(u32,)>::None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, { allow_ro::BUFFER }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::WRITE_DONE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<C, DRIVER_NUM, { allow_ro::BUFFER }>(allow_ro, page)?;
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::WRITE_DONE }>(subscribe, &called)?;
+
+            // When this fails, `called` is guaranteed unmodified,
+            // because upcalls are never processed until we call `yield`.
+            S::command(DRIVER_NUM, command::WRITE, address, 0).to_result::<(), ErrorCode>()?;
+
+            loop {
+                S::yield_wait();
+                if let Some((status,)) = called.get() {
+                    return match status {
+                        0 => Ok(()),
+                        e_status => Err(e_status.try_into().unwrap_or(ErrorCode::Fail)),
+                    };
+                }
+            }
+        })
+    }
+}
+
+/// The size, in bytes, of a page of app flash.
+pub const PAGE_SIZE: usize = 512;
+
+/// A page-aligned region of the process' flash, `SIZE` bytes long, that can be
+/// rewritten with `AppFlash`. Regions are declared with [`app_flash_region!`],
+/// which places them in a writeable flash region, and start out erased (all
+/// `0xff`).
+#[repr(C, align(512))]
+pub struct AppFlashRegion<const SIZE: usize> {
+    // The kernel modifies the region's contents, so it must not be treated as
+    // immutable.
+    data: UnsafeCell<[u8; SIZE]>,
+}
+
+// Safety: the region is only modified by the kernel, in response to a
+// `AppFlash::write`, and is only read by volatile reads.
+unsafe impl<const SIZE: usize> Sync for AppFlashRegion<SIZE> {}
+
+impl<const SIZE: usize> AppFlashRegion<SIZE> {
+    /// Creates an erased region. Regions should be declared with
+    /// `app_flash_region!` rather than constructed directly.
+    ///
+    /// Panics (at compile time, when used in a `static`) if `SIZE` is not a
+    /// multiple of `PAGE_SIZE`.
+    pub const fn new() -> AppFlashRegion<SIZE> {
+        assert!(
+            SIZE % PAGE_SIZE == 0,
+            "app flash regions must be whole pages"
+        );
+        AppFlashRegion {
+            data: UnsafeCell::new([0xff; SIZE]),
+        }
+    }
+
+    /// Returns the address of the start of the region.
+    pub fn address(&self) -> usize {
+        self.data.get() as usize
+    }
+
+    /// Returns the size of the region, in bytes.
+    pub const fn size(&self) -> usize {
+        SIZE
+    }
+
+    /// Returns the number of pages in the region.
+    pub const fn pages(&self) -> usize {
+        SIZE / PAGE_SIZE
+    }
+
+    /// Fills `buf` with the contents of the region starting at `offset`.
+    ///
+    /// Panics if the read extends past the end of the region.
+    pub fn read(&self, offset: usize, buf: &mut [u8]) {
+        assert!(offset <= SIZE && buf.len() <= SIZE - offset);
+        let start = self.data.get() as *const u8;
+        for (i, byte) in buf.iter_mut().enumerate() {
+            // Safety: the assertion above keeps the read within the region. A
+            // volatile read is used because the kernel may have rewritten the
+            // region since it was last read.
+            *byte = unsafe { core::ptr::read_volatile(start.add(offset + i)) };
+        }
+    }
+}
+
+/// Declares a `static` [`AppFlashRegion`] named `$name` that is `$size` bytes
+/// long, placed in a writeable flash region. `$size` must be a multiple of
+/// [`PAGE_SIZE`]. Example:
+/// ```ignore
+/// app_flash_region! {LOG, 4 * PAGE_SIZE}
+/// ```
+// The .wfr section is placed in flash by libtock_layout.ld, and elf2tab
+// declares it as a writeable flash region in the TBF headers.
+#[macro_export]
+macro_rules! app_flash_region {
+    {$name:ident, $size:expr} => {
+        #[link_section = ".wfr.app_flash"]
+        static $name: $crate::AppFlashRegion<{ $size }> = $crate::AppFlashRegion::new();
+    }
+}
+
+/// System call configuration trait for `AppFlash`.
+pub trait Config: platform::allow_ro::Config + platform::subscribe::Config {}
+impl<T: platform::allow_ro::Config + platform::subscribe::Config> Config for T {}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x50000;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const WRITE: u32 = 1;
+}
+
+mod subscribe {
+    pub const WRITE_DONE: u32 = 0;
+}
+
+mod allow_ro {
+    pub const BUFFER: u32 = 0;
+}
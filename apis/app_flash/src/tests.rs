@@ -0,0 +1,95 @@
+use super::*;
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type AppFlash = super::AppFlash<fake::Syscalls>;
+
+const BASE: usize = 0x40000;
+
+app_flash_region! {REGION, 2 * PAGE_SIZE}
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!AppFlash::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AppFlash::new(BASE as u32, 2);
+    kernel.add_driver(&driver);
+
+    assert!(AppFlash::driver_check());
+}
+
+#[test]
+fn region() {
+    assert_eq!(REGION.size(), 1024);
+    assert_eq!(REGION.pages(), 2);
+    assert_eq!(REGION.address() % PAGE_SIZE, 0);
+
+    let mut buf = [0; 4];
+    REGION.read(1020, &mut buf);
+    assert_eq!(buf, [0xff; 4]);
+}
+
+#[test]
+#[should_panic]
+fn region_read_out_of_bounds() {
+    REGION.read(1021, &mut [0; 4]);
+}
+
+#[test]
+fn write() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AppFlash::new(BASE as u32, 2);
+    kernel.add_driver(&driver);
+
+    let mut page = [0xff; PAGE_SIZE];
+    page[..4].copy_from_slice(b"tock");
+    assert_eq!(AppFlash::write(BASE + PAGE_SIZE, &page), Ok(()));
+    assert_eq!(driver.contents()[PAGE_SIZE..PAGE_SIZE + 5], *b"tock\xff");
+    assert_eq!(driver.erase_count(1), 1);
+
+    assert_eq!(AppFlash::write(BASE + 4, &page), Err(ErrorCode::Invalid));
+    assert_eq!(
+        AppFlash::write(BASE + 2 * PAGE_SIZE, &page),
+        Err(ErrorCode::Invalid)
+    );
+}
+
+#[test]
+fn write_page_out_of_range() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(
+        AppFlash::write_page(&REGION, 2, &[0; PAGE_SIZE]),
+        Err(ErrorCode::Invalid)
+    );
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AppFlash::new(BASE as u32, 2);
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_ro::BUFFER,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::WRITE_DONE,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::WRITE,
+        argument0: BASE as u32,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+
+    assert_eq!(AppFlash::write(BASE, &[0; PAGE_SIZE]), Err(ErrorCode::Busy));
+}
@@ -0,0 +1,213 @@
+//! Building and parsing advertising data, which is a sequence of AD
+//! structures: a length byte, an AD type byte, and `length - 1` bytes of data.
+
+/// The maximum length of legacy advertising data, in bytes.
+pub const MAX_ADVERTISING_DATA_LEN: usize = 31;
+
+/// Bits of the flags AD structure.
+pub mod flags {
+    pub const LE_LIMITED_DISCOVERABLE: u8 = 0x01;
+    pub const LE_GENERAL_DISCOVERABLE: u8 = 0x02;
+    pub const BR_EDR_NOT_SUPPORTED: u8 = 0x04;
+}
+
+/// AD types, as assigned by the Bluetooth SIG.
+pub mod ad_type {
+    pub const FLAGS: u8 = 0x01;
+    pub const INCOMPLETE_SERVICE_UUIDS_16: u8 = 0x02;
+    pub const COMPLETE_SERVICE_UUIDS_16: u8 = 0x03;
+    pub const SHORT_LOCAL_NAME: u8 = 0x08;
+    pub const COMPLETE_LOCAL_NAME: u8 = 0x09;
+    pub const TX_POWER_LEVEL: u8 = 0x0a;
+    pub const SERVICE_DATA_16: u8 = 0x16;
+    pub const MANUFACTURER_SPECIFIC_DATA: u8 = 0xff;
+}
+
+/// Advertising data, built by an [`AdvertisingDataBuilder`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct AdvertisingData {
+    data: [u8; MAX_ADVERTISING_DATA_LEN],
+    len: usize,
+}
+
+impl AdvertisingData {
+    pub fn builder() -> AdvertisingDataBuilder {
+        AdvertisingDataBuilder {
+            data: AdvertisingData {
+                data: [0; MAX_ADVERTISING_DATA_LEN],
+                len: 0,
+            },
+            overflowed: false,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+
+    /// Returns the AD structures, in order.
+    pub fn structures(&self) -> AdStructures<'_> {
+        AdStructures(self.as_bytes())
+    }
+}
+
+/// Builds [`AdvertisingData`] one AD structure at a time.
+///
+/// # Example
+/// ```ignore
+/// let data = AdvertisingData::builder()
+///     .flags(flags::LE_GENERAL_DISCOVERABLE | flags::BR_EDR_NOT_SUPPORTED)
+///     .complete_local_name("tock")
+///     .manufacturer_data(0xffff, &[1, 2, 3])
+///     .build()?;
+/// ```
+pub struct AdvertisingDataBuilder {
+    data: AdvertisingData,
+    // Set when a structure did not fit.
+    overflowed: bool,
+}
+
+impl AdvertisingDataBuilder {
+    pub fn flags(self, flags: u8) -> Self {
+        self.structure(ad_type::FLAGS, &[&[flags]])
+    }
+
+    pub fn complete_local_name(self, name: &str) -> Self {
+        self.structure(ad_type::COMPLETE_LOCAL_NAME, &[name.as_bytes()])
+    }
+
+    pub fn short_local_name(self, name: &str) -> Self {
+        self.structure(ad_type::SHORT_LOCAL_NAME, &[name.as_bytes()])
+    }
+
+    /// Lists 16-bit service UUIDs. Set `complete` if these are all the services
+    /// the device offers.
+    pub fn service_uuids_16(mut self, uuids: &[u16], complete: bool) -> Self {
+        let ad_type = match complete {
+            true => ad_type::COMPLETE_SERVICE_UUIDS_16,
+            false => ad_type::INCOMPLETE_SERVICE_UUIDS_16,
+        };
+        let start = self.data.len;
+        self = self.structure(ad_type, &[]);
+        for uuid in uuids {
+            self.extend(start, &uuid.to_le_bytes());
+        }
+        self
+    }
+
+    /// Sets the transmit power level, in dBm.
+    pub fn tx_power_level(self, dbm: i8) -> Self {
+        self.structure(ad_type::TX_POWER_LEVEL, &[&[dbm as u8]])
+    }
+
+    pub fn service_data_16(self, uuid: u16, data: &[u8]) -> Self {
+        self.structure(ad_type::SERVICE_DATA_16, &[&uuid.to_le_bytes(), data])
+    }
+
+    /// Adds manufacturer-specific data, for the company with the given
+    /// Bluetooth SIG company identifier.
+    pub fn manufacturer_data(self, company_id: u16, data: &[u8]) -> Self {
+        self.structure(
+            ad_type::MANUFACTURER_SPECIFIC_DATA,
+            &[&company_id.to_le_bytes(), data],
+        )
+    }
+
+    /// Adds an AD structure of any type.
+    pub fn raw(self, ad_type: u8, data: &[u8]) -> Self {
+        self.structure(ad_type, &[data])
+    }
+
+    /// Returns the advertising data, or `ErrorCode::Size` if it did not fit in
+    /// `MAX_ADVERTISING_DATA_LEN` bytes.
+    pub fn build(self) -> Result<AdvertisingData, libtock_platform::ErrorCode> {
+        match self.overflowed {
+            true => Err(libtock_platform::ErrorCode::Size),
+            false => Ok(self.data),
+        }
+    }
+
+    // Appends a structure of type `ad_type`, whose data is the concatenation
+    // of `parts`.
+    fn structure(mut self, ad_type: u8, parts: &[&[u8]]) -> Self {
+        let start = self.data.len;
+        if !self.push(&[1, ad_type]) {
+            return self;
+        }
+        for part in parts {
+            self.extend(start, part);
+        }
+        self
+    }
+
+    // Appends `bytes` to the structure starting at `start`, updating its length.
+    fn extend(&mut self, start: usize, bytes: &[u8]) {
+        if self.push(bytes) {
+            self.data.data[start] += bytes.len() as u8;
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) -> bool {
+        let end = self.data.len + bytes.len();
+        if self.overflowed || end > MAX_ADVERTISING_DATA_LEN {
+            self.overflowed = true;
+            return false;
+        }
+        self.data.data[self.data.len..end].copy_from_slice(bytes);
+        self.data.len = end;
+        true
+    }
+}
+
+/// An AD structure.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AdStructure<'a> {
+    Flags(u8),
+    ShortLocalName(&'a [u8]),
+    CompleteLocalName(&'a [u8]),
+    TxPowerLevel(i8),
+    ManufacturerData {
+        company_id: u16,
+        data: &'a [u8],
+    },
+    /// Any other structure, including malformed ones of the types above.
+    Other {
+        ad_type: u8,
+        data: &'a [u8],
+    },
+}
+
+/// An iterator over the AD structures of advertising data. It stops at the
+/// first truncated structure.
+#[derive(Clone)]
+pub struct AdStructures<'a>(pub &'a [u8]);
+
+impl<'a> Iterator for AdStructures<'a> {
+    type Item = AdStructure<'a>;
+
+    fn next(&mut self) -> Option<AdStructure<'a>> {
+        let (&len, rest) = self.0.split_first()?;
+        let len = len as usize;
+        if len == 0 || len > rest.len() {
+            // Zero-length structures mark the end of significant data.
+            self.0 = &[];
+            return None;
+        }
+        let (structure, rest) = rest.split_at(len);
+        self.0 = rest;
+        let (ad_type, data) = (structure[0], &structure[1..]);
+        Some(match (ad_type, data) {
+            (ad_type::FLAGS, &[flags]) => AdStructure::Flags(flags),
+            (ad_type::SHORT_LOCAL_NAME, name) => AdStructure::ShortLocalName(name),
+            (ad_type::COMPLETE_LOCAL_NAME, name) => AdStructure::CompleteLocalName(name),
+            (ad_type::TX_POWER_LEVEL, &[dbm]) => AdStructure::TxPowerLevel(dbm as i8),
+            (ad_type::MANUFACTURER_SPECIFIC_DATA, &[low, high, ref data @ ..]) => {
+                AdStructure::ManufacturerData {
+                    company_id: u16::from_le_bytes([low, high]),
+                    data,
+                }
+            }
+            _ => AdStructure::Other { ad_type, data },
+        })
+    }
+}
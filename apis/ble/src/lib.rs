@@ -0,0 +1,235 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform as platform;
+use libtock_platform::allow_ro::AllowRo;
+use libtock_platform::allow_rw::AllowRw;
+use libtock_platform::share::{self, Handle};
+use libtock_platform::subscribe::Subscribe;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+mod advertising_data;
+
+pub use advertising_data::{
+    ad_type, flags, AdStructure, AdStructures, AdvertisingData, AdvertisingDataBuilder,
+    MAX_ADVERTISING_DATA_LEN,
+};
+
+/// The BLE advertising driver.
+///
+/// It broadcasts advertisements periodically, and passively scans for the
+/// advertisements of other devices. It does not support connections.
+///
+/// # Example
+/// ```ignore
+/// use libtock::ble::{flags, AdvertisingData, AdvertisingType, Ble, SCAN_BUFFER_LEN};
+///
+/// let data = AdvertisingData::builder()
+///     .flags(flags::LE_GENERAL_DISCOVERABLE | flags::BR_EDR_NOT_SUPPORTED)
+///     .complete_local_name("tock")
+///     .build()?;
+/// share::scope(|allow_ro| {
+///     Ble::start_advertising(&data, allow_ro, AdvertisingType::NonConnectable, 300)?;
+///     // The kernel advertises until stop_advertising is called or the
+///     // scope ends.
+/// });
+///
+/// let mut buffer = [0; SCAN_BUFFER_LEN];
+/// Ble::passive_scan(&mut buffer, |report| {
+///     // make use of report.address and report.structures()
+///     true // keep scanning
+/// })?;
+/// ```
+pub struct Ble<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> Ble<S, C> {
+    /// Run a check against the BLE advertising capsule to ensure it is
+    /// present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Starts advertising `data` every `interval_ms` milliseconds (from 20 to
+    /// 10240). The kernel reads `data` for each advertisement, so it stays
+    /// shared until advertising stops.
+    pub fn start_advertising<'share>(
+        data: &'share AdvertisingData,
+        allow_ro: Handle<AllowRo<'share, S, DRIVER_NUM, { allow_ro::ADVERTISING_DATA }>>,
+        advertising_type: AdvertisingType,
+        interval_ms: u32,
+    ) -> Result<(), ErrorCode> {
+        S::allow_ro::<C, DRIVER_NUM, { allow_ro::ADVERTISING_DATA }>(allow_ro, data.as_bytes())?;
+        S::command(
+            DRIVER_NUM,
+            command::START_ADVERTISING,
+            advertising_type as u32,
+            interval_ms,
+        )
+        .to_result()
+    }
+
+    /// Stops advertising, or scanning.
+    pub fn stop() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::STOP, 0, 0).to_result()
+    }
+
+    /// Sets the transmit power, in dBm.
+    pub fn set_tx_power(dbm: i8) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::SET_TX_POWER, dbm as u32, 0).to_result()
+    }
+
+    /// Scans for advertisements, passing each one received to `listener`
+    /// until it returns `false`.
+    ///
+    /// `buffer` is only shared with the kernel while waiting for an
+    /// advertisement, so advertisements received while `listener` runs are
+    /// lost.
+    pub fn passive_scan<F: FnMut(&ScanReport) -> bool>(
+        buffer: &mut [u8; SCAN_BUFFER_LEN],
+        mut listener: F,
+    ) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::PASSIVE_SCAN, 0, 0).to_result::<(), ErrorCode>()?;
+        let result = loop {
+            let len = match Self::wait_for_advertisement(buffer) {
+                Ok(len) => len,
+                Err(error) => break Err(error),
+            };
+            if let Some(report) = ScanReport::parse(&buffer[..len.min(SCAN_BUFFER_LEN)]) {
+                if !listener(&report) {
+                    break Ok(());
+                }
+            }
+        };
+        // A scan error is more useful than an error stopping the scan.
+        let stopped = Self::stop();
+        result.and(stopped)
+    }
+}
+
+/// The kind of advertisements sent, which tells scanners how they may
+/// respond.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AdvertisingType {
+    /// Connectable and scannable (`ADV_IND`).
+    ConnectableUndirected = 0x00,
+    /// Connectable by a single known device (`ADV_DIRECT_IND`).
+    ConnectableDirected = 0x01,
+    /// Neither connectable nor scannable (`ADV_NONCONN_IND`).
+    NonConnectable = 0x02,
+    /// Scannable but not connectable (`ADV_SCAN_IND`).
+    Scannable = 0x06,
+}
+
+/// The size of the buffer `Ble::passive_scan` receives advertisements into: a
+/// PDU header and length, the advertiser's address, and the advertising
+/// data.
+pub const SCAN_BUFFER_LEN: usize = 2 + 6 + MAX_ADVERTISING_DATA_LEN;
+
+/// An advertisement received while scanning.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ScanReport<'a> {
+    /// The PDU type of the advertisement, such as `AdvertisingType as u8`.
+    pub pdu_type: u8,
+    /// The advertiser's address, least significant byte first.
+    pub address: [u8; 6],
+    pub data: &'a [u8],
+}
+
+impl<'a> ScanReport<'a> {
+    /// Returns the AD structures of the advertising data.
+    pub fn structures(&self) -> AdStructures<'a> {
+        AdStructures(self.data)
+    }
+
+    // Parses a received PDU, returning `None` if it is truncated.
+    fn parse(pdu: &'a [u8]) -> Option<ScanReport<'a>> {
+        let (&header, rest) = pdu.split_first()?;
+        let (&len, payload) = rest.split_first()?;
+        let payload = payload.get(..len as usize)?;
+        let (address, data) = (payload.get(..6)?, &payload[6..]);
+        Some(ScanReport {
+            pdu_type: header & 0x0f,
+            address: address.try_into().ok()?,
+            data,
+        })
+    }
+}
+
+/// System call configuration trait for `Ble`.
+pub trait Config:
+    platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config
+{
+}
+impl<T: platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config>
+    Config for T
+{
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls, C: Config> Ble<S, C> {
+    // Shares `buffer` until an advertisement is received into it, and returns
+    // its length.
+    fn wait_for_advertisement(buffer: &mut [u8]) -> Result<usize, ErrorCode> {
+        let called = Cell::new(Option::<(u32, u32)>::None);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, { allow_rw::SCAN }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::SCAN }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::SCAN }>(subscribe, &called)?;
+            S::allow_rw::<C, DRIVER_NUM, { allow_rw::SCAN }>(allow_rw, buffer)?;
+
+            loop {
+                S::yield_wait();
+                if let Some((status, len)) = called.get() {
+                    return match status {
+                        0 => Ok(len as usize),
+                        e_status => Err(e_status.try_into().unwrap_or(ErrorCode::Fail)),
+                    };
+                }
+            }
+        })
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x30000;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const START_ADVERTISING: u32 = 1;
+    pub const STOP: u32 = 2;
+    pub const SET_TX_POWER: u32 = 3;
+    pub const PASSIVE_SCAN: u32 = 5;
+}
+
+mod subscribe {
+    pub const SCAN: u32 = 0;
+}
+
+mod allow_ro {
+    pub const ADVERTISING_DATA: u32 = 0;
+}
+
+mod allow_rw {
+    pub const SCAN: u32 = 0;
+}
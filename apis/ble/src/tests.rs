@@ -0,0 +1,248 @@
+use super::*;
+use libtock_platform::{share, ErrorCode};
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type Ble = super::Ble<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Ble::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ble::new();
+    kernel.add_driver(&driver);
+
+    assert!(Ble::driver_check());
+}
+
+#[test]
+fn build_advertising_data() {
+    let data = AdvertisingData::builder()
+        .flags(flags::LE_GENERAL_DISCOVERABLE | flags::BR_EDR_NOT_SUPPORTED)
+        .complete_local_name("tock")
+        .service_uuids_16(&[0x180f, 0x181a], true)
+        .tx_power_level(-4)
+        .manufacturer_data(0xffff, &[1, 2])
+        .build()
+        .unwrap();
+    assert_eq!(
+        data.as_bytes(),
+        [
+            2, 0x01, 0x06, // flags
+            5, 0x09, b't', b'o', b'c', b'k', // name
+            5, 0x03, 0x0f, 0x18, 0x1a, 0x18, // UUIDs
+            2, 0x0a, 0xfc, // TX power
+            5, 0xff, 0xff, 0xff, 1, 2, // manufacturer data
+        ]
+    );
+
+    let mut structures = data.structures();
+    assert_eq!(structures.next(), Some(AdStructure::Flags(0x06)));
+    assert_eq!(
+        structures.next(),
+        Some(AdStructure::CompleteLocalName(b"tock"))
+    );
+    assert_eq!(
+        structures.next(),
+        Some(AdStructure::Other {
+            ad_type: ad_type::COMPLETE_SERVICE_UUIDS_16,
+            data: &[0x0f, 0x18, 0x1a, 0x18]
+        })
+    );
+    assert_eq!(structures.next(), Some(AdStructure::TxPowerLevel(-4)));
+    assert_eq!(
+        structures.next(),
+        Some(AdStructure::ManufacturerData {
+            company_id: 0xffff,
+            data: &[1, 2]
+        })
+    );
+    assert_eq!(structures.next(), None);
+}
+
+#[test]
+fn advertising_data_overflow() {
+    let name = "a name that is far too long for one advertisement";
+    assert_eq!(
+        AdvertisingData::builder().complete_local_name(name).build(),
+        Err(ErrorCode::Size)
+    );
+    // The maximum length fits.
+    let data = AdvertisingData::builder()
+        .raw(0xff, &[0; MAX_ADVERTISING_DATA_LEN - 2])
+        .build()
+        .unwrap();
+    assert_eq!(data.as_bytes().len(), MAX_ADVERTISING_DATA_LEN);
+}
+
+#[test]
+fn truncated_structures() {
+    let mut structures = AdStructures(&[2, 0x01, 0x06, 4, 0x09, b'a']);
+    assert_eq!(structures.next(), Some(AdStructure::Flags(0x06)));
+    assert_eq!(structures.next(), None);
+    assert_eq!(AdStructures(&[0, 2, 0x01, 0x06]).next(), None);
+}
+
+#[test]
+fn advertise() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ble::new();
+    kernel.add_driver(&driver);
+    let data = AdvertisingData::builder()
+        .flags(flags::LE_GENERAL_DISCOVERABLE)
+        .short_local_name("tk")
+        .build()
+        .unwrap();
+
+    assert_eq!(Ble::set_tx_power(-8), Ok(()));
+    assert_eq!(driver.tx_power(), -8);
+    share::scope(|allow_ro| {
+        assert_eq!(
+            Ble::start_advertising(&data, allow_ro, AdvertisingType::NonConnectable, 10),
+            Err(ErrorCode::Invalid)
+        );
+    });
+    share::scope(|allow_ro| {
+        assert_eq!(
+            Ble::start_advertising(&data, allow_ro, AdvertisingType::NonConnectable, 300),
+            Ok(())
+        );
+        assert_eq!(
+            driver.advertising(),
+            Some(fake::Advertising {
+                pdu_type: 0x02,
+                interval_ms: 300
+            })
+        );
+        assert_eq!(driver.advertising_data(), data.as_bytes());
+        assert_eq!(Ble::stop(), Ok(()));
+    });
+    assert_eq!(driver.advertising(), None);
+}
+
+#[test]
+fn passive_scan() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ble::new();
+    kernel.add_driver(&driver);
+    driver.inject_advertisement(0x00, [1, 2, 3, 4, 5, 6], &[2, 0x01, 0x06]);
+    driver.inject_advertisement(0x02, [6, 5, 4, 3, 2, 1], &[3, 0x09, b'h', b'i']);
+    driver.inject_advertisement(0x02, [0; 6], &[]);
+
+    let mut buffer = [0; SCAN_BUFFER_LEN];
+    let mut reports = 0;
+    assert_eq!(
+        Ble::passive_scan(&mut buffer, |report| {
+            reports += 1;
+            match reports {
+                1 => {
+                    assert_eq!(report.pdu_type, 0x00);
+                    assert_eq!(report.address, [1, 2, 3, 4, 5, 6]);
+                    assert_eq!(report.structures().next(), Some(AdStructure::Flags(0x06)));
+                    true
+                }
+                _ => {
+                    assert_eq!(report.address, [6, 5, 4, 3, 2, 1]);
+                    let mut structures = report.structures();
+                    assert_eq!(
+                        structures.next(),
+                        Some(AdStructure::CompleteLocalName(b"hi"))
+                    );
+                    assert_eq!(structures.next(), None);
+                    false
+                }
+            }
+        }),
+        Ok(())
+    );
+    assert_eq!(reports, 2);
+    assert!(!driver.is_scanning());
+}
+
+// When both the scan and stopping it fail, the scan error is returned.
+#[test]
+fn passive_scan_error() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ble::new();
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::PASSIVE_SCAN,
+        argument0: 0,
+        argument1: 0,
+        override_return: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::SCAN,
+        skip_with_error: Some(ErrorCode::NoMem),
+    });
+    // Leaving the share scope unshares the buffer and unsubscribes.
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRw {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_rw::SCAN,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::SCAN,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::STOP,
+        argument0: 0,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::Fail)),
+    });
+
+    let mut buffer = [0; SCAN_BUFFER_LEN];
+    assert_eq!(
+        Ble::passive_scan(&mut buffer, |_| true),
+        Err(ErrorCode::NoMem)
+    );
+}
+
+#[test]
+fn parse_scan_report() {
+    assert_eq!(ScanReport::parse(&[0x42, 6, 1, 2, 3, 4, 5]), None);
+    assert_eq!(
+        ScanReport::parse(&[0x42, 7, 1, 2, 3, 4, 5, 6, 7, 0xaa]),
+        Some(ScanReport {
+            pdu_type: 0x02,
+            address: [1, 2, 3, 4, 5, 6],
+            data: &[7],
+        })
+    );
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ble::new();
+    kernel.add_driver(&driver);
+    let data = AdvertisingData::builder().flags(0x06).build().unwrap();
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_ro::ADVERTISING_DATA,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::START_ADVERTISING,
+        argument0: AdvertisingType::Scannable as u32,
+        argument1: 100,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+
+    share::scope(|allow_ro| {
+        assert_eq!(
+            Ble::start_advertising(&data, allow_ro, AdvertisingType::Scannable, 100),
+            Err(ErrorCode::Busy)
+        );
+    });
+}
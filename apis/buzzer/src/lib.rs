@@ -0,0 +1,138 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_alarm::{Alarm, Milliseconds};
+use libtock_platform as platform;
+use libtock_platform::share;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+pub mod pitch;
+
+/// The buzzer driver.
+///
+/// It plays square-wave tones of a given frequency for a given duration. The
+/// kernel stops each tone on its own once its duration has elapsed.
+///
+/// # Example
+/// ```ignore
+/// use libtock::buzzer::{pitch, Buzzer, Note};
+///
+/// // Play an A4 for half a second, waiting until it is over.
+/// Buzzer::tone_sync(pitch::A4, 500)?;
+///
+/// // Play a short melody.
+/// Buzzer::play_melody(&[
+///     Note::new(pitch::C4, 250),
+///     Note::new(pitch::E4, 250),
+///     Note::rest(125),
+///     Note::new(pitch::G4, 500),
+/// ])?;
+/// ```
+pub struct Buzzer<S: Syscalls, C: platform::subscribe::Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: platform::subscribe::Config> Buzzer<S, C> {
+    /// Run a check against the buzzer capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Starts playing a tone of `frequency_hz` for `duration_ms` and returns
+    /// immediately. Fails with `ErrorCode::Busy` if a tone is already
+    /// playing.
+    pub fn tone(frequency_hz: u32, duration_ms: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::TONE, frequency_hz, duration_ms).to_result()
+    }
+
+    /// Plays a tone of `frequency_hz` for `duration_ms`, and waits until the
+    /// kernel reports that it has finished.
+    pub fn tone_sync(frequency_hz: u32, duration_ms: u32) -> Result<(), ErrorCode> {
+        let called = Cell::new(false);
+        share::scope(|subscribe| {
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::DONE }>(subscribe, &called)?;
+
+            // When this fails, `called` is guaranteed unmodified,
+            // because upcalls are never processed until we call `yield`.
+            Self::tone(frequency_hz, duration_ms)?;
+
+            while !called.get() {
+                S::yield_wait();
+            }
+            Ok(())
+        })
+    }
+
+    /// Stops the tone currently playing, if any.
+    pub fn stop() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::STOP, 0, 0).to_result()
+    }
+
+    /// Plays the notes of `melody` one after another, waiting for each to
+    /// finish before starting the next. Rests are timed with the alarm driver.
+    ///
+    /// Notes are played back to back; put short rests between them to
+    /// separate repeated notes.
+    pub fn play_melody(melody: &[Note]) -> Result<(), ErrorCode> {
+        for note in melody {
+            if note.is_rest() {
+                Alarm::<S, C>::sleep_for(Milliseconds(note.duration_ms))?;
+            } else {
+                Self::tone_sync(note.frequency_hz, note.duration_ms)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A single step of a melody: either a tone or, if `frequency_hz` is 0, a rest.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Note {
+    pub frequency_hz: u32,
+    pub duration_ms: u32,
+}
+
+impl Note {
+    /// A tone of `frequency_hz` (see the `pitch` module) for `duration_ms`.
+    pub const fn new(frequency_hz: u32, duration_ms: u32) -> Note {
+        Note {
+            frequency_hz,
+            duration_ms,
+        }
+    }
+
+    /// Silence for `duration_ms`.
+    pub const fn rest(duration_ms: u32) -> Note {
+        Note {
+            frequency_hz: 0,
+            duration_ms,
+        }
+    }
+
+    pub const fn is_rest(&self) -> bool {
+        self.frequency_hz == 0
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x90000;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const TONE: u32 = 1;
+    pub const STOP: u32 = 2;
+}
+
+mod subscribe {
+    pub const DONE: u32 = 0;
+}
@@ -0,0 +1,64 @@
+//! Frequencies, in Hz, of the notes of the equal-tempered scale (A4 = 440 Hz),
+//! rounded to the nearest integer. Sharps are named with an `S` suffix on the
+//! note letter, so `CS4` is C♯4 (middle C sharp).
+
+pub const C3: u32 = 131;
+pub const CS3: u32 = 139;
+pub const D3: u32 = 147;
+pub const DS3: u32 = 156;
+pub const E3: u32 = 165;
+pub const F3: u32 = 175;
+pub const FS3: u32 = 185;
+pub const G3: u32 = 196;
+pub const GS3: u32 = 208;
+pub const A3: u32 = 220;
+pub const AS3: u32 = 233;
+pub const B3: u32 = 247;
+pub const C4: u32 = 262;
+pub const CS4: u32 = 277;
+pub const D4: u32 = 294;
+pub const DS4: u32 = 311;
+pub const E4: u32 = 330;
+pub const F4: u32 = 349;
+pub const FS4: u32 = 370;
+pub const G4: u32 = 392;
+pub const GS4: u32 = 415;
+pub const A4: u32 = 440;
+pub const AS4: u32 = 466;
+pub const B4: u32 = 494;
+pub const C5: u32 = 523;
+pub const CS5: u32 = 554;
+pub const D5: u32 = 587;
+pub const DS5: u32 = 622;
+pub const E5: u32 = 659;
+pub const F5: u32 = 698;
+pub const FS5: u32 = 740;
+pub const G5: u32 = 784;
+pub const GS5: u32 = 831;
+pub const A5: u32 = 880;
+pub const AS5: u32 = 932;
+pub const B5: u32 = 988;
+pub const C6: u32 = 1047;
+pub const CS6: u32 = 1109;
+pub const D6: u32 = 1175;
+pub const DS6: u32 = 1245;
+pub const E6: u32 = 1319;
+pub const F6: u32 = 1397;
+pub const FS6: u32 = 1480;
+pub const G6: u32 = 1568;
+pub const GS6: u32 = 1661;
+pub const A6: u32 = 1760;
+pub const AS6: u32 = 1865;
+pub const B6: u32 = 1976;
+pub const C7: u32 = 2093;
+pub const CS7: u32 = 2217;
+pub const D7: u32 = 2349;
+pub const DS7: u32 = 2489;
+pub const E7: u32 = 2637;
+pub const F7: u32 = 2794;
+pub const FS7: u32 = 2960;
+pub const G7: u32 = 3136;
+pub const GS7: u32 = 3322;
+pub const A7: u32 = 3520;
+pub const AS7: u32 = 3729;
+pub const B7: u32 = 3951;
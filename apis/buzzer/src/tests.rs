@@ -0,0 +1,102 @@
+use super::*;
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type Buzzer = super::Buzzer<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Buzzer::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Buzzer::new();
+    kernel.add_driver(&driver);
+
+    assert!(Buzzer::driver_check());
+}
+
+#[test]
+fn tone() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Buzzer::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Buzzer::tone(pitch::A4, 100), Ok(()));
+    assert_eq!(Buzzer::stop(), Ok(()));
+    assert_eq!(Buzzer::tone_sync(pitch::C5, 250), Ok(()));
+    assert_eq!(Buzzer::tone_sync(0, 250), Err(ErrorCode::Invalid));
+    assert_eq!(
+        driver.take_tones(),
+        [
+            fake::Tone {
+                frequency_hz: 440,
+                duration_ms: 100
+            },
+            fake::Tone {
+                frequency_hz: 523,
+                duration_ms: 250
+            },
+        ]
+    );
+    assert_eq!(driver.stops(), 1);
+}
+
+#[test]
+fn play_melody() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Buzzer::new();
+    kernel.add_driver(&driver);
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+
+    let melody = [
+        Note::new(pitch::C4, 250),
+        Note::new(pitch::E4, 250),
+        Note::rest(125),
+        Note::new(pitch::G4, 500),
+    ];
+    assert!(melody[2].is_rest());
+    assert_eq!(Buzzer::play_melody(&melody), Ok(()));
+    assert_eq!(
+        driver.take_tones(),
+        [
+            fake::Tone {
+                frequency_hz: 262,
+                duration_ms: 250
+            },
+            fake::Tone {
+                frequency_hz: 330,
+                duration_ms: 250
+            },
+            fake::Tone {
+                frequency_hz: 392,
+                duration_ms: 500
+            },
+        ]
+    );
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Buzzer::new();
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::DONE,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::TONE,
+        argument0: pitch::A4,
+        argument1: 100,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+
+    assert_eq!(Buzzer::tone_sync(pitch::A4, 100), Err(ErrorCode::Busy));
+}
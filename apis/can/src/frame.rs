@@ -0,0 +1,132 @@
+//! CAN identifiers and frames, and the buffer received frames are written
+//! into.
+
+/// The maximum length of the data of a classic CAN frame, in bytes.
+pub const MAX_DATA_LEN: usize = 8;
+
+/// The space each received frame takes in an [`RxRingBuffer`]: its
+/// identifier (4 bytes, little-endian, with bit 31 set for extended
+/// identifiers), its data length (1 byte) and its data.
+pub const FRAME_SLOT_LEN: usize = 4 + 1 + MAX_DATA_LEN;
+
+/// A CAN identifier, which also sets the priority of the frame on the bus:
+/// the lower the identifier, the higher the priority.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Id {
+    /// An 11-bit identifier.
+    Standard(u16),
+    /// A 29-bit identifier.
+    Extended(u32),
+}
+
+impl Id {
+    pub const MAX_STANDARD: u16 = 0x7ff;
+    pub const MAX_EXTENDED: u32 = 0x1fff_ffff;
+
+    /// Returns `true` if the identifier fits in its number of bits.
+    pub fn is_valid(self) -> bool {
+        match self {
+            Id::Standard(id) => id <= Self::MAX_STANDARD,
+            Id::Extended(id) => id <= Self::MAX_EXTENDED,
+        }
+    }
+
+    pub(crate) fn to_raw(self) -> u32 {
+        match self {
+            Id::Standard(id) => id as u32,
+            Id::Extended(id) => id | EXTENDED_FLAG,
+        }
+    }
+
+    pub(crate) fn from_raw(raw: u32) -> Id {
+        match raw & EXTENDED_FLAG {
+            0 => Id::Standard(raw as u16 & Self::MAX_STANDARD),
+            _ => Id::Extended(raw & Self::MAX_EXTENDED),
+        }
+    }
+}
+
+/// A received frame.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Frame {
+    id: Id,
+    len: u8,
+    data: [u8; MAX_DATA_LEN],
+}
+
+impl Frame {
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.len as usize]
+    }
+}
+
+/// A ring buffer of `N` frame slots, shared with the kernel by
+/// `Can::receive`.
+///
+/// Its first two bytes are the index of the next slot to read, which the app
+/// advances, and the index of the next slot to write, which the kernel
+/// advances. One slot is always kept empty to tell a full buffer from an
+/// empty one, so the buffer holds up to `N - 1` frames; the kernel drops
+/// frames received while it is full. `N` must be between 2 and 256, which is
+/// checked at compile time.
+#[repr(C)]
+pub struct RxRingBuffer<const N: usize> {
+    indices: [u8; 2],
+    slots: [[u8; FRAME_SLOT_LEN]; N],
+}
+
+impl<const N: usize> RxRingBuffer<N> {
+    // The indices are single bytes, and one slot is always kept empty.
+    const _CHECK: () = assert!(N >= 2 && N <= 256, "RxRingBuffer needs 2 to 256 slots");
+
+    #[allow(clippy::let_unit_value)]
+    pub const fn new() -> RxRingBuffer<N> {
+        let () = Self::_CHECK;
+        RxRingBuffer {
+            indices: [0; 2],
+            slots: [[0; FRAME_SLOT_LEN]; N],
+        }
+    }
+
+    /// Removes and returns the oldest frame received, if any.
+    pub fn pop(&mut self) -> Option<Frame> {
+        let [read, write] = self.indices;
+        if read == write || read as usize >= N {
+            return None;
+        }
+        self.indices[0] = ((read as usize + 1) % N) as u8;
+
+        let slot = &self.slots[read as usize];
+        let mut frame = Frame {
+            id: Id::from_raw(u32::from_le_bytes([slot[0], slot[1], slot[2], slot[3]])),
+            len: slot[4].min(MAX_DATA_LEN as u8),
+            data: [0; MAX_DATA_LEN],
+        };
+        frame.data.copy_from_slice(&slot[5..]);
+        Some(frame)
+    }
+
+    /// Returns the buffer as shared with the kernel.
+    pub(crate) fn as_mut_bytes(&mut self) -> &mut [u8] {
+        let len = core::mem::size_of::<Self>();
+        // Safety: `RxRingBuffer` is `repr(C)` and contains only `u8` arrays, so
+        // it has no padding, and every byte pattern is valid for it.
+        unsafe { core::slice::from_raw_parts_mut(self as *mut Self as *mut u8, len) }
+    }
+}
+
+impl<const N: usize> Default for RxRingBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+const EXTENDED_FLAG: u32 = 1 << 31;
@@ -0,0 +1,194 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform as platform;
+use libtock_platform::allow_ro::AllowRo;
+use libtock_platform::allow_rw::AllowRw;
+use libtock_platform::share;
+use libtock_platform::subscribe::Subscribe;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+mod frame;
+
+pub use frame::{Frame, Id, RxRingBuffer, FRAME_SLOT_LEN, MAX_DATA_LEN};
+
+/// The CAN bus driver.
+///
+/// The bitrate and mode can only be changed while the controller is
+/// disabled. Once enabled, the controller sends frames with `send` and
+/// writes the frames it receives into an [`RxRingBuffer`], from which the app
+/// pops them once `receive` returns.
+///
+/// # Example
+/// ```ignore
+/// use libtock::can::{Can, Id, Mode, RxRingBuffer};
+///
+/// Can::set_bitrate(500_000)?;
+/// Can::set_mode(Mode::Normal)?;
+/// Can::enable()?;
+///
+/// Can::send(Id::Standard(0x123), &[1, 2, 3])?;
+///
+/// let mut frames = RxRingBuffer::<4>::new();
+/// Can::receive(&mut frames)?;
+/// while let Some(frame) = frames.pop() {
+///     // make use of frame.id() and frame.data()
+/// }
+/// ```
+pub struct Can<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> Can<S, C> {
+    /// Run a check against the CAN capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Sets the bitrate of the bus, in bits per second. Every node on the bus
+    /// must use the same bitrate.
+    pub fn set_bitrate(bitrate: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::SET_BITRATE, bitrate, 0).to_result()
+    }
+
+    pub fn set_mode(mode: Mode) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::SET_MODE, mode as u32, 0).to_result()
+    }
+
+    /// Connects the controller to the bus.
+    pub fn enable() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::ENABLE, 0, 0).to_result()
+    }
+
+    pub fn disable() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::DISABLE, 0, 0).to_result()
+    }
+
+    /// Sends a frame with identifier `id` and up to `MAX_DATA_LEN` bytes of
+    /// `data`, and waits until another node acknowledges it.
+    pub fn send(id: Id, data: &[u8]) -> Result<(), ErrorCode> {
+        if !id.is_valid() {
+            return Err(ErrorCode::Invalid);
+        }
+        if data.len() > MAX_DATA_LEN {
+            return Err(ErrorCode::Size);
+        }
+        let called = Cell::new(Option::<(u32,)>::None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, { allow_ro::TX }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::TX_DONE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<C, DRIVER_NUM, { allow_ro::TX }>(allow_ro, data)?;
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::TX_DONE }>(subscribe, &called)?;
+
+            // When this fails, `called` is guaranteed unmodified,
+            // because upcalls are never processed until we call `yield`.
+            S::command(DRIVER_NUM, command::SEND, id.to_raw(), data.len() as u32)
+                .to_result::<(), ErrorCode>()?;
+
+            loop {
+                S::yield_wait();
+                if let Some((status,)) = called.get() {
+                    return match status {
+                        0 => Ok(()),
+                        e_status => Err(e_status.try_into().unwrap_or(ErrorCode::Fail)),
+                    };
+                }
+            }
+        })
+    }
+
+    /// Shares `frames` with the kernel, starts receiving and waits until it
+    /// receives a frame. Frames already in `frames` are kept, and more than
+    /// one frame may have been received by the time this returns. Receiving
+    /// stops before this returns, so frames sent in between calls are lost.
+    pub fn receive<const N: usize>(frames: &mut RxRingBuffer<N>) -> Result<(), ErrorCode> {
+        let called = Cell::new(false);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, { allow_rw::RX }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::RECEIVED }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            // Subscribe first, so no frame arrives before we are listening.
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::RECEIVED }>(subscribe, &called)?;
+            S::allow_rw::<C, DRIVER_NUM, { allow_rw::RX }>(allow_rw, frames.as_mut_bytes())?;
+
+            // When this fails, `called` is guaranteed unmodified,
+            // because upcalls are never processed until we call `yield`.
+            S::command(DRIVER_NUM, command::START_RECEIVE, 0, 0).to_result::<(), ErrorCode>()?;
+
+            while !called.get() {
+                S::yield_wait();
+            }
+            S::command(DRIVER_NUM, command::STOP_RECEIVE, 0, 0).to_result()
+        })
+    }
+}
+
+/// The operating mode of the controller.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Mode {
+    /// Sends and receives frames on the bus.
+    Normal = 0,
+    /// Receives the frames it sends, without driving the bus. Used for
+    /// self-tests.
+    Loopback = 1,
+    /// Receives frames without acknowledging them, and cannot send.
+    Monitoring = 2,
+}
+
+/// System call configuration trait for `Can`.
+pub trait Config:
+    platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config
+{
+}
+impl<T: platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config>
+    Config for T
+{
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x20007;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const SET_BITRATE: u32 = 1;
+    pub const SET_MODE: u32 = 2;
+    pub const ENABLE: u32 = 3;
+    pub const DISABLE: u32 = 4;
+    pub const SEND: u32 = 5;
+    pub const START_RECEIVE: u32 = 7;
+    pub const STOP_RECEIVE: u32 = 8;
+}
+
+mod subscribe {
+    pub const RECEIVED: u32 = 0;
+    pub const TX_DONE: u32 = 1;
+}
+
+mod allow_ro {
+    pub const TX: u32 = 0;
+}
+
+mod allow_rw {
+    pub const RX: u32 = 0;
+}
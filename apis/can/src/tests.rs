@@ -0,0 +1,167 @@
+use super::*;
+use libtock_platform::ErrorCode;
+use libtock_unittest::fake::{CanFrame, CanId, CanMode};
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type Can = super::Can<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Can::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Can::new();
+    kernel.add_driver(&driver);
+
+    assert!(Can::driver_check());
+}
+
+#[test]
+fn configure() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Can::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Can::set_bitrate(250_000), Ok(()));
+    assert_eq!(driver.bitrate(), 250_000);
+    assert_eq!(Can::set_mode(Mode::Loopback), Ok(()));
+    assert_eq!(driver.mode(), CanMode::Loopback);
+    assert_eq!(Can::enable(), Ok(()));
+    assert!(driver.is_enabled());
+    assert_eq!(Can::set_bitrate(125_000), Err(ErrorCode::Busy));
+    assert_eq!(Can::disable(), Ok(()));
+    assert!(!driver.is_enabled());
+}
+
+#[test]
+fn ids() {
+    assert!(Id::Standard(Id::MAX_STANDARD).is_valid());
+    assert!(!Id::Standard(Id::MAX_STANDARD + 1).is_valid());
+    assert!(Id::Extended(Id::MAX_EXTENDED).is_valid());
+    assert!(!Id::Extended(Id::MAX_EXTENDED + 1).is_valid());
+    for id in [Id::Standard(0x123), Id::Extended(0x123)] {
+        assert_eq!(Id::from_raw(id.to_raw()), id);
+    }
+}
+
+#[test]
+fn send() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Can::new();
+    kernel.add_driver(&driver);
+    let bus = fake::CanBus::new(500_000);
+    bus.connect(&driver);
+
+    assert_eq!(Can::send(Id::Standard(1), b"off"), Err(ErrorCode::Off));
+    Can::enable().unwrap();
+    // No node on the bus acknowledges the frame.
+    assert_eq!(Can::send(Id::Standard(1), b"alone"), Err(ErrorCode::Fail));
+
+    let node = bus.node();
+    assert_eq!(Can::send(Id::Standard(0x123), b"hello"), Ok(()));
+    assert_eq!(Can::send(Id::Extended(0x1abc_def0), &[]), Ok(()));
+    assert_eq!(
+        Can::send(Id::Standard(0x800), b"hello"),
+        Err(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        Can::send(Id::Standard(1), b"too long!"),
+        Err(ErrorCode::Size)
+    );
+    assert_eq!(
+        node.take_received(),
+        [
+            CanFrame::new(CanId::Standard(0x123), b"hello"),
+            CanFrame::new(CanId::Extended(0x1abc_def0), &[]),
+        ]
+    );
+}
+
+#[test]
+fn receive() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Can::new();
+    kernel.add_driver(&driver);
+    let bus = fake::CanBus::new(500_000);
+    bus.connect(&driver);
+    let node = bus.node();
+    Can::enable().unwrap();
+
+    // The controller rejects frames until the app starts receiving.
+    assert!(!node.send(CanFrame::new(CanId::Standard(0x01), b"early")));
+
+    // A ring of three slots holds two frames; the third is dropped when
+    // receiving stops.
+    node.send_on_receive(CanFrame::new(CanId::Standard(0x10), b"one"));
+    node.send_on_receive(CanFrame::new(CanId::Extended(0x20), b"two"));
+    node.send_on_receive(CanFrame::new(CanId::Standard(0x30), b"three"));
+    let mut frames = RxRingBuffer::<3>::new();
+    assert_eq!(Can::receive(&mut frames), Ok(()));
+    assert!(!driver.is_receiving());
+    let frame = frames.pop().unwrap();
+    assert_eq!(frame.id(), Id::Standard(0x10));
+    assert_eq!(frame.data(), b"one");
+    let frame = frames.pop().unwrap();
+    assert_eq!(frame.id(), Id::Extended(0x20));
+    assert_eq!(frame.data(), b"two");
+    assert_eq!(frames.pop(), None);
+
+    assert!(!node.send(CanFrame::new(CanId::Standard(0x40), b"late")));
+    node.send_on_receive(CanFrame::new(CanId::Standard(0x50), b"four"));
+    assert_eq!(Can::receive(&mut frames), Ok(()));
+    assert_eq!(frames.pop().unwrap().data(), b"four");
+    assert_eq!(frames.pop(), None);
+}
+
+#[test]
+fn receive_disabled() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Can::new();
+    kernel.add_driver(&driver);
+
+    let mut frames = RxRingBuffer::<2>::new();
+    assert_eq!(Can::receive(&mut frames), Err(ErrorCode::Off));
+}
+
+#[test]
+fn loopback() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Can::new();
+    kernel.add_driver(&driver);
+    Can::set_mode(Mode::Loopback).unwrap();
+    Can::enable().unwrap();
+
+    // The controller acknowledges its own frame, though it drops it because
+    // it is not receiving.
+    assert_eq!(Can::send(Id::Standard(7), b"echo"), Ok(()));
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Can::new();
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_ro::TX,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::TX_DONE,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::SEND,
+        argument0: 0x123,
+        argument1: 2,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+
+    assert_eq!(Can::send(Id::Standard(0x123), b"hi"), Err(ErrorCode::Busy));
+}
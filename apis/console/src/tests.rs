@@ -1,6 +1,9 @@
 use super::*;
 use core::fmt::Write;
 use libtock_platform::ErrorCode;
+use libtock_unittest::fake::serial::{
+    ALLOW_READ, ALLOW_WRITE, READ, SUBSCRIBE_READ, SUBSCRIBE_WRITE, WRITE,
+};
 use libtock_unittest::{command_return, fake, ExpectedSyscall};
 
 type Console = super::Console<fake::Syscalls>;
@@ -79,17 +82,17 @@ fn failed_print() {
     kernel.add_driver(&driver);
     kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
         driver_num: DRIVER_NUM,
-        buffer_num: allow_ro::WRITE,
+        buffer_num: ALLOW_WRITE,
         return_error: None,
     });
     kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
         driver_num: DRIVER_NUM,
-        subscribe_num: subscribe::WRITE,
+        subscribe_num: SUBSCRIBE_WRITE,
         skip_with_error: None,
     });
     kernel.add_expected_syscall(ExpectedSyscall::Command {
         driver_id: DRIVER_NUM,
-        command_id: command::WRITE,
+        command_id: WRITE,
         argument0: 5,
         argument1: 0,
         override_return: Some(command_return::failure(ErrorCode::Fail)),
@@ -107,17 +110,17 @@ fn failed_read() {
     kernel.add_driver(&driver);
     kernel.add_expected_syscall(ExpectedSyscall::AllowRw {
         driver_num: DRIVER_NUM,
-        buffer_num: allow_rw::READ,
+        buffer_num: ALLOW_READ,
         return_error: None,
     });
     kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
         driver_num: DRIVER_NUM,
-        subscribe_num: subscribe::READ,
+        subscribe_num: SUBSCRIBE_READ,
         skip_with_error: None,
     });
     kernel.add_expected_syscall(ExpectedSyscall::Command {
         driver_id: DRIVER_NUM,
-        command_id: command::READ,
+        command_id: READ,
         argument0: 3,
         argument1: 0,
         override_return: Some(command_return::failure(ErrorCode::Fail)),
@@ -0,0 +1,152 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform as platform;
+use libtock_platform::allow_ro::AllowRo;
+use libtock_platform::share;
+use libtock_platform::subscribe::Subscribe;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+/// The CRC driver.
+///
+/// It computes CRCs using the kernel's (usually hardware-backed)
+/// implementation. Inputs of any length are supported: `update` can be called
+/// any number of times between `start` and `finish`, and each call passes as
+/// many pieces to the kernel as it takes to consume the data.
+///
+/// # Example
+/// ```ignore
+/// use libtock::crc::{Algorithm, Crc};
+///
+/// let crc = Crc::compute(Algorithm::Crc32, b"123456789")?;
+///
+/// // Or, over data that is not contiguous:
+/// Crc::start(Algorithm::Crc32)?;
+/// Crc::update(header)?;
+/// Crc::update(payload)?;
+/// let crc = Crc::finish()?;
+/// ```
+pub struct Crc<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> Crc<S, C> {
+    /// Run a check against the CRC capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Starts a new CRC computation using `algorithm`, discarding any
+    /// computation in progress.
+    pub fn start(algorithm: Algorithm) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::SET_ALGORITHM, algorithm as u32, 0).to_result()
+    }
+
+    /// Adds `data` to the CRC computation in progress.
+    pub fn update(mut data: &[u8]) -> Result<(), ErrorCode> {
+        // The kernel may consume only part of each buffer, in which case the
+        // rest is shared again.
+        while !data.is_empty() {
+            let consumed = Self::run(data, command::INPUT, data.len() as u32)? as usize;
+            if consumed == 0 || consumed > data.len() {
+                return Err(ErrorCode::Fail);
+            }
+            data = &data[consumed..];
+        }
+        Ok(())
+    }
+
+    /// Completes the CRC computation in progress and returns the CRC.
+    pub fn finish() -> Result<u32, ErrorCode> {
+        Self::run(&[], command::COMPUTE, 0)
+    }
+
+    /// Computes the CRC of `data` using `algorithm` in one call.
+    pub fn compute(algorithm: Algorithm, data: &[u8]) -> Result<u32, ErrorCode> {
+        Self::start(algorithm)?;
+        Self::update(data)?;
+        Self::finish()
+    }
+}
+
+/// A CRC algorithm supported by the kernel.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Algorithm {
+    /// CRC-32 (polynomial 0x04C11DB7, as used by Ethernet and zlib).
+    Crc32 = 0,
+    /// CRC-32C (Castagnoli polynomial 0x1EDC6F41, as used by iSCSI).
+    Crc32c = 1,
+    /// CRC-16-CCITT (polynomial 0x1021, initial value 0xFFFF, not reflected,
+    /// also known as CRC-16/CCITT-FALSE).
+    Crc16Ccitt = 2,
+}
+
+/// System call configuration trait for `Crc`.
+pub trait Config: platform::allow_ro::Config + platform::subscribe::Config {}
+impl<T: platform::allow_ro::Config + platform::subscribe::Config> Config for T {}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls, C: Config> Crc<S, C> {
+    // Shares `data`, runs `command_id`, and returns the value reported by its
+    // completion upcall.
+    fn run(data: &[u8], command_id: u32, argument0: u32) -> Result<u32, ErrorCode> {
+        let called = Cell::new(Option::<(u32, u32)>::None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, { allow_ro::DATA }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::DONE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<C, DRIVER_NUM, { allow_ro::DATA }>(allow_ro, data)?;
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::DONE }>(subscribe, &called)?;
+
+            // When this fails, `called` is guaranteed unmodified,
+            // because upcalls are never processed until we call `yield`.
+            S::command(DRIVER_NUM, command_id, argument0, 0).to_result::<(), ErrorCode>()?;
+
+            loop {
+                S::yield_wait();
+                if let Some((status, value)) = called.get() {
+                    return match status {
+                        0 => Ok(value),
+                        e_status => Err(e_status.try_into().unwrap_or(ErrorCode::Fail)),
+                    };
+                }
+            }
+        })
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x40002;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const SET_ALGORITHM: u32 = 1;
+    pub const INPUT: u32 = 2;
+    pub const COMPUTE: u32 = 3;
+}
+
+mod subscribe {
+    pub const DONE: u32 = 0;
+}
+
+mod allow_ro {
+    pub const DATA: u32 = 0;
+}
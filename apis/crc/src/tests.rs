@@ -0,0 +1,88 @@
+use super::*;
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type Crc = super::Crc<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Crc::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Crc::new();
+    kernel.add_driver(&driver);
+
+    assert!(Crc::driver_check());
+}
+
+// The standard check values, computed over "123456789".
+#[test]
+fn compute() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Crc::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Crc::compute(Algorithm::Crc32, b"123456789"), Ok(0xcbf43926));
+    assert_eq!(
+        Crc::compute(Algorithm::Crc32c, b"123456789"),
+        Ok(0xe3069283)
+    );
+    assert_eq!(
+        Crc::compute(Algorithm::Crc16Ccitt, b"123456789"),
+        Ok(0x29b1)
+    );
+}
+
+#[test]
+fn chunked() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Crc::new();
+    kernel.add_driver(&driver);
+    driver.set_chunk_size(2);
+
+    assert_eq!(Crc::start(Algorithm::Crc32), Ok(()));
+    assert_eq!(Crc::update(b"1234"), Ok(()));
+    assert_eq!(Crc::update(b""), Ok(()));
+    assert_eq!(Crc::update(b"56789"), Ok(()));
+    assert_eq!(Crc::finish(), Ok(0xcbf43926));
+    assert_eq!(driver.inputs(), 5);
+
+    // A long input, consumed in many pieces.
+    driver.set_chunk_size(64);
+    let data = [0xa5; 1000];
+    assert_eq!(Crc::compute(Algorithm::Crc32, &data), Ok(0x2156b7dc));
+    assert_eq!(driver.inputs(), 5 + 16);
+}
+
+#[test]
+fn not_started() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Crc::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Crc::update(b"1"), Err(ErrorCode::Reserve));
+    assert_eq!(Crc::finish(), Err(ErrorCode::Reserve));
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Crc::new();
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::SET_ALGORITHM,
+        argument0: Algorithm::Crc32c as u32,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::NoSupport)),
+    });
+
+    assert_eq!(
+        Crc::compute(Algorithm::Crc32c, b"123456789"),
+        Err(ErrorCode::NoSupport)
+    );
+}
@@ -0,0 +1,59 @@
+#![no_std]
+
+use libtock_platform::{ErrorCode, Syscalls};
+
+/// The digital-to-analog converter driver.
+///
+/// It drives the DAC's output pin to a voltage proportional to the value
+/// set, from 0 up to the converter's full scale, which depends on the
+/// hardware.
+///
+/// # Example
+/// ```ignore
+/// use libtock::dac::Dac;
+///
+/// Dac::initialize()?;
+/// Dac::set_value(512)?;
+/// ```
+pub struct Dac<S: Syscalls>(S);
+
+impl<S: Syscalls> Dac<S> {
+    /// Run a check against the DAC capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Powers up and configures the converter. This must be called before the
+    /// first `set_value`.
+    pub fn initialize() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::INITIALIZE, 0, 0).to_result()
+    }
+
+    /// Sets the output to `value`. Returns `ErrorCode::Invalid` if `value` is
+    /// over the converter's full scale, and an error if the converter has not
+    /// been initialized.
+    pub fn set_value(value: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::SET_VALUE, value, 0).to_result()
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x6;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const INITIALIZE: u32 = 1;
+    pub const SET_VALUE: u32 = 2;
+}
@@ -0,0 +1,38 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::fake;
+
+type Dac = super::Dac<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Dac::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Dac::new();
+    kernel.add_driver(&driver);
+
+    assert!(Dac::driver_check());
+}
+
+#[test]
+fn set_value() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Dac::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Dac::set_value(0), Err(ErrorCode::Off));
+    assert_eq!(Dac::initialize(), Ok(()));
+    assert!(driver.is_initialized());
+    assert_eq!(Dac::set_value(0), Ok(()));
+    assert_eq!(Dac::set_value(fake::Dac::MAX_VALUE), Ok(()));
+    assert_eq!(
+        Dac::set_value(fake::Dac::MAX_VALUE + 1),
+        Err(ErrorCode::Invalid)
+    );
+    assert_eq!(driver.value(), Some(fake::Dac::MAX_VALUE));
+    assert_eq!(driver.take_history(), [0, fake::Dac::MAX_VALUE]);
+}
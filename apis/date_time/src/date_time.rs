@@ -0,0 +1,178 @@
+//! Calendar dates and times, and their conversion to Unix timestamps.
+
+use libtock_platform::ErrorCode;
+
+/// A calendar date and time, without a time zone.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct DateTime {
+    pub year: u16,
+    /// From 1 (January) to 12 (December).
+    pub month: u8,
+    /// From 1 to the number of days in the month.
+    pub day: u8,
+    /// From 0 to 23.
+    pub hour: u8,
+    /// From 0 to 59.
+    pub minute: u8,
+    /// From 0 to 59.
+    pub second: u8,
+}
+
+/// A day of the week.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DayOfWeek {
+    Sunday = 0,
+    Monday = 1,
+    Tuesday = 2,
+    Wednesday = 3,
+    Thursday = 4,
+    Friday = 5,
+    Saturday = 6,
+}
+
+impl DateTime {
+    /// Returns the date and time, or `ErrorCode::Invalid` if it does not
+    /// exist.
+    pub fn new(
+        year: u16,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    ) -> Result<DateTime, ErrorCode> {
+        let date_time = DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        };
+        date_time.validate()?;
+        Ok(date_time)
+    }
+
+    /// Returns `ErrorCode::Invalid` if the date and time does not exist, such
+    /// as on February 30th.
+    pub fn validate(&self) -> Result<(), ErrorCode> {
+        if !(1..=12).contains(&self.month)
+            || self.day == 0
+            || self.day > days_in_month(self.year, self.month)
+            || self.hour > 23
+            || self.minute > 59
+            || self.second > 59
+        {
+            return Err(ErrorCode::Invalid);
+        }
+        Ok(())
+    }
+
+    pub fn day_of_week(&self) -> DayOfWeek {
+        // 1970-01-01 was a Thursday.
+        match (self.days_since_epoch() + 4).rem_euclid(7) {
+            0 => DayOfWeek::Sunday,
+            1 => DayOfWeek::Monday,
+            2 => DayOfWeek::Tuesday,
+            3 => DayOfWeek::Wednesday,
+            4 => DayOfWeek::Thursday,
+            5 => DayOfWeek::Friday,
+            _ => DayOfWeek::Saturday,
+        }
+    }
+
+    /// Returns the number of seconds since 1970-01-01 00:00:00, taking this
+    /// date and time to be in UTC.
+    pub fn to_unix_timestamp(&self) -> i64 {
+        self.days_since_epoch() * SECONDS_PER_DAY
+            + self.hour as i64 * 3600
+            + self.minute as i64 * 60
+            + self.second as i64
+    }
+
+    /// Returns the UTC date and time `timestamp` seconds after 1970-01-01
+    /// 00:00:00, or `ErrorCode::Invalid` if its year does not fit in a `u16`.
+    pub fn from_unix_timestamp(timestamp: i64) -> Result<DateTime, ErrorCode> {
+        let days = timestamp.div_euclid(SECONDS_PER_DAY);
+        let seconds = timestamp.rem_euclid(SECONDS_PER_DAY);
+
+        // Converts days to a civil date, using eras of 400 years starting on
+        // March 1st so leap days fall at the end of each year.
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_from_march = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+        let month = if month_from_march < 10 {
+            month_from_march + 3
+        } else {
+            month_from_march - 9
+        };
+        let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+        Ok(DateTime {
+            year: year.try_into().map_err(|_| ErrorCode::Invalid)?,
+            month: month as u8,
+            day: day as u8,
+            hour: (seconds / 3600) as u8,
+            minute: (seconds / 60 % 60) as u8,
+            second: (seconds % 60) as u8,
+        })
+    }
+
+    // Returns the number of days between 1970-01-01 and this date.
+    fn days_since_epoch(&self) -> i64 {
+        // The inverse of the conversion in `from_unix_timestamp`.
+        let year = self.year as i64 - (self.month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let month = self.month as i64;
+        let day_of_year =
+            (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    // Returns the date and time as encoded by the driver.
+    pub(crate) fn encode(&self) -> (u32, u32) {
+        let date = (self.year as u32) << 9 | (self.month as u32) << 5 | self.day as u32;
+        let time = (self.day_of_week() as u32) << 17
+            | (self.hour as u32) << 12
+            | (self.minute as u32) << 6
+            | self.second as u32;
+        (date, time)
+    }
+
+    // Decodes a date and time as encoded by the driver. The day of the week is
+    // ignored, as it follows from the date.
+    pub(crate) fn decode(date: u32, time: u32) -> DateTime {
+        DateTime {
+            year: (date >> 9) as u16,
+            month: (date >> 5 & 0xf) as u8,
+            day: (date & 0x1f) as u8,
+            hour: (time >> 12 & 0x1f) as u8,
+            minute: (time >> 6 & 0x3f) as u8,
+            second: (time & 0x3f) as u8,
+        }
+    }
+}
+
+/// Returns whether `year` has a February 29th.
+pub fn is_leap_year(year: u16) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Returns the number of days in `month` (from 1 to 12) of `year`.
+pub fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+const SECONDS_PER_DAY: i64 = 86_400;
@@ -0,0 +1,101 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform as platform;
+use libtock_platform::share;
+use libtock_platform::subscribe::Subscribe;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+mod date_time;
+
+pub use date_time::{days_in_month, is_leap_year, DateTime, DayOfWeek};
+
+/// The real-time clock driver, which keeps the calendar date and time.
+///
+/// # Example
+/// ```ignore
+/// use libtock::date_time::{DateTime, Rtc};
+///
+/// Rtc::set_date_time(&DateTime::new(2024, 2, 29, 12, 30, 0)?)?;
+/// let now = Rtc::get_date_time()?;
+/// let timestamp = now.to_unix_timestamp();
+/// ```
+pub struct Rtc<S: Syscalls, C: platform::subscribe::Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: platform::subscribe::Config> Rtc<S, C> {
+    /// Run a check against the date and time capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Reads the current date and time. Returns `ErrorCode::Fail` if the
+    /// driver reports a date or time that does not exist.
+    pub fn get_date_time() -> Result<DateTime, ErrorCode> {
+        let (date, time) = Self::command_sync(command::GET_DATE_TIME, 0, 0)?;
+        let date_time = DateTime::decode(date, time);
+        date_time.validate().map_err(|_| ErrorCode::Fail)?;
+        Ok(date_time)
+    }
+
+    /// Sets the current date and time. Returns `ErrorCode::Invalid` if it
+    /// does not exist.
+    pub fn set_date_time(date_time: &DateTime) -> Result<(), ErrorCode> {
+        date_time.validate()?;
+        let (date, time) = date_time.encode();
+        Self::command_sync(command::SET_DATE_TIME, date, time).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls, C: platform::subscribe::Config> Rtc<S, C> {
+    // Runs a command that completes with an upcall, and returns the upcall's
+    // date and time arguments.
+    fn command_sync(command_num: u32, arg0: u32, arg1: u32) -> Result<(u32, u32), ErrorCode> {
+        let called = Cell::new(Option::<(u32, u32, u32)>::None);
+        share::scope::<Subscribe<_, DRIVER_NUM, { subscribe::DONE }>, _, _>(|handle| {
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::DONE }>(handle, &called)?;
+
+            // When this fails, `called` is guaranteed unmodified,
+            // because upcalls are never processed until we call `yield`.
+            S::command(DRIVER_NUM, command_num, arg0, arg1).to_result::<(), ErrorCode>()?;
+
+            loop {
+                S::yield_wait();
+                if let Some((status, date, time)) = called.get() {
+                    return match status {
+                        0 => Ok((date, time)),
+                        e_status => Err(e_status.try_into().unwrap_or(ErrorCode::Fail)),
+                    };
+                }
+            }
+        })
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x90007;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const GET_DATE_TIME: u32 = 1;
+    pub const SET_DATE_TIME: u32 = 2;
+}
+
+mod subscribe {
+    pub const DONE: u32 = 0;
+}
@@ -0,0 +1,135 @@
+use super::*;
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type Rtc = super::Rtc<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Rtc::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Rtc::new();
+    kernel.add_driver(&driver);
+
+    assert!(Rtc::driver_check());
+}
+
+#[test]
+fn validation() {
+    assert!(DateTime::new(2024, 2, 29, 23, 59, 59).is_ok());
+    assert_eq!(DateTime::new(2023, 2, 29, 0, 0, 0), Err(ErrorCode::Invalid));
+    assert_eq!(DateTime::new(1900, 2, 29, 0, 0, 0), Err(ErrorCode::Invalid));
+    assert!(DateTime::new(2000, 2, 29, 0, 0, 0).is_ok());
+    assert_eq!(DateTime::new(2024, 4, 31, 0, 0, 0), Err(ErrorCode::Invalid));
+    assert_eq!(DateTime::new(2024, 0, 1, 0, 0, 0), Err(ErrorCode::Invalid));
+    assert_eq!(DateTime::new(2024, 13, 1, 0, 0, 0), Err(ErrorCode::Invalid));
+    assert_eq!(DateTime::new(2024, 1, 0, 0, 0, 0), Err(ErrorCode::Invalid));
+    assert_eq!(DateTime::new(2024, 1, 1, 24, 0, 0), Err(ErrorCode::Invalid));
+    assert_eq!(DateTime::new(2024, 1, 1, 0, 60, 0), Err(ErrorCode::Invalid));
+    assert_eq!(DateTime::new(2024, 1, 1, 0, 0, 60), Err(ErrorCode::Invalid));
+}
+
+#[test]
+fn unix_timestamps() {
+    let epoch = DateTime::new(1970, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(epoch.to_unix_timestamp(), 0);
+    assert_eq!(epoch.day_of_week(), DayOfWeek::Thursday);
+    assert_eq!(DateTime::from_unix_timestamp(0), Ok(epoch));
+
+    let leap_day = DateTime::new(2024, 2, 29, 12, 30, 15).unwrap();
+    assert_eq!(leap_day.to_unix_timestamp(), 1_709_209_815);
+    assert_eq!(leap_day.day_of_week(), DayOfWeek::Thursday);
+    assert_eq!(DateTime::from_unix_timestamp(1_709_209_815), Ok(leap_day));
+
+    let before_epoch = DateTime::new(1969, 12, 31, 23, 59, 59).unwrap();
+    assert_eq!(before_epoch.to_unix_timestamp(), -1);
+    assert_eq!(DateTime::from_unix_timestamp(-1), Ok(before_epoch));
+
+    let y2038 = DateTime::new(2038, 1, 19, 3, 14, 8).unwrap();
+    assert_eq!(y2038.to_unix_timestamp(), 1 << 31);
+    assert_eq!(y2038.day_of_week(), DayOfWeek::Tuesday);
+    assert_eq!(DateTime::from_unix_timestamp(1 << 31), Ok(y2038));
+
+    assert_eq!(
+        DateTime::from_unix_timestamp(i64::MAX / 2),
+        Err(ErrorCode::Invalid)
+    );
+}
+
+#[test]
+fn get_and_set() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Rtc::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(
+        Rtc::get_date_time(),
+        Ok(DateTime::new(2000, 1, 1, 0, 0, 0).unwrap())
+    );
+    let date_time = DateTime::new(2024, 2, 29, 12, 30, 15).unwrap();
+    assert_eq!(Rtc::set_date_time(&date_time), Ok(()));
+    assert_eq!(
+        driver.date_time(),
+        fake::RtcDateTime {
+            year: 2024,
+            month: 2,
+            day: 29,
+            day_of_week: 4,
+            hour: 12,
+            minute: 30,
+            second: 15,
+        }
+    );
+    assert_eq!(Rtc::get_date_time(), Ok(date_time));
+
+    let invalid = DateTime {
+        day: 30,
+        ..date_time
+    };
+    assert_eq!(Rtc::set_date_time(&invalid), Err(ErrorCode::Invalid));
+    assert_eq!(Rtc::get_date_time(), Ok(date_time));
+}
+
+#[test]
+fn invalid_from_driver() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Rtc::new();
+    kernel.add_driver(&driver);
+
+    driver.set_date_time(fake::RtcDateTime {
+        year: 2024,
+        month: 13,
+        day: 1,
+        day_of_week: 0,
+        hour: 0,
+        minute: 0,
+        second: 0,
+    });
+    assert_eq!(Rtc::get_date_time(), Err(ErrorCode::Fail));
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Rtc::new();
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::DONE,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::GET_DATE_TIME,
+        argument0: 0,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+
+    assert_eq!(Rtc::get_date_time(), Err(ErrorCode::Busy));
+}
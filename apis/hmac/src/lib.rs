@@ -0,0 +1,195 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform as platform;
+use libtock_platform::allow_ro::AllowRo;
+use libtock_platform::allow_rw::AllowRw;
+use libtock_platform::share::{self, Handle};
+use libtock_platform::subscribe::Subscribe;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+/// The HMAC driver.
+///
+/// It computes HMACs using the kernel's (usually hardware-backed)
+/// implementation. Start an HMAC with `start`, which shares the key, pass the
+/// message to `update` in as many pieces as needed, and retrieve the result
+/// with `finalize`. The kernel keeps one HMAC in progress per process.
+///
+/// # Example
+/// ```ignore
+/// use libtock::hmac::{Algorithm, Hmac};
+///
+/// let mut mac = [0; 32];
+/// share::scope(|allow_ro| {
+///     Hmac::start(Algorithm::Sha256, b"secret key", allow_ro)?;
+///     Hmac::update(b"temperature=21")?;
+///     Hmac::update(b";humidity=40")?;
+///     Hmac::finalize(&mut mac)
+/// })?;
+/// ```
+pub struct Hmac<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> Hmac<S, C> {
+    /// Run a check against the HMAC capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    ///
+    /// The capsule has no separate check command: this selects HMAC-SHA256,
+    /// so it must not be called while an HMAC is in progress.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Starts a new HMAC using `algorithm` and `key`, discarding any HMAC in
+    /// progress. The kernel reads the key while it processes the message, so
+    /// it stays shared until the scope of `allow_ro` ends; call `update` and
+    /// `finalize` within that scope.
+    pub fn start<'share>(
+        algorithm: Algorithm,
+        key: &'share [u8],
+        allow_ro: Handle<AllowRo<'share, S, DRIVER_NUM, { allow_ro::KEY }>>,
+    ) -> Result<(), ErrorCode> {
+        S::allow_ro::<C, DRIVER_NUM, { allow_ro::KEY }>(allow_ro, key)?;
+        S::command(DRIVER_NUM, command::SET_ALGORITHM, algorithm as u32, 0).to_result()
+    }
+
+    /// Adds `data` to the message being authenticated.
+    pub fn update(data: &[u8]) -> Result<(), ErrorCode> {
+        let called = Cell::new(Option::<(u32,)>::None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, { allow_ro::DATA }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::DONE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<C, DRIVER_NUM, { allow_ro::DATA }>(allow_ro, data)?;
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::DONE }>(subscribe, &called)?;
+            Self::wait_for(command::UPDATE, &called)
+        })
+    }
+
+    /// Completes the HMAC in progress and writes it into the start of `mac`.
+    ///
+    /// Fails with `ErrorCode::Size` if `mac` is shorter than the selected
+    /// algorithm's output.
+    pub fn finalize(mac: &mut [u8]) -> Result<(), ErrorCode> {
+        let called = Cell::new(Option::<(u32,)>::None);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, { allow_rw::DEST }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::DONE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            S::allow_rw::<C, DRIVER_NUM, { allow_rw::DEST }>(allow_rw, mac)?;
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::DONE }>(subscribe, &called)?;
+            Self::wait_for(command::FINISH, &called)
+        })
+    }
+
+    /// Computes the HMAC of `data` using `algorithm` and `key` in one call.
+    pub fn hmac(
+        algorithm: Algorithm,
+        key: &[u8],
+        data: &[u8],
+        mac: &mut [u8],
+    ) -> Result<(), ErrorCode> {
+        share::scope(|allow_ro| {
+            Self::start(algorithm, key, allow_ro)?;
+            Self::update(data)?;
+            Self::finalize(mac)
+        })
+    }
+}
+
+/// The hash function used by an HMAC.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Algorithm {
+    Sha256 = 0,
+    Sha384 = 1,
+    Sha512 = 2,
+}
+
+impl Algorithm {
+    /// Returns the length of the algorithm's output, in bytes.
+    pub const fn output_len(self) -> usize {
+        match self {
+            Algorithm::Sha256 => 32,
+            Algorithm::Sha384 => 48,
+            Algorithm::Sha512 => 64,
+        }
+    }
+}
+
+/// System call configuration trait for `Hmac`.
+pub trait Config:
+    platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config
+{
+}
+impl<T: platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config>
+    Config for T
+{
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls, C: Config> Hmac<S, C> {
+    // Runs `command_id` and waits for its completion upcall.
+    fn wait_for(command_id: u32, called: &Cell<Option<(u32,)>>) -> Result<(), ErrorCode> {
+        // When this fails, `called` is guaranteed unmodified,
+        // because upcalls are never processed until we call `yield`.
+        S::command(DRIVER_NUM, command_id, 0, 0).to_result::<(), ErrorCode>()?;
+
+        loop {
+            S::yield_wait();
+            if let Some((status,)) = called.get() {
+                return match status {
+                    0 => Ok(()),
+                    e_status => Err(e_status.try_into().unwrap_or(ErrorCode::Fail)),
+                };
+            }
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x40003;
+
+// Command IDs
+mod command {
+    // The capsule has no separate driver check: command 0 sets the algorithm,
+    // and succeeds with argument 0 (SHA-256).
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const SET_ALGORITHM: u32 = 0;
+    pub const UPDATE: u32 = 2;
+    pub const FINISH: u32 = 3;
+}
+
+mod subscribe {
+    pub const DONE: u32 = 0;
+}
+
+mod allow_ro {
+    pub const KEY: u32 = 0;
+    pub const DATA: u32 = 1;
+}
+
+mod allow_rw {
+    pub const DEST: u32 = 2;
+}
@@ -0,0 +1,131 @@
+use super::*;
+use libtock_platform::{share, ErrorCode};
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type Hmac = super::Hmac<fake::Syscalls>;
+
+// RFC 4231 test case 2: key "Jefe", data "what do ya want for nothing?".
+const CASE2_SHA256: [u8; 32] = [
+    0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26, 0x08, 0x95, 0x75, 0xc7,
+    0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, 0x9d, 0xec, 0x58, 0xb9, 0x64, 0xec, 0x38, 0x43,
+];
+const CASE2_SHA384: [u8; 48] = [
+    0xaf, 0x45, 0xd2, 0xe3, 0x76, 0x48, 0x40, 0x31, 0x61, 0x7f, 0x78, 0xd2, 0xb5, 0x8a, 0x6b, 0x1b,
+    0x9c, 0x7e, 0xf4, 0x64, 0xf5, 0xa0, 0x1b, 0x47, 0xe4, 0x2e, 0xc3, 0x73, 0x63, 0x22, 0x44, 0x5e,
+    0x8e, 0x22, 0x40, 0xca, 0x5e, 0x69, 0xe2, 0xc7, 0x8b, 0x32, 0x39, 0xec, 0xfa, 0xb2, 0x16, 0x49,
+];
+const CASE2_SHA512: [u8; 64] = [
+    0x16, 0x4b, 0x7a, 0x7b, 0xfc, 0xf8, 0x19, 0xe2, 0xe3, 0x95, 0xfb, 0xe7, 0x3b, 0x56, 0xe0, 0xa3,
+    0x87, 0xbd, 0x64, 0x22, 0x2e, 0x83, 0x1f, 0xd6, 0x10, 0x27, 0x0c, 0xd7, 0xea, 0x25, 0x05, 0x54,
+    0x97, 0x58, 0xbf, 0x75, 0xc0, 0x5a, 0x99, 0x4a, 0x6d, 0x03, 0x4f, 0x65, 0xf8, 0xf0, 0xe6, 0xfd,
+    0xca, 0xea, 0xb1, 0xa3, 0x4d, 0x4a, 0x6b, 0x4b, 0x63, 0x6e, 0x07, 0x0a, 0x38, 0xbc, 0xe7, 0x37,
+];
+const KEY: &[u8] = b"Jefe";
+const DATA: &[u8] = b"what do ya want for nothing?";
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Hmac::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Hmac::new();
+    kernel.add_driver(&driver);
+
+    assert!(Hmac::driver_check());
+}
+
+#[test]
+fn hmac() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Hmac::new();
+    kernel.add_driver(&driver);
+
+    let mut mac = [0; 64];
+    assert_eq!(Hmac::hmac(Algorithm::Sha256, KEY, DATA, &mut mac), Ok(()));
+    assert_eq!(mac[..32], CASE2_SHA256);
+    assert_eq!(Hmac::hmac(Algorithm::Sha384, KEY, DATA, &mut mac), Ok(()));
+    assert_eq!(mac[..48], CASE2_SHA384);
+    assert_eq!(Hmac::hmac(Algorithm::Sha512, KEY, DATA, &mut mac), Ok(()));
+    assert_eq!(mac, CASE2_SHA512);
+}
+
+#[test]
+fn streaming() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Hmac::new();
+    kernel.add_driver(&driver);
+
+    let mut mac = [0; 32];
+    share::scope(|allow_ro| {
+        assert_eq!(Hmac::start(Algorithm::Sha256, KEY, allow_ro), Ok(()));
+        assert_eq!(driver.output_len(), Some(32));
+        for chunk in DATA.chunks(5) {
+            assert_eq!(Hmac::update(chunk), Ok(()));
+        }
+        assert_eq!(Hmac::finalize(&mut mac), Ok(()));
+    });
+    assert_eq!(mac, CASE2_SHA256);
+    assert_eq!(driver.output_len(), None);
+
+    // The output buffer is too small for HMAC-SHA512.
+    share::scope(|allow_ro| {
+        assert_eq!(Hmac::start(Algorithm::Sha512, KEY, allow_ro), Ok(()));
+        assert_eq!(Hmac::finalize(&mut mac), Err(ErrorCode::Size));
+    });
+}
+
+// The kernel reads the key when it computes the HMAC, so finishing after the
+// key is unshared must fail.
+#[test]
+fn key_unshared() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Hmac::new();
+    kernel.add_driver(&driver);
+
+    let mut mac = [0; 32];
+    share::scope(|allow_ro| {
+        assert_eq!(Hmac::start(Algorithm::Sha256, KEY, allow_ro), Ok(()));
+    });
+    assert_eq!(Hmac::update(DATA), Ok(()));
+    assert_eq!(Hmac::finalize(&mut mac), Err(ErrorCode::Invalid));
+    assert_eq!(mac, [0; 32]);
+}
+
+#[test]
+fn not_started() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Hmac::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Hmac::update(DATA), Err(ErrorCode::Invalid));
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Hmac::new();
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_ro::KEY,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::SET_ALGORITHM,
+        argument0: Algorithm::Sha384 as u32,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::NoSupport)),
+    });
+
+    share::scope(|allow_ro| {
+        assert_eq!(
+            Hmac::start(Algorithm::Sha384, KEY, allow_ro),
+            Err(ErrorCode::NoSupport)
+        );
+    });
+}
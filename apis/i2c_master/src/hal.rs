@@ -0,0 +1,88 @@
+//! `embedded-hal` support for the I2C master driver.
+
+use crate::{Config, Error, I2cMaster};
+use core::marker::PhantomData;
+use embedded_hal::i2c::{self, ErrorKind, NoAcknowledgeSource, Operation};
+use libtock_platform::{DefaultConfig, Syscalls};
+
+/// An [`embedded_hal::i2c::I2c`] implementation on top of [`I2cMaster`].
+///
+/// The kernel driver needs a mutable buffer for every transfer, so written
+/// data is first copied into an internal buffer of `BUFFER_SIZE` bytes. Writes
+/// longer than that fail with [`Error::Size`].
+///
+/// The kernel only supports a repeated start between a write and the read that
+/// follows it, so a write operation directly followed by a read operation is
+/// performed as a single write-read transfer. All other operations of a
+/// transaction are performed as separate transfers.
+pub struct I2cBus<S: Syscalls, C: Config = DefaultConfig, const BUFFER_SIZE: usize = 32> {
+    buffer: [u8; BUFFER_SIZE],
+    _syscalls: PhantomData<(S, C)>,
+}
+
+impl<S: Syscalls, C: Config, const BUFFER_SIZE: usize> I2cBus<S, C, BUFFER_SIZE> {
+    pub fn new() -> Self {
+        Self {
+            buffer: [0; BUFFER_SIZE],
+            _syscalls: PhantomData,
+        }
+    }
+
+    fn write_bytes(&mut self, address: u8, bytes: &[u8]) -> Result<(), Error> {
+        let buffer = self.buffer.get_mut(..bytes.len()).ok_or(Error::Size)?;
+        buffer.copy_from_slice(bytes);
+        I2cMaster::<S, C>::write(address, buffer)
+    }
+
+    fn write_then_read(&mut self, address: u8, bytes: &[u8], read: &mut [u8]) -> Result<(), Error> {
+        let len = core::cmp::max(bytes.len(), read.len());
+        let buffer = self.buffer.get_mut(..len).ok_or(Error::Size)?;
+        buffer[..bytes.len()].copy_from_slice(bytes);
+        I2cMaster::<S, C>::write_read(address, buffer, bytes.len(), read.len())?;
+        read.copy_from_slice(&buffer[..read.len()]);
+        Ok(())
+    }
+}
+
+impl<S: Syscalls, C: Config, const BUFFER_SIZE: usize> Default for I2cBus<S, C, BUFFER_SIZE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl i2c::Error for Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Nack => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Unknown),
+            Error::ArbitrationLost => ErrorKind::ArbitrationLoss,
+            Error::Size => ErrorKind::Overrun,
+            Error::Other(_) => ErrorKind::Other,
+        }
+    }
+}
+
+impl<S: Syscalls, C: Config, const BUFFER_SIZE: usize> i2c::ErrorType
+    for I2cBus<S, C, BUFFER_SIZE>
+{
+    type Error = Error;
+}
+
+impl<S: Syscalls, C: Config, const BUFFER_SIZE: usize> i2c::I2c for I2cBus<S, C, BUFFER_SIZE> {
+    fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Error> {
+        let mut operations = operations.iter_mut().peekable();
+        while let Some(operation) = operations.next() {
+            match operation {
+                Operation::Write(bytes) => {
+                    match operations.next_if(|next| matches!(next, Operation::Read(_))) {
+                        Some(Operation::Read(read)) => {
+                            self.write_then_read(address, bytes, read)?
+                        }
+                        _ => self.write_bytes(address, bytes)?,
+                    }
+                }
+                Operation::Read(read) => I2cMaster::<S, C>::read(address, read)?,
+            }
+        }
+        Ok(())
+    }
+}
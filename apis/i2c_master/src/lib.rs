@@ -0,0 +1,171 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform as platform;
+use libtock_platform::allow_rw::AllowRw;
+use libtock_platform::share;
+use libtock_platform::subscribe::Subscribe;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+mod hal;
+
+pub use hal::I2cBus;
+
+/// The I2C master driver.
+///
+/// It allows a process to perform transactions with devices on an I2C bus the
+/// process acts as master on. All transfers go through a single buffer shared
+/// with the kernel: data to be written is taken from the start of the buffer,
+/// and data that is read is placed at the start of the buffer.
+///
+/// # Example
+/// ```ignore
+/// use libtock::i2c_master::I2cMaster;
+///
+/// // Read two bytes from register 0x0f of the device at address 0x1d.
+/// let mut buf = [0x0f, 0];
+/// I2cMaster::write_read(0x1d, &mut buf, 1, 2)?;
+/// ```
+pub struct I2cMaster<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> I2cMaster<S, C> {
+    /// Run a check against the I2C master capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Writes the whole of `buf` to the device at `address`.
+    pub fn write(address: u8, buf: &mut [u8]) -> Result<(), Error> {
+        let len = transfer_len(buf.len())?;
+        Self::transaction(command::WRITE, buf, address as u32, len)
+    }
+
+    /// Fills `buf` with data read from the device at `address`.
+    pub fn read(address: u8, buf: &mut [u8]) -> Result<(), Error> {
+        let len = transfer_len(buf.len())?;
+        Self::transaction(command::READ, buf, address as u32, len)
+    }
+
+    /// Writes the first `write_len` bytes of `buf` to the device at `address`,
+    /// then reads `read_len` bytes from it into the start of `buf`, using a
+    /// repeated start condition between the two.
+    pub fn write_read(
+        address: u8,
+        buf: &mut [u8],
+        write_len: usize,
+        read_len: usize,
+    ) -> Result<(), Error> {
+        if write_len > buf.len() || read_len > buf.len() {
+            return Err(Error::Size);
+        }
+        let write_len = transfer_len(write_len)?;
+        let read_len = transfer_len(read_len)?;
+        Self::transaction(
+            command::WRITE_READ,
+            buf,
+            write_len << 8 | address as u32,
+            read_len,
+        )
+    }
+}
+
+/// An I2C transaction error.
+///
+/// The kernel reports I2C bus errors as `ErrorCode`s; the ones with an I2C
+/// specific meaning are given their own variant.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The device did not acknowledge its address or a data byte.
+    Nack,
+    /// Another master took over the bus during the transaction.
+    ArbitrationLost,
+    /// The transfer does not fit in the buffer, or the controller overran.
+    Size,
+    /// Any other failure reported by the kernel.
+    Other(ErrorCode),
+}
+
+impl From<ErrorCode> for Error {
+    fn from(error: ErrorCode) -> Error {
+        match error {
+            ErrorCode::NoAck => Error::Nack,
+            ErrorCode::Reserve => Error::ArbitrationLost,
+            ErrorCode::Size => Error::Size,
+            error => Error::Other(error),
+        }
+    }
+}
+
+/// System call configuration trait for `I2cMaster`.
+pub trait Config: platform::allow_rw::Config + platform::subscribe::Config {}
+impl<T: platform::allow_rw::Config + platform::subscribe::Config> Config for T {}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+// The kernel takes transfer lengths as 16-bit values.
+fn transfer_len(len: usize) -> Result<u32, Error> {
+    u16::try_from(len).map(u32::from).map_err(|_| Error::Size)
+}
+
+impl<S: Syscalls, C: Config> I2cMaster<S, C> {
+    fn transaction(command_id: u32, buf: &mut [u8], arg0: u32, arg1: u32) -> Result<(), Error> {
+        let called = Cell::new(Option::<(u32, u32)>::None);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, { allow_rw::BUFFER }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::COMPLETE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            S::allow_rw::<C, DRIVER_NUM, { allow_rw::BUFFER }>(allow_rw, buf)?;
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::COMPLETE }>(subscribe, &called)?;
+
+            S::command(DRIVER_NUM, command_id, arg0, arg1).to_result::<(), ErrorCode>()?;
+
+            loop {
+                S::yield_wait();
+                if let Some((_command, status)) = called.get() {
+                    return match status {
+                        0 => Ok(()),
+                        e_status => Err(e_status.try_into().unwrap_or(ErrorCode::Fail).into()),
+                    };
+                }
+            }
+        })
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x20003;
+
+// Command IDs
+#[allow(unused)]
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const WRITE: u32 = 1;
+    pub const READ: u32 = 2;
+    pub const WRITE_READ: u32 = 3;
+}
+
+mod subscribe {
+    pub const COMPLETE: u32 = 0;
+}
+
+mod allow_rw {
+    pub const BUFFER: u32 = 1;
+}
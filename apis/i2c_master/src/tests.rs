@@ -0,0 +1,147 @@
+use super::*;
+use embedded_hal::i2c::{ErrorKind, I2c, NoAcknowledgeSource, Operation};
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type I2cMaster = super::I2cMaster<fake::Syscalls>;
+type I2cBus = super::I2cBus<fake::Syscalls, DefaultConfig, 4>;
+
+const ADDRESS: u8 = 0x1d;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!I2cMaster::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::I2cMaster::new();
+    kernel.add_driver(&driver);
+
+    assert!(I2cMaster::driver_check());
+}
+
+#[test]
+fn write() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::I2cMaster::new();
+    kernel.add_driver(&driver);
+    driver.add_device(ADDRESS, &[0; 4]);
+
+    I2cMaster::write(ADDRESS, &mut [1, 0xaa, 0xbb]).unwrap();
+    assert_eq!(driver.get_registers(ADDRESS).unwrap(), [0, 0xaa, 0xbb, 0]);
+}
+
+#[test]
+fn read() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::I2cMaster::new();
+    kernel.add_driver(&driver);
+    driver.add_device(ADDRESS, &[1, 2, 3, 4]);
+
+    let mut buf = [0; 3];
+    I2cMaster::read(ADDRESS, &mut buf).unwrap();
+    assert_eq!(buf, [1, 2, 3]);
+}
+
+#[test]
+fn write_read() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::I2cMaster::new();
+    kernel.add_driver(&driver);
+    driver.add_device(ADDRESS, &[1, 2, 3, 4]);
+
+    let mut buf = [2, 0];
+    I2cMaster::write_read(ADDRESS, &mut buf, 1, 2).unwrap();
+    assert_eq!(buf, [3, 4]);
+
+    assert_eq!(
+        I2cMaster::write_read(ADDRESS, &mut buf, 1, 3),
+        Err(Error::Size)
+    );
+}
+
+#[test]
+fn nack() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::I2cMaster::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(I2cMaster::read(ADDRESS, &mut [0; 2]), Err(Error::Nack));
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::I2cMaster::new();
+    kernel.add_driver(&driver);
+    driver.add_device(ADDRESS, &[0; 4]);
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRw {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_rw::BUFFER,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::COMPLETE,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::WRITE,
+        argument0: ADDRESS as u32,
+        argument1: 2,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+
+    assert_eq!(
+        I2cMaster::write(ADDRESS, &mut [0, 1]),
+        Err(Error::Other(ErrorCode::Busy))
+    );
+}
+
+#[test]
+fn error_conversion() {
+    assert_eq!(Error::from(ErrorCode::NoAck), Error::Nack);
+    assert_eq!(Error::from(ErrorCode::Reserve), Error::ArbitrationLost);
+    assert_eq!(Error::from(ErrorCode::Size), Error::Size);
+    assert_eq!(Error::from(ErrorCode::Fail), Error::Other(ErrorCode::Fail));
+
+    use embedded_hal::i2c::Error as _;
+    assert_eq!(
+        Error::Nack.kind(),
+        ErrorKind::NoAcknowledge(NoAcknowledgeSource::Unknown)
+    );
+    assert_eq!(Error::ArbitrationLost.kind(), ErrorKind::ArbitrationLoss);
+}
+
+#[test]
+fn hal_transaction() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::I2cMaster::new();
+    kernel.add_driver(&driver);
+    driver.add_device(ADDRESS, &[1, 2, 3, 4]);
+    let mut bus = I2cBus::new();
+
+    bus.write(ADDRESS, &[0, 0xaa]).unwrap();
+    let mut read = [0; 2];
+    bus.write_read(ADDRESS, &[0], &mut read).unwrap();
+    assert_eq!(read, [0xaa, 2]);
+
+    let (mut first, mut second) = ([0; 1], [0; 1]);
+    bus.transaction(
+        ADDRESS,
+        &mut [
+            Operation::Write(&[3]),
+            Operation::Read(&mut first),
+            Operation::Read(&mut second),
+        ],
+    )
+    .unwrap();
+    assert_eq!((first, second), ([4], [0xaa]));
+
+    // Larger than the bus' internal buffer.
+    assert_eq!(bus.write(ADDRESS, &[0; 5]), Err(Error::Size));
+}
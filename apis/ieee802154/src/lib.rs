@@ -0,0 +1,239 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform as platform;
+use libtock_platform::allow_ro::AllowRo;
+use libtock_platform::allow_rw::AllowRw;
+use libtock_platform::share;
+use libtock_platform::subscribe::Subscribe;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+mod rx;
+
+pub use rx::{Address, Frame, RxRingBuffer, FRAME_META_LEN, MAX_FRAME_LEN, RX_FRAME_SLOT_LEN};
+
+/// The IEEE 802.15.4 radio driver.
+///
+/// The kernel builds the MAC header of transmitted frames from the radio's
+/// configuration (PAN ID, short and long addresses), so apps only provide the
+/// destination and payload. Configuration changes take effect once committed
+/// with `commit_config`.
+///
+/// Received frames are written by the kernel into an [`RxRingBuffer`], from
+/// which the app pops them once `receive` returns.
+///
+/// # Example
+/// ```ignore
+/// use libtock::ieee802154::{Ieee802154, RxRingBuffer};
+///
+/// Ieee802154::radio_on()?;
+/// Ieee802154::set_pan(0xabcd)?;
+/// Ieee802154::set_short_address(0x0001)?;
+/// Ieee802154::set_channel(26)?;
+/// Ieee802154::commit_config()?;
+///
+/// let acked = Ieee802154::transmit(0x0002, b"hello")?;
+///
+/// let mut frames = RxRingBuffer::<4>::new();
+/// Ieee802154::receive(&mut frames)?;
+/// while let Some(frame) = frames.pop() {
+///     // make use of frame.payload()
+/// }
+/// ```
+pub struct Ieee802154<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> Ieee802154<S, C> {
+    /// Run a check against the IEEE 802.15.4 capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Returns `true` if the radio is on.
+    pub fn is_on() -> bool {
+        S::command(DRIVER_NUM, command::IS_ON, 0, 0).is_success()
+    }
+
+    pub fn radio_on() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::TURN_ON, 0, 0).to_result()
+    }
+
+    pub fn radio_off() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::TURN_OFF, 0, 0).to_result()
+    }
+
+    pub fn set_short_address(address: u16) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::SET_SHORT_ADDRESS, address.into(), 0).to_result()
+    }
+
+    pub fn set_long_address(address: u64) -> Result<(), ErrorCode> {
+        let (low, high) = (address as u32, (address >> 32) as u32);
+        S::command(DRIVER_NUM, command::SET_LONG_ADDRESS, low, high).to_result()
+    }
+
+    pub fn set_pan(pan: u16) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::SET_PAN, pan.into(), 0).to_result()
+    }
+
+    /// Sets the channel, from 11 to 26.
+    pub fn set_channel(channel: u8) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::SET_CHANNEL, channel.into(), 0).to_result()
+    }
+
+    /// Sets the transmit power, in dBm.
+    pub fn set_tx_power(power: i8) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::SET_TX_POWER, power as u32, 0).to_result()
+    }
+
+    /// Applies the configuration changes made since the last commit.
+    pub fn commit_config() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::COMMIT_CONFIG, 0, 0).to_result()
+    }
+
+    pub fn get_short_address() -> Result<u16, ErrorCode> {
+        S::command(DRIVER_NUM, command::GET_SHORT_ADDRESS, 0, 0)
+            .to_result()
+            .map(|address: u32| address as u16)
+    }
+
+    pub fn get_long_address() -> Result<u64, ErrorCode> {
+        S::command(DRIVER_NUM, command::GET_LONG_ADDRESS, 0, 0).to_result()
+    }
+
+    pub fn get_pan() -> Result<u16, ErrorCode> {
+        S::command(DRIVER_NUM, command::GET_PAN, 0, 0)
+            .to_result()
+            .map(|pan: u32| pan as u16)
+    }
+
+    pub fn get_channel() -> Result<u8, ErrorCode> {
+        S::command(DRIVER_NUM, command::GET_CHANNEL, 0, 0)
+            .to_result()
+            .map(|channel: u32| channel as u8)
+    }
+
+    pub fn get_tx_power() -> Result<i8, ErrorCode> {
+        S::command(DRIVER_NUM, command::GET_TX_POWER, 0, 0)
+            .to_result()
+            .map(|power: u32| power as i8)
+    }
+
+    /// Transmits `payload` in a data frame to the device with short address
+    /// `destination` (0xffff to broadcast), and waits until it is sent.
+    ///
+    /// Returns whether the destination acknowledged the frame. Broadcast
+    /// frames are never acknowledged.
+    pub fn transmit(destination: u16, payload: &[u8]) -> Result<bool, ErrorCode> {
+        let called = Cell::new(Option::<(u32, u32)>::None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, { allow_ro::WRITE }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::FRAME_TRANSMITTED }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<C, DRIVER_NUM, { allow_ro::WRITE }>(allow_ro, payload)?;
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::FRAME_TRANSMITTED }>(
+                subscribe, &called,
+            )?;
+
+            // When this fails, `called` is guaranteed unmodified,
+            // because upcalls are never processed until we call `yield`.
+            S::command(DRIVER_NUM, command::TRANSMIT, destination.into(), 0)
+                .to_result::<(), ErrorCode>()?;
+
+            loop {
+                S::yield_wait();
+                if let Some((status, acked)) = called.get() {
+                    return match status {
+                        0 => Ok(acked != 0),
+                        e_status => Err(e_status.try_into().unwrap_or(ErrorCode::Fail)),
+                    };
+                }
+            }
+        })
+    }
+
+    /// Shares `frames` with the kernel and waits until it receives a frame.
+    /// Frames already in `frames` are kept, and more than one frame may have
+    /// been received by the time this returns.
+    pub fn receive<const N: usize>(frames: &mut RxRingBuffer<N>) -> Result<(), ErrorCode> {
+        let called = Cell::new(false);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, { allow_rw::READ }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::FRAME_RECEIVED }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            // Subscribe first, so no frame arrives before we are listening.
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::FRAME_RECEIVED }>(subscribe, &called)?;
+            S::allow_rw::<C, DRIVER_NUM, { allow_rw::READ }>(allow_rw, frames.as_mut_bytes())?;
+
+            while !called.get() {
+                S::yield_wait();
+            }
+            Ok(())
+        })
+    }
+}
+
+/// System call configuration trait for `Ieee802154`.
+pub trait Config:
+    platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config
+{
+}
+impl<T: platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config>
+    Config for T
+{
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x30001;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const IS_ON: u32 = 1;
+    pub const SET_SHORT_ADDRESS: u32 = 2;
+    pub const SET_LONG_ADDRESS: u32 = 3;
+    pub const SET_PAN: u32 = 4;
+    pub const SET_CHANNEL: u32 = 5;
+    pub const SET_TX_POWER: u32 = 6;
+    pub const COMMIT_CONFIG: u32 = 7;
+    pub const GET_SHORT_ADDRESS: u32 = 8;
+    pub const GET_LONG_ADDRESS: u32 = 9;
+    pub const GET_PAN: u32 = 10;
+    pub const GET_CHANNEL: u32 = 11;
+    pub const GET_TX_POWER: u32 = 12;
+    pub const TRANSMIT: u32 = 26;
+    pub const TURN_ON: u32 = 27;
+    pub const TURN_OFF: u32 = 28;
+}
+
+mod subscribe {
+    pub const FRAME_RECEIVED: u32 = 0;
+    pub const FRAME_TRANSMITTED: u32 = 1;
+}
+
+mod allow_ro {
+    pub const WRITE: u32 = 0;
+}
+
+mod allow_rw {
+    pub const READ: u32 = 0;
+}
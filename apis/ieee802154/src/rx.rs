@@ -0,0 +1,188 @@
+//! The buffer received frames are written into, and the parsing of those
+//! frames.
+
+/// The maximum length of an IEEE 802.15.4 frame, in bytes.
+pub const MAX_FRAME_LEN: usize = 127;
+
+/// The length of the metadata the kernel stores before each received frame:
+/// the lengths of its MAC header, payload and MIC, one byte each.
+pub const FRAME_META_LEN: usize = 3;
+
+/// The space each received frame takes in an [`RxRingBuffer`].
+pub const RX_FRAME_SLOT_LEN: usize = FRAME_META_LEN + MAX_FRAME_LEN;
+
+/// A ring buffer of `N` frame slots, shared with the kernel by
+/// `Ieee802154::receive`.
+///
+/// Its first two bytes are the index of the next slot to read, which the app
+/// advances, and the index of the next slot to write, which the kernel
+/// advances. One slot is always kept empty to tell a full buffer from an
+/// empty one, so the buffer holds up to `N - 1` frames; the kernel drops
+/// frames received while it is full. `N` must be between 2 and 256, which is
+/// checked at compile time.
+#[repr(C)]
+pub struct RxRingBuffer<const N: usize> {
+    indices: [u8; 2],
+    slots: [[u8; RX_FRAME_SLOT_LEN]; N],
+}
+
+impl<const N: usize> RxRingBuffer<N> {
+    // The indices are single bytes, and one slot is always kept empty.
+    const _CHECK: () = assert!(N >= 2 && N <= 256, "RxRingBuffer needs 2 to 256 slots");
+
+    #[allow(clippy::let_unit_value)]
+    pub const fn new() -> RxRingBuffer<N> {
+        let () = Self::_CHECK;
+        RxRingBuffer {
+            indices: [0; 2],
+            slots: [[0; RX_FRAME_SLOT_LEN]; N],
+        }
+    }
+
+    /// Removes and returns the oldest frame received, if any.
+    pub fn pop(&mut self) -> Option<Frame<'_>> {
+        let [read, write] = self.indices;
+        if read == write || read as usize >= N {
+            return None;
+        }
+        self.indices[0] = ((read as usize + 1) % N) as u8;
+
+        let slot = &self.slots[read as usize];
+        let (meta, data) = slot.split_at(FRAME_META_LEN);
+        let header_len = meta[0] as usize;
+        let payload_len = meta[1] as usize;
+        let header = data.get(..header_len).unwrap_or(&[]);
+        let payload = data
+            .get(header_len..header_len + payload_len)
+            .unwrap_or(&[]);
+        Some(Frame::parse(header, payload))
+    }
+
+    /// Returns the buffer as shared with the kernel.
+    pub(crate) fn as_mut_bytes(&mut self) -> &mut [u8] {
+        let len = core::mem::size_of::<Self>();
+        // Safety: `RxRingBuffer` is `repr(C)` and contains only `u8` arrays, so
+        // it has no padding, and every byte pattern is valid for it.
+        unsafe { core::slice::from_raw_parts_mut(self as *mut Self as *mut u8, len) }
+    }
+}
+
+impl<const N: usize> Default for RxRingBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A device address.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Address {
+    Short(u16),
+    Long(u64),
+}
+
+/// A received frame.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Frame<'a> {
+    header: &'a [u8],
+    payload: &'a [u8],
+    pan: Option<u16>,
+    destination: Option<Address>,
+    source: Option<Address>,
+}
+
+impl<'a> Frame<'a> {
+    /// Returns the frame's MAC header.
+    pub fn header(&self) -> &'a [u8] {
+        self.header
+    }
+
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    /// Returns the sequence number from the MAC header, if it could be
+    /// parsed.
+    pub fn sequence_number(&self) -> Option<u8> {
+        self.header.get(2).copied()
+    }
+
+    /// Returns the destination PAN ID, or the source PAN ID if the frame has
+    /// no destination address.
+    pub fn pan(&self) -> Option<u16> {
+        self.pan
+    }
+
+    pub fn destination(&self) -> Option<Address> {
+        self.destination
+    }
+
+    pub fn source(&self) -> Option<Address> {
+        self.source
+    }
+
+    // Parses the addressing fields of `header`. Fields that cannot be parsed
+    // are left out.
+    pub(crate) fn parse(header: &'a [u8], payload: &'a [u8]) -> Frame<'a> {
+        let mut frame = Frame {
+            header,
+            payload,
+            pan: None,
+            destination: None,
+            source: None,
+        };
+        let frame_control = match header.get(..2) {
+            Some(bytes) => u16::from_le_bytes([bytes[0], bytes[1]]),
+            None => return frame,
+        };
+        let pan_id_compression = frame_control & 1 << 6 != 0;
+        let destination_mode = frame_control >> 10 & 0b11;
+        let source_mode = frame_control >> 14 & 0b11;
+
+        // Skip the frame control field and the sequence number.
+        let mut fields = Fields(header.get(3..).unwrap_or(&[]));
+        if destination_mode != 0 {
+            frame.pan = fields.u16();
+            frame.destination = fields.address(destination_mode);
+        }
+        if source_mode != 0 {
+            if !pan_id_compression || destination_mode == 0 {
+                let pan = fields.u16();
+                frame.pan = frame.pan.or(pan);
+            }
+            frame.source = fields.address(source_mode);
+        }
+        frame
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+// The fields of a MAC header that remain to be parsed.
+struct Fields<'a>(&'a [u8]);
+
+impl<'a> Fields<'a> {
+    fn take<const LEN: usize>(&mut self) -> Option<[u8; LEN]> {
+        let field = self.0.get(..LEN)?;
+        self.0 = &self.0[LEN..];
+        field.try_into().ok()
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take().map(u16::from_le_bytes)
+    }
+
+    fn address(&mut self, mode: u16) -> Option<Address> {
+        match mode {
+            SHORT_ADDRESS_MODE => self.u16().map(Address::Short),
+            LONG_ADDRESS_MODE => self
+                .take()
+                .map(|bytes| Address::Long(u64::from_le_bytes(bytes))),
+            _ => None,
+        }
+    }
+}
+
+const SHORT_ADDRESS_MODE: u16 = 0b10;
+const LONG_ADDRESS_MODE: u16 = 0b11;
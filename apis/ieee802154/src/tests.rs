@@ -0,0 +1,186 @@
+use super::*;
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type Ieee802154 = super::Ieee802154<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Ieee802154::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ieee802154::new();
+    kernel.add_driver(&driver);
+
+    assert!(Ieee802154::driver_check());
+}
+
+#[test]
+fn configuration() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ieee802154::new();
+    kernel.add_driver(&driver);
+
+    assert!(!Ieee802154::is_on());
+    assert_eq!(Ieee802154::radio_on(), Ok(()));
+    assert!(Ieee802154::is_on());
+    assert_eq!(Ieee802154::radio_off(), Ok(()));
+    assert!(!driver.is_on());
+
+    assert_eq!(Ieee802154::set_short_address(0x1234), Ok(()));
+    assert_eq!(Ieee802154::set_long_address(0x0102_0304_0506_0708), Ok(()));
+    assert_eq!(Ieee802154::set_pan(0xabcd), Ok(()));
+    assert_eq!(Ieee802154::set_channel(11), Ok(()));
+    assert_eq!(Ieee802154::set_channel(10), Err(ErrorCode::Invalid));
+    assert_eq!(Ieee802154::set_tx_power(-8), Ok(()));
+    assert_eq!(Ieee802154::commit_config(), Ok(()));
+    assert_eq!(driver.commits(), 1);
+
+    assert_eq!(Ieee802154::get_short_address(), Ok(0x1234));
+    assert_eq!(Ieee802154::get_long_address(), Ok(0x0102_0304_0506_0708));
+    assert_eq!(Ieee802154::get_pan(), Ok(0xabcd));
+    assert_eq!(Ieee802154::get_channel(), Ok(11));
+    assert_eq!(Ieee802154::get_tx_power(), Ok(-8));
+}
+
+#[test]
+fn transmit() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ieee802154::new();
+    kernel.add_driver(&driver);
+    let peer = fake::Ieee802154Peer::new(0xabcd, 0x0002, 26);
+    driver.connect(&peer);
+
+    assert_eq!(Ieee802154::transmit(0x0002, b"hello"), Err(ErrorCode::Off));
+    assert_eq!(Ieee802154::radio_on(), Ok(()));
+    assert_eq!(Ieee802154::set_pan(0xabcd), Ok(()));
+    assert_eq!(Ieee802154::set_short_address(0x0001), Ok(()));
+
+    assert_eq!(Ieee802154::transmit(0x0002, b"hello"), Ok(true));
+    assert_eq!(Ieee802154::transmit(0xffff, b"all"), Ok(false));
+    peer.set_acknowledge(false);
+    assert_eq!(Ieee802154::transmit(0x0002, b"lost"), Ok(false));
+    assert_eq!(Ieee802154::transmit(0x0003, b"nobody"), Ok(false));
+    assert_eq!(
+        Ieee802154::transmit(0x0002, &[0; MAX_FRAME_LEN]),
+        Err(ErrorCode::Size)
+    );
+
+    let received = peer.take_received();
+    assert_eq!(received.len(), 3);
+    assert_eq!(received[0].payload, b"hello");
+    assert_eq!(received[1].payload, b"all");
+    assert_eq!(received[2].payload, b"lost");
+}
+
+#[test]
+fn receive() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ieee802154::new();
+    kernel.add_driver(&driver);
+    let peer = fake::Ieee802154Peer::new(0xabcd, 0x0002, 26);
+    driver.connect(&peer);
+    assert_eq!(Ieee802154::radio_on(), Ok(()));
+    assert_eq!(Ieee802154::set_pan(0xabcd), Ok(()));
+    assert_eq!(Ieee802154::set_short_address(0x0001), Ok(()));
+
+    assert!(driver.send_from(&peer, 0x0001, b"one"));
+    assert!(driver.send_from(&peer, 0xffff, b"two"));
+    assert!(driver.send_from(&peer, 0x0001, b"three"));
+    assert!(!driver.send_from(&peer, 0x0005, b"other"));
+
+    // The buffer has room for two frames at a time.
+    let mut frames = RxRingBuffer::<3>::new();
+    assert_eq!(Ieee802154::receive(&mut frames), Ok(()));
+    let frame = frames.pop().unwrap();
+    assert_eq!(frame.payload(), b"one");
+    assert_eq!(frame.sequence_number(), Some(0));
+    assert_eq!(frame.pan(), Some(0xabcd));
+    assert_eq!(frame.destination(), Some(Address::Short(0x0001)));
+    assert_eq!(frame.source(), Some(Address::Short(0x0002)));
+    let frame = frames.pop().unwrap();
+    assert_eq!(frame.payload(), b"two");
+    assert_eq!(frame.destination(), Some(Address::Short(0xffff)));
+    assert_eq!(frames.pop(), None);
+
+    assert_eq!(Ieee802154::receive(&mut frames), Ok(()));
+    assert_eq!(
+        frames.pop().map(|frame| frame.payload()),
+        Some(&b"three"[..])
+    );
+    assert_eq!(frames.pop(), None);
+}
+
+// Loops frames between the app and a peer that echoes them back.
+#[test]
+fn echo() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ieee802154::new();
+    kernel.add_driver(&driver);
+    let peer = fake::Ieee802154Peer::new(0xffff, 0x0002, 26);
+    driver.connect(&peer);
+    assert_eq!(Ieee802154::radio_on(), Ok(()));
+    assert_eq!(Ieee802154::set_long_address(0x1122_3344_5566_7788), Ok(()));
+
+    let mut frames = RxRingBuffer::<2>::new();
+    for message in [&b"ping"[..], b"pong", b""] {
+        assert_eq!(Ieee802154::transmit(0x0002, message), Ok(true));
+        for frame in peer.take_received() {
+            assert!(driver.send_from(&peer, 0xffff, &frame.payload));
+        }
+        assert_eq!(Ieee802154::receive(&mut frames), Ok(()));
+        assert_eq!(frames.pop().map(|frame| frame.payload()), Some(message));
+    }
+}
+
+#[test]
+fn frame_parsing() {
+    // A frame without PAN ID compression, from a long address.
+    let header = [
+        0x01, 0xc8, 9, 0xcd, 0xab, 0x02, 0x00, 0x34, 0x12, 1, 2, 3, 4, 5, 6, 7, 8,
+    ];
+    let frame = Frame::parse(&header, b"data");
+    assert_eq!(frame.header(), header);
+    assert_eq!(frame.payload(), b"data");
+    assert_eq!(frame.sequence_number(), Some(9));
+    assert_eq!(frame.pan(), Some(0xabcd));
+    assert_eq!(frame.destination(), Some(Address::Short(0x0002)));
+    assert_eq!(frame.source(), Some(Address::Long(0x0807_0605_0403_0201)));
+
+    // A truncated header.
+    let frame = Frame::parse(&header[..6], b"");
+    assert_eq!(frame.pan(), Some(0xabcd));
+    assert_eq!(frame.destination(), None);
+    assert_eq!(frame.source(), None);
+    assert_eq!(Frame::parse(&[], b"").sequence_number(), None);
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ieee802154::new();
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_ro::WRITE,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::FRAME_TRANSMITTED,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::TRANSMIT,
+        argument0: 0x0002,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+
+    assert_eq!(Ieee802154::transmit(0x0002, b"hello"), Err(ErrorCode::Busy));
+}
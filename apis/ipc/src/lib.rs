@@ -0,0 +1,179 @@
+#![no_std]
+
+use libtock_platform as platform;
+use libtock_platform::allow_ro::AllowRo;
+use libtock_platform::allow_rw::AllowRw;
+use libtock_platform::share::{self, Handle};
+use libtock_platform::subscribe::{OneId, Subscribe};
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls, Upcall};
+
+/// The inter-process communication driver.
+///
+/// A process offers a service under its package name by registering a
+/// service listener. Clients find it with `discover`, which returns its
+/// process ID, then share a buffer with it and notify it. The service is
+/// passed the client's buffer when notified, and may notify the client back.
+///
+/// Process IDs are used as subscribe and allow numbers, so they must be known
+/// at compile time to register client listeners and share buffers.
+///
+/// # Example
+/// ```ignore
+/// use libtock::ipc::{Ipc, IpcListener};
+///
+/// // The "org.tock.rng" service has process ID 1.
+/// assert_eq!(Ipc::discover("org.tock.rng"), Ok(1));
+/// let mut buffer = [0; 16];
+/// let listener = IpcListener(|service, _buffer| {
+///     // the service filled `buffer`
+/// });
+/// share::scope::<(AllowRw<_, 0x10000, 1>, Subscribe<_, 0x10000, 1>), _, _>(|handle| {
+///     let (allow_rw, subscribe) = handle.split();
+///     Ipc::share(&mut buffer, allow_rw)?;
+///     Ipc::register_client_listener(&listener, subscribe)?;
+///     Ipc::notify_service(1)?;
+///     // yield
+/// });
+/// ```
+pub struct Ipc<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> Ipc<S, C> {
+    /// Run a check against the IPC driver to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Returns the process ID of the service with the package name `name`.
+    pub fn discover(name: &str) -> Result<u32, ErrorCode> {
+        share::scope::<AllowRo<_, DRIVER_NUM, { allow_ro::SEARCH }>, _, _>(|handle| {
+            S::allow_ro::<C, DRIVER_NUM, { allow_ro::SEARCH }>(handle, name.as_bytes())?;
+            S::command(DRIVER_NUM, command::DISCOVER, 0, 0).to_result()
+        })
+    }
+
+    /// Registers `listener` to be called when a client notifies this process's
+    /// service, with the client's process ID and the buffer it shared.
+    pub fn register_service_listener<'share, F: Fn(u32, SharedBuffer)>(
+        listener: &'share IpcListener<F>,
+        subscribe: Handle<Subscribe<'share, S, DRIVER_NUM, SERVICE_UPCALL>>,
+    ) -> Result<(), ErrorCode> {
+        S::subscribe::<_, _, C, DRIVER_NUM, SERVICE_UPCALL>(subscribe, listener)
+    }
+
+    /// Registers `listener` to be called when the service with process ID
+    /// `SERVICE` notifies this process, with the service's process ID and the
+    /// buffer shared with it.
+    pub fn register_client_listener<'share, const SERVICE: u32, F: Fn(u32, SharedBuffer)>(
+        listener: &'share IpcListener<F>,
+        subscribe: Handle<Subscribe<'share, S, DRIVER_NUM, SERVICE>>,
+    ) -> Result<(), ErrorCode> {
+        S::subscribe::<_, _, C, DRIVER_NUM, SERVICE>(subscribe, listener)
+    }
+
+    /// Shares `buffer` with the service with process ID `SERVICE`, which may
+    /// read and write it when notified.
+    pub fn share<'share, const SERVICE: u32>(
+        buffer: &'share mut [u8],
+        allow_rw: Handle<AllowRw<'share, S, DRIVER_NUM, SERVICE>>,
+    ) -> Result<(), ErrorCode> {
+        S::allow_rw::<C, DRIVER_NUM, SERVICE>(allow_rw, buffer)
+    }
+
+    /// Notifies the service with process ID `service`.
+    pub fn notify_service(service: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::NOTIFY_SERVICE, service, 0).to_result()
+    }
+
+    /// Notifies the client with process ID `client`.
+    pub fn notify_client(client: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::NOTIFY_CLIENT, client, 0).to_result()
+    }
+}
+
+/// A buffer a client shared with a service, as passed to an [`IpcListener`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SharedBuffer {
+    /// The address of the buffer, or 0 if none was shared.
+    pub address: u32,
+    pub len: usize,
+}
+
+impl SharedBuffer {
+    /// Returns the buffer, or `None` if none was shared.
+    ///
+    /// # Safety
+    /// The buffer must not be accessed in any other way while the returned
+    /// slice is in use, and the slice must not be used after the client
+    /// stops sharing it.
+    pub unsafe fn as_mut_slice<'a>(&self) -> Option<&'a mut [u8]> {
+        match self.address {
+            0 => None,
+            address => Some(core::slice::from_raw_parts_mut(
+                address as usize as *mut u8,
+                self.len,
+            )),
+        }
+    }
+}
+
+/// A wrapper around a closure to be registered and called when another
+/// process sends a notification. It is passed the process ID of the notifier
+/// and the shared buffer.
+///
+/// ```ignore
+/// let listener = IpcListener(|client, buffer| {
+///     // handle the client's request
+/// });
+/// ```
+pub struct IpcListener<F: Fn(u32, SharedBuffer)>(pub F);
+
+impl<F: Fn(u32, SharedBuffer), const ID: u32> Upcall<OneId<DRIVER_NUM, ID>> for IpcListener<F> {
+    fn upcall(&self, process_id: u32, len: u32, address: u32) {
+        self.0(
+            process_id,
+            SharedBuffer {
+                address,
+                len: len as usize,
+            },
+        )
+    }
+}
+
+/// System call configuration trait for `Ipc`.
+pub trait Config:
+    platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config
+{
+}
+impl<T: platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config>
+    Config for T
+{
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x10000;
+
+// Client listeners use the service's process ID as subscribe number.
+const SERVICE_UPCALL: u32 = 0;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const DISCOVER: u32 = 1;
+    pub const NOTIFY_SERVICE: u32 = 2;
+    pub const NOTIFY_CLIENT: u32 = 3;
+}
+
+mod allow_ro {
+    pub const SEARCH: u32 = 0;
+}
@@ -0,0 +1,111 @@
+use core::cell::Cell;
+
+use super::*;
+use libtock_platform::{share, ErrorCode, YieldNoWaitReturn};
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type Ipc = super::Ipc<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Ipc::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ipc::new();
+    kernel.add_driver(&driver);
+
+    assert!(Ipc::driver_check());
+}
+
+#[test]
+fn discover() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ipc::new();
+    kernel.add_driver(&driver);
+    driver.add_process("org.tock.first");
+    driver.add_process("org.tock.second");
+
+    assert_eq!(Ipc::discover("org.tock.second"), Ok(2));
+    assert_eq!(Ipc::discover("org.tock.first"), Ok(1));
+    assert_eq!(Ipc::discover("org.tock"), Err(ErrorCode::Invalid));
+}
+
+#[test]
+fn client() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ipc::new();
+    kernel.add_driver(&driver);
+    let service = driver.add_process("org.tock.service");
+
+    let notified = Cell::new(None);
+    let listener = IpcListener(|service, buffer| notified.set(Some((service, buffer))));
+    let mut buffer = [0; 4];
+    share::scope::<(AllowRw<_, DRIVER_NUM, 1>, Subscribe<_, DRIVER_NUM, 1>), _, _>(|handle| {
+        let (allow_rw, subscribe) = handle.split();
+        assert_eq!(Ipc::share(&mut buffer, allow_rw), Ok(()));
+        assert_eq!(Ipc::register_client_listener(&listener, subscribe), Ok(()));
+        assert_eq!(Ipc::notify_service(1), Ok(()));
+        assert_eq!(
+            service.take_notifications(),
+            [fake::IpcNotification::Service]
+        );
+        assert_eq!(Ipc::notify_service(2), Err(ErrorCode::Invalid));
+
+        driver.write_shared_with(&service, b"done");
+        driver.notify_client_from(&service);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+    });
+    let (service_id, shared) = notified.get().unwrap();
+    assert_eq!(service_id, 1);
+    assert_eq!(shared.len, 4);
+    // The fake does not pass addresses.
+    assert_eq!(unsafe { shared.as_mut_slice() }, None);
+    assert_eq!(buffer, *b"done");
+}
+
+#[test]
+fn service() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ipc::new();
+    kernel.add_driver(&driver);
+    let client = driver.add_process("org.tock.client");
+    client.share(&[0; 8]);
+
+    let notified = Cell::new(None);
+    let listener =
+        IpcListener(|client, buffer: SharedBuffer| notified.set(Some((client, buffer.len))));
+    share::scope(|subscribe| {
+        assert_eq!(Ipc::register_service_listener(&listener, subscribe), Ok(()));
+        driver.notify_service_from(&client);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+    });
+    assert_eq!(notified.get(), Some((1, 8)));
+
+    assert_eq!(Ipc::notify_client(1), Ok(()));
+    assert_eq!(client.take_notifications(), [fake::IpcNotification::Client]);
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ipc::new();
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_ro::SEARCH,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::DISCOVER,
+        argument0: 0,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+
+    assert_eq!(Ipc::discover("org.tock.service"), Err(ErrorCode::Busy));
+}
@@ -0,0 +1,201 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform as platform;
+use libtock_platform::allow_ro::AllowRo;
+use libtock_platform::allow_rw::AllowRw;
+use libtock_platform::share;
+use libtock_platform::subscribe::Subscribe;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+/// The key-value storage driver.
+///
+/// It allows a process to store values under arbitrary byte-string keys in the
+/// kernel's persistent key-value store. Keys are private to the process.
+///
+/// # Example
+/// ```ignore
+/// use libtock::kv::KeyValue;
+///
+/// KeyValue::set(b"boot_count", &[1, 0, 0, 0])?;
+///
+/// let mut value = [0; 4];
+/// let len = KeyValue::get(b"boot_count", &mut value)?;
+/// ```
+pub struct KeyValue<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> KeyValue<S, C> {
+    /// Run a check against the key-value capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Reads the value stored under `key` into `value`, and returns the length
+    /// of the value.
+    ///
+    /// Fails with `Error::Size` if the value is longer than `value`.
+    pub fn get(key: &[u8], value: &mut [u8]) -> Result<usize, Error> {
+        Self::operation(command::GET, key, &[], value, Error::NotFound)
+    }
+
+    /// Stores `value` under `key`, replacing any value already stored there.
+    pub fn set(key: &[u8], value: &[u8]) -> Result<(), Error> {
+        Self::operation(command::SET, key, value, &mut [], Error::NoSupport).map(|_| ())
+    }
+
+    /// Stores `value` under `key`.
+    ///
+    /// Fails with `Error::AlreadyExists` if `key` already has a value.
+    pub fn add(key: &[u8], value: &[u8]) -> Result<(), Error> {
+        Self::operation(command::ADD, key, value, &mut [], Error::AlreadyExists).map(|_| ())
+    }
+
+    /// Replaces the value stored under `key` with `value`.
+    ///
+    /// Fails with `Error::NotFound` if `key` has no value.
+    pub fn update(key: &[u8], value: &[u8]) -> Result<(), Error> {
+        Self::operation(command::UPDATE, key, value, &mut [], Error::NotFound).map(|_| ())
+    }
+
+    /// Removes `key` and its value from the store.
+    ///
+    /// Fails with `Error::NotFound` if `key` has no value.
+    pub fn delete(key: &[u8]) -> Result<(), Error> {
+        Self::operation(command::DELETE, key, &[], &mut [], Error::NotFound).map(|_| ())
+    }
+}
+
+/// A key-value storage error.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The key has no value.
+    NotFound,
+    /// `add` was called on a key that already has a value.
+    AlreadyExists,
+    /// The key or value is too large for the store, or the value is too large
+    /// for the buffer passed to `get`.
+    Size,
+    /// The store does not have room for the value.
+    Full,
+    /// The kernel does not support the operation.
+    NoSupport,
+    /// Any other failure reported by the kernel.
+    Other(ErrorCode),
+}
+
+impl From<ErrorCode> for Error {
+    fn from(error: ErrorCode) -> Error {
+        match error {
+            ErrorCode::Size => Error::Size,
+            ErrorCode::NoMem => Error::Full,
+            ErrorCode::NoSupport => Error::NoSupport,
+            error => Error::Other(error),
+        }
+    }
+}
+
+/// System call configuration trait for `KeyValue`.
+pub trait Config:
+    platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config
+{
+}
+impl<T: platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config>
+    Config for T
+{
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls, C: Config> KeyValue<S, C> {
+    // Performs a key-value operation and returns the value length reported by
+    // the kernel.
+    //
+    // The kernel reports both missing keys and keys that already exist as
+    // `NoSupport` in the completion upcall, so the caller passes the error
+    // that upcall means for this operation as `key_error`. A `NoSupport`
+    // returned by the command itself still means the operation is
+    // unsupported.
+    fn operation(
+        command_id: u32,
+        key: &[u8],
+        input: &[u8],
+        output: &mut [u8],
+        key_error: Error,
+    ) -> Result<usize, Error> {
+        let called = Cell::new(Option::<(u32, u32)>::None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, { allow_ro::KEY }>,
+                AllowRo<_, DRIVER_NUM, { allow_ro::INPUT }>,
+                AllowRw<_, DRIVER_NUM, { allow_rw::OUTPUT }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::COMPLETE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_key, allow_input, allow_output, subscribe) = handle.split();
+            S::allow_ro::<C, DRIVER_NUM, { allow_ro::KEY }>(allow_key, key)?;
+            if !input.is_empty() {
+                S::allow_ro::<C, DRIVER_NUM, { allow_ro::INPUT }>(allow_input, input)?;
+            }
+            if !output.is_empty() {
+                S::allow_rw::<C, DRIVER_NUM, { allow_rw::OUTPUT }>(allow_output, output)?;
+            }
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::COMPLETE }>(subscribe, &called)?;
+
+            S::command(DRIVER_NUM, command_id, 0, 0).to_result::<(), ErrorCode>()?;
+
+            loop {
+                S::yield_wait();
+                if let Some((status, len)) = called.get() {
+                    return match status {
+                        0 => Ok(len as usize),
+                        e_status => match e_status.try_into().unwrap_or(ErrorCode::Fail) {
+                            ErrorCode::NoSupport => Err(key_error),
+                            error_code => Err(error_code.into()),
+                        },
+                    };
+                }
+            }
+        })
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x50003;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const GET: u32 = 1;
+    pub const SET: u32 = 2;
+    pub const DELETE: u32 = 3;
+    pub const ADD: u32 = 4;
+    pub const UPDATE: u32 = 5;
+}
+
+mod subscribe {
+    pub const COMPLETE: u32 = 0;
+}
+
+mod allow_ro {
+    pub const KEY: u32 = 0;
+    pub const INPUT: u32 = 1;
+}
+
+mod allow_rw {
+    pub const OUTPUT: u32 = 0;
+}
@@ -0,0 +1,111 @@
+use super::*;
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type KeyValue = super::KeyValue<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!KeyValue::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::KeyValue::new(64);
+    kernel.add_driver(&driver);
+
+    assert!(KeyValue::driver_check());
+}
+
+#[test]
+fn set_get() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::KeyValue::new(64);
+    kernel.add_driver(&driver);
+
+    assert_eq!(KeyValue::set(b"name", b"tock"), Ok(()));
+    assert_eq!(driver.get(b"name").unwrap(), b"tock");
+
+    let mut value = [0; 8];
+    assert_eq!(KeyValue::get(b"name", &mut value), Ok(4));
+    assert_eq!(&value[..4], b"tock");
+
+    assert_eq!(KeyValue::set(b"name", b"libtock"), Ok(()));
+    assert_eq!(KeyValue::get(b"name", &mut value), Ok(7));
+    assert_eq!(&value[..7], b"libtock");
+}
+
+#[test]
+fn get_errors() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::KeyValue::new(64);
+    kernel.add_driver(&driver);
+    driver.insert(b"long", b"0123456789");
+
+    let mut value = [0; 4];
+    assert_eq!(KeyValue::get(b"missing", &mut value), Err(Error::NotFound));
+    assert_eq!(KeyValue::get(b"long", &mut value), Err(Error::Size));
+}
+
+#[test]
+fn add_update_delete() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::KeyValue::new(64);
+    kernel.add_driver(&driver);
+
+    assert_eq!(KeyValue::update(b"key", b"1"), Err(Error::NotFound));
+    assert_eq!(KeyValue::add(b"key", b"1"), Ok(()));
+    assert_eq!(KeyValue::add(b"key", b"2"), Err(Error::AlreadyExists));
+    assert_eq!(KeyValue::update(b"key", b"3"), Ok(()));
+    assert_eq!(driver.get(b"key").unwrap(), b"3");
+
+    assert_eq!(KeyValue::delete(b"key"), Ok(()));
+    assert_eq!(driver.get(b"key"), None);
+    assert_eq!(KeyValue::delete(b"key"), Err(Error::NotFound));
+}
+
+#[test]
+fn full() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::KeyValue::new(8);
+    kernel.add_driver(&driver);
+
+    assert_eq!(KeyValue::set(b"a", b"1234"), Ok(()));
+    assert_eq!(KeyValue::set(b"b", b"1234"), Err(Error::Full));
+    assert_eq!(driver.get(b"b"), None);
+}
+
+// A `NoSupport` returned by the command itself means the operation is not
+// supported, not that the key is missing.
+#[test]
+fn command_no_support() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::KeyValue::new(64);
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_ro::KEY,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_ro::INPUT,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::COMPLETE,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::UPDATE,
+        argument0: 0,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::NoSupport)),
+    });
+
+    assert_eq!(KeyValue::update(b"key", b"1"), Err(Error::NoSupport));
+}
@@ -0,0 +1,444 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_alarm::{Alarm, Milliseconds};
+use libtock_platform as platform;
+use libtock_platform::allow_ro::AllowRo;
+use libtock_platform::allow_rw::AllowRw;
+use libtock_platform::share;
+use libtock_platform::subscribe::Subscribe;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+mod sx1262;
+
+/// The LoRa radio driver.
+///
+/// The kernel does not drive the radio itself: it passes the SPI bus an
+/// SX1262 transceiver is on (driver 0x30003) and the radio's reset, busy and
+/// interrupt lines (driver 0x30004) through to a single process. This driver
+/// configures the radio over that passthrough using the SX1262 command set.
+///
+/// Every radio that talks to another must use the same frequency, spreading
+/// factor and bandwidth. Higher spreading factors and narrower bandwidths
+/// reach further, at lower data rates.
+///
+/// # Example
+/// ```ignore
+/// use libtock::lora::{Bandwidth, Lora};
+///
+/// Lora::init()?;
+/// Lora::set_frequency(868_100_000)?;
+/// Lora::set_modulation(9, Bandwidth::Khz125)?;
+///
+/// Lora::transmit(b"temperature=21")?;
+///
+/// let mut buffer = [0; 64];
+/// let packet = Lora::receive(&mut buffer)?;
+/// // make use of buffer[..packet.len], packet.rssi and packet.snr
+/// ```
+pub struct Lora<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> Lora<S, C> {
+    /// Run a check against the SPI and GPIO passthrough capsules to ensure
+    /// they are present.
+    ///
+    /// Returns `true` if both drivers were present. This does not necessarily
+    /// mean that the drivers are working, as they may still fail to allocate
+    /// grant memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(spi::DRIVER_NUM, spi::command::DRIVER_CHECK, 0, 0).is_success()
+            && S::command(gpio::DRIVER_NUM, gpio::command::COUNT, 0, 0).is_success_u32()
+    }
+
+    /// Resets the radio and puts it in LoRa mode. Call this once, before
+    /// configuring the radio; the frequency and modulation must then be set
+    /// before transmitting or receiving.
+    pub fn init() -> Result<(), ErrorCode> {
+        S::command(
+            spi::DRIVER_NUM,
+            spi::command::SET_CHIP_SELECT,
+            spi::CHIP_SELECT,
+            0,
+        )
+        .to_result::<(), ErrorCode>()?;
+        Self::gpio_command(gpio::command::ENABLE_OUTPUT, gpio::RESET, 0)?;
+        Self::gpio_command(gpio::command::ENABLE_INPUT, gpio::BUSY, gpio::PULL_NONE)?;
+        Self::gpio_command(gpio::command::ENABLE_INPUT, gpio::DIO1, gpio::PULL_NONE)?;
+
+        // The radio holds BUSY high until it has started up again, which
+        // `radio_command` waits for.
+        Self::gpio_command(gpio::command::CLEAR, gpio::RESET, 0)?;
+        Alarm::<S, C>::sleep_for(Milliseconds(1))?;
+        Self::gpio_command(gpio::command::SET, gpio::RESET, 0)?;
+
+        Self::radio_command(&[sx1262::SET_STANDBY, sx1262::STANDBY_RC])?;
+        Self::radio_command(&[sx1262::SET_PACKET_TYPE, sx1262::PACKET_TYPE_LORA])?;
+        // Both the transmitted and the received packet start at offset 0.
+        Self::radio_command(&[sx1262::SET_BUFFER_BASE_ADDRESS, 0, 0])?;
+        let [register_hi, register_lo] = sx1262::SYNC_WORD_REGISTER.to_be_bytes();
+        let [sync_hi, sync_lo] = sx1262::PRIVATE_SYNC_WORD;
+        Self::radio_command(&[
+            sx1262::WRITE_REGISTER,
+            register_hi,
+            register_lo,
+            sync_hi,
+            sync_lo,
+        ])?;
+        // Raises DIO1 on every interrupt `wait_for_irq` handles.
+        let [mask_hi, mask_lo] = IRQ_MASK.to_be_bytes();
+        Self::radio_command(&[
+            sx1262::SET_DIO_IRQ_PARAMS,
+            mask_hi,
+            mask_lo,
+            mask_hi,
+            mask_lo,
+            0,
+            0,
+            0,
+            0,
+        ])
+    }
+
+    /// Sets the carrier frequency, in hertz, from `MIN_FREQUENCY_HZ` to
+    /// `MAX_FREQUENCY_HZ`. The frequencies allowed also depend on local
+    /// regulations.
+    pub fn set_frequency(frequency_hz: u32) -> Result<(), ErrorCode> {
+        if !(MIN_FREQUENCY_HZ..=MAX_FREQUENCY_HZ).contains(&frequency_hz) {
+            return Err(ErrorCode::Invalid);
+        }
+        // The radio takes the frequency in steps of XTAL_HZ / 2^25.
+        let steps = (((frequency_hz as u64) << 25) + sx1262::XTAL_HZ / 2) / sx1262::XTAL_HZ;
+        let [b3, b2, b1, b0] = (steps as u32).to_be_bytes();
+        Self::radio_command(&[sx1262::SET_RF_FREQUENCY, b3, b2, b1, b0])
+    }
+
+    /// Sets the spreading factor, from `MIN_SPREADING_FACTOR` to
+    /// `MAX_SPREADING_FACTOR`, and the bandwidth.
+    pub fn set_modulation(spreading_factor: u8, bandwidth: Bandwidth) -> Result<(), ErrorCode> {
+        if !(MIN_SPREADING_FACTOR..=MAX_SPREADING_FACTOR).contains(&spreading_factor) {
+            return Err(ErrorCode::Invalid);
+        }
+        // Symbols of 16 ms or longer need the low data rate optimization.
+        let symbol_us = (1u32 << spreading_factor) * 1000 / bandwidth.khz();
+        let low_data_rate_optimize = (symbol_us >= 16_384) as u8;
+        Self::radio_command(&[
+            sx1262::SET_MODULATION_PARAMS,
+            spreading_factor,
+            bandwidth as u8,
+            sx1262::CODING_RATE_4_5,
+            low_data_rate_optimize,
+        ])
+    }
+
+    /// Transmits `payload`, of up to `MAX_PACKET_LEN` bytes, and waits until
+    /// it is sent.
+    pub fn transmit(payload: &[u8]) -> Result<(), ErrorCode> {
+        if payload.len() > MAX_PACKET_LEN {
+            return Err(ErrorCode::Size);
+        }
+        Self::set_packet_params(payload.len() as u8)?;
+
+        let mut write = [0; 2 + MAX_PACKET_LEN];
+        write[0] = sx1262::WRITE_BUFFER;
+        write[2..2 + payload.len()].copy_from_slice(payload);
+        Self::radio_command(&write[..2 + payload.len()])?;
+
+        let irq = Self::wait_for_irq(&[sx1262::SET_TX, 0, 0, 0])?;
+        match irq & sx1262::IRQ_TX_DONE {
+            0 => Err(ErrorCode::Fail),
+            _ => Ok(()),
+        }
+    }
+
+    /// Listens until a packet is received, and writes it into `buffer`.
+    /// Packets longer than `buffer` are truncated. Fails with
+    /// `ErrorCode::Fail` if a packet arrives corrupted.
+    pub fn receive(buffer: &mut [u8]) -> Result<Packet, ErrorCode> {
+        Self::set_packet_params(MAX_PACKET_LEN as u8)?;
+
+        let irq = Self::wait_for_irq(&[sx1262::SET_RX, 0, 0, 0])?;
+        if irq & sx1262::IRQ_RX_DONE == 0
+            || irq & (sx1262::IRQ_HEADER_ERR | sx1262::IRQ_CRC_ERR) != 0
+        {
+            return Err(ErrorCode::Fail);
+        }
+
+        let mut status = [0; 4];
+        Self::radio_query(&[sx1262::GET_RX_BUFFER_STATUS, 0, 0, 0], &mut status)?;
+        let len = (status[2] as usize).min(buffer.len());
+
+        // The radio clocks the packet out after the opcode, the offset and a
+        // status byte.
+        let mut write = [0; 3 + MAX_PACKET_LEN];
+        let mut read = [0; 3 + MAX_PACKET_LEN];
+        write[0] = sx1262::READ_BUFFER;
+        write[1] = status[3];
+        Self::radio_query(&write[..3 + len], &mut read[..3 + len])?;
+        buffer[..len].copy_from_slice(&read[3..3 + len]);
+
+        Self::radio_query(&[sx1262::GET_PACKET_STATUS, 0, 0, 0], &mut status)?;
+        Ok(Packet {
+            len,
+            rssi: -(status[2] as i16) / 2,
+            snr: (status[3] as i8) / 4,
+        })
+    }
+}
+
+/// The longest packet the radio can send or receive, in bytes.
+pub const MAX_PACKET_LEN: usize = 255;
+
+pub const MIN_SPREADING_FACTOR: u8 = 5;
+pub const MAX_SPREADING_FACTOR: u8 = 12;
+
+/// The frequency range the SX1262 covers.
+pub const MIN_FREQUENCY_HZ: u32 = 150_000_000;
+pub const MAX_FREQUENCY_HZ: u32 = 960_000_000;
+
+/// The bandwidth of the signal.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Bandwidth {
+    Khz125 = 0x04,
+    Khz250 = 0x05,
+    Khz500 = 0x06,
+}
+
+impl Bandwidth {
+    fn khz(self) -> u32 {
+        match self {
+            Bandwidth::Khz125 => 125,
+            Bandwidth::Khz250 => 250,
+            Bandwidth::Khz500 => 500,
+        }
+    }
+}
+
+/// A received packet.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Packet {
+    /// The number of bytes written into the receive buffer.
+    pub len: usize,
+    /// The received signal strength, in dBm.
+    pub rssi: i16,
+    /// The signal-to-noise ratio, in dB.
+    pub snr: i8,
+}
+
+/// System call configuration trait for `Lora`.
+pub trait Config:
+    platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config
+{
+}
+impl<T: platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config>
+    Config for T
+{
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+// The interrupts routed to DIO1.
+const IRQ_MASK: u16 = sx1262::IRQ_TX_DONE
+    | sx1262::IRQ_RX_DONE
+    | sx1262::IRQ_HEADER_ERR
+    | sx1262::IRQ_CRC_ERR
+    | sx1262::IRQ_TIMEOUT;
+
+// How many times to read BUSY before giving up on the radio.
+const BUSY_POLLS: u32 = 10_000;
+
+impl<S: Syscalls, C: Config> Lora<S, C> {
+    fn gpio_command(command: u32, pin: u32, arg: u32) -> Result<(), ErrorCode> {
+        S::command(gpio::DRIVER_NUM, command, pin, arg).to_result()
+    }
+
+    // The radio raises BUSY while it processes a command, and ignores any
+    // command sent in the meantime.
+    fn wait_until_ready() -> Result<(), ErrorCode> {
+        for _ in 0..BUSY_POLLS {
+            let busy: u32 = S::command(gpio::DRIVER_NUM, gpio::command::READ_INPUT, gpio::BUSY, 0)
+                .to_result()?;
+            if busy == 0 {
+                return Ok(());
+            }
+        }
+        Err(ErrorCode::Busy)
+    }
+
+    // Sends a command, discarding the bytes clocked back.
+    fn radio_command(write: &[u8]) -> Result<(), ErrorCode> {
+        Self::wait_until_ready()?;
+        let called = Cell::new(Option::<(u32, u32)>::None);
+        share::scope::<
+            (
+                AllowRo<_, { spi::DRIVER_NUM }, { spi::allow_ro::WRITE }>,
+                Subscribe<_, { spi::DRIVER_NUM }, { spi::subscribe::COMPLETE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<C, { spi::DRIVER_NUM }, { spi::allow_ro::WRITE }>(allow_ro, write)?;
+            Self::spi_transfer(subscribe, &called, write.len())
+        })
+    }
+
+    // Sends a command, storing the bytes clocked back into `read`, which must
+    // be as long as `write`.
+    fn radio_query(write: &[u8], read: &mut [u8]) -> Result<(), ErrorCode> {
+        Self::wait_until_ready()?;
+        let called = Cell::new(Option::<(u32, u32)>::None);
+        share::scope::<
+            (
+                AllowRo<_, { spi::DRIVER_NUM }, { spi::allow_ro::WRITE }>,
+                AllowRw<_, { spi::DRIVER_NUM }, { spi::allow_rw::READ }>,
+                Subscribe<_, { spi::DRIVER_NUM }, { spi::subscribe::COMPLETE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, allow_rw, subscribe) = handle.split();
+            S::allow_ro::<C, { spi::DRIVER_NUM }, { spi::allow_ro::WRITE }>(allow_ro, write)?;
+            S::allow_rw::<C, { spi::DRIVER_NUM }, { spi::allow_rw::READ }>(allow_rw, read)?;
+            Self::spi_transfer(subscribe, &called, write.len())
+        })
+    }
+
+    fn spi_transfer<'share>(
+        subscribe: share::Handle<
+            Subscribe<'share, S, { spi::DRIVER_NUM }, { spi::subscribe::COMPLETE }>,
+        >,
+        called: &'share Cell<Option<(u32, u32)>>,
+        len: usize,
+    ) -> Result<(), ErrorCode> {
+        S::subscribe::<_, _, C, { spi::DRIVER_NUM }, { spi::subscribe::COMPLETE }>(
+            subscribe, called,
+        )?;
+
+        // When this fails, `called` is guaranteed unmodified,
+        // because upcalls are never processed until we call `yield`.
+        S::command(spi::DRIVER_NUM, spi::command::READ_WRITE, len as u32, 0)
+            .to_result::<(), ErrorCode>()?;
+
+        loop {
+            S::yield_wait();
+            if let Some((_len, status)) = called.get() {
+                return match status {
+                    0 => Ok(()),
+                    e_status => Err(e_status.try_into().unwrap_or(ErrorCode::Fail)),
+                };
+            }
+        }
+    }
+
+    fn set_packet_params(payload_len: u8) -> Result<(), ErrorCode> {
+        let [preamble_hi, preamble_lo] = sx1262::PREAMBLE_LEN.to_be_bytes();
+        Self::radio_command(&[
+            sx1262::SET_PACKET_PARAMS,
+            preamble_hi,
+            preamble_lo,
+            sx1262::HEADER_EXPLICIT,
+            payload_len,
+            sx1262::CRC_ON,
+            sx1262::IQ_STANDARD,
+        ])
+    }
+
+    // Sends `start`, a command that starts transmitting or receiving, waits
+    // for the radio to raise DIO1, then returns and clears the interrupts
+    // that were raised.
+    fn wait_for_irq(start: &[u8]) -> Result<u16, ErrorCode> {
+        let [all_hi, all_lo] = sx1262::IRQ_ALL.to_be_bytes();
+        Self::radio_command(&[sx1262::CLEAR_IRQ_STATUS, all_hi, all_lo])?;
+
+        let called = Cell::new(Option::<(u32, u32)>::None);
+        share::scope(|subscribe| {
+            S::subscribe::<_, _, C, { gpio::DRIVER_NUM }, { gpio::subscribe::INTERRUPT }>(
+                subscribe, &called,
+            )?;
+            Self::gpio_command(
+                gpio::command::ENABLE_INTERRUPTS,
+                gpio::DIO1,
+                gpio::EDGE_RISING,
+            )?;
+
+            // The SPI transfer yields, so DIO1 may already have risen when
+            // it returns.
+            let result = Self::radio_command(start).map(|()| {
+                while called.get().map(|(pin, _)| pin) != Some(gpio::DIO1) {
+                    S::yield_wait();
+                }
+            });
+            Self::gpio_command(gpio::command::DISABLE_INTERRUPTS, gpio::DIO1, 0)?;
+            result
+        })?;
+
+        let mut read = [0; 4];
+        Self::radio_query(&[sx1262::GET_IRQ_STATUS, 0, 0, 0], &mut read)?;
+        Self::radio_command(&[sx1262::CLEAR_IRQ_STATUS, all_hi, all_lo])?;
+        Ok(u16::from_be_bytes([read[2], read[3]]))
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Driver numbers and command IDs
+// -----------------------------------------------------------------------------
+
+// The SPI passthrough, which has the SPI controller system call interface.
+mod spi {
+    pub const DRIVER_NUM: u32 = 0x30003;
+
+    // The radio's chip select line.
+    pub const CHIP_SELECT: u32 = 0;
+
+    pub mod command {
+        pub const DRIVER_CHECK: u32 = 0;
+        pub const READ_WRITE: u32 = 2;
+        pub const SET_CHIP_SELECT: u32 = 3;
+    }
+
+    pub mod subscribe {
+        pub const COMPLETE: u32 = 0;
+    }
+
+    pub mod allow_ro {
+        pub const WRITE: u32 = 0;
+    }
+
+    pub mod allow_rw {
+        pub const READ: u32 = 0;
+    }
+}
+
+// The GPIO passthrough, which has the GPIO system call interface.
+mod gpio {
+    pub const DRIVER_NUM: u32 = 0x30004;
+
+    // The radio's control lines.
+    pub const BUSY: u32 = 1;
+    pub const DIO1: u32 = 2;
+    pub const RESET: u32 = 4;
+
+    pub const PULL_NONE: u32 = 0;
+    pub const EDGE_RISING: u32 = 1;
+
+    pub mod command {
+        pub const COUNT: u32 = 0;
+        pub const ENABLE_OUTPUT: u32 = 1;
+        pub const SET: u32 = 2;
+        pub const CLEAR: u32 = 3;
+        pub const ENABLE_INPUT: u32 = 5;
+        pub const READ_INPUT: u32 = 6;
+        pub const ENABLE_INTERRUPTS: u32 = 7;
+        pub const DISABLE_INTERRUPTS: u32 = 8;
+    }
+
+    pub mod subscribe {
+        pub const INTERRUPT: u32 = 0;
+    }
+}
@@ -0,0 +1,44 @@
+//! The parts of the SX1262 command set the driver uses. Every command is one
+//! SPI transaction starting with its opcode; in the bytes clocked back, the
+//! byte after the opcode is the radio's status, followed by any response.
+
+pub const SET_STANDBY: u8 = 0x80;
+pub const SET_PACKET_TYPE: u8 = 0x8a;
+pub const SET_RF_FREQUENCY: u8 = 0x86;
+pub const SET_MODULATION_PARAMS: u8 = 0x8b;
+pub const SET_PACKET_PARAMS: u8 = 0x8c;
+pub const SET_BUFFER_BASE_ADDRESS: u8 = 0x8f;
+pub const SET_DIO_IRQ_PARAMS: u8 = 0x08;
+pub const SET_TX: u8 = 0x83;
+pub const SET_RX: u8 = 0x82;
+pub const WRITE_REGISTER: u8 = 0x0d;
+pub const WRITE_BUFFER: u8 = 0x0e;
+pub const READ_BUFFER: u8 = 0x1e;
+pub const GET_IRQ_STATUS: u8 = 0x12;
+pub const CLEAR_IRQ_STATUS: u8 = 0x02;
+pub const GET_RX_BUFFER_STATUS: u8 = 0x13;
+pub const GET_PACKET_STATUS: u8 = 0x14;
+
+pub const STANDBY_RC: u8 = 0x00;
+pub const PACKET_TYPE_LORA: u8 = 0x01;
+pub const CODING_RATE_4_5: u8 = 0x01;
+pub const PREAMBLE_LEN: u16 = 8;
+pub const HEADER_EXPLICIT: u8 = 0x00;
+pub const CRC_ON: u8 = 0x01;
+pub const IQ_STANDARD: u8 = 0x00;
+
+/// The LoRa sync word register, and the sync word of private networks.
+pub const SYNC_WORD_REGISTER: u16 = 0x0740;
+pub const PRIVATE_SYNC_WORD: [u8; 2] = [0x14, 0x24];
+
+/// The crystal frequency, which the RF frequency is set in steps of
+/// `XTAL_HZ / 2^25`.
+pub const XTAL_HZ: u64 = 32_000_000;
+
+// Interrupt flags.
+pub const IRQ_TX_DONE: u16 = 1 << 0;
+pub const IRQ_RX_DONE: u16 = 1 << 1;
+pub const IRQ_HEADER_ERR: u16 = 1 << 5;
+pub const IRQ_CRC_ERR: u16 = 1 << 6;
+pub const IRQ_TIMEOUT: u16 = 1 << 9;
+pub const IRQ_ALL: u16 = 0x03ff;
@@ -0,0 +1,189 @@
+use super::*;
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type Lora = super::Lora<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Lora::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let radio = fake::Lora::new();
+    kernel.add_driver(&radio.spi());
+    assert!(!Lora::driver_check());
+    kernel.add_driver(&radio.gpio());
+
+    assert!(Lora::driver_check());
+}
+
+#[test]
+fn init() {
+    let kernel = fake::Kernel::new();
+    kernel.add_driver(&fake::Alarm::new(1000));
+    let radio = fake::Lora::new();
+    kernel.add_driver(&radio.spi());
+    kernel.add_driver(&radio.gpio());
+
+    assert_eq!(Lora::init(), Ok(()));
+    assert_eq!(radio.reset_count(), 1);
+    assert!(radio.is_lora());
+    assert_eq!(radio.register(0x0740), Some(0x14));
+    assert_eq!(radio.register(0x0741), Some(0x24));
+}
+
+#[test]
+fn configure() {
+    let kernel = fake::Kernel::new();
+    kernel.add_driver(&fake::Alarm::new(1000));
+    let radio = fake::Lora::new();
+    kernel.add_driver(&radio.spi());
+    kernel.add_driver(&radio.gpio());
+    assert_eq!(Lora::init(), Ok(()));
+
+    assert_eq!(Lora::set_frequency(915_000_000), Ok(()));
+    assert_eq!(radio.frequency_hz(), 915_000_000);
+    assert_eq!(Lora::set_frequency(2_400_000_000), Err(ErrorCode::Invalid));
+    assert_eq!(Lora::set_modulation(9, Bandwidth::Khz250), Ok(()));
+    assert_eq!(radio.spreading_factor(), 9);
+    assert_eq!(radio.bandwidth_khz(), 250);
+    assert!(!radio.low_data_rate_optimize());
+    // SF12 symbols at 250 kHz last 16 ms.
+    assert_eq!(Lora::set_modulation(12, Bandwidth::Khz250), Ok(()));
+    assert!(radio.low_data_rate_optimize());
+    assert_eq!(
+        Lora::set_modulation(4, Bandwidth::Khz125),
+        Err(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        Lora::set_modulation(13, Bandwidth::Khz125),
+        Err(ErrorCode::Invalid)
+    );
+}
+
+#[test]
+fn transmit() {
+    let kernel = fake::Kernel::new();
+    kernel.add_driver(&fake::Alarm::new(1000));
+    let radio = fake::Lora::new();
+    kernel.add_driver(&radio.spi());
+    kernel.add_driver(&radio.gpio());
+    assert_eq!(Lora::init(), Ok(()));
+
+    assert_eq!(Lora::transmit(b"temperature=21"), Ok(()));
+    assert_eq!(
+        Lora::transmit(&[0; MAX_PACKET_LEN + 1]),
+        Err(ErrorCode::Size)
+    );
+    assert_eq!(Lora::transmit(b"ok"), Ok(()));
+    assert_eq!(
+        radio.take_transmitted(),
+        [b"temperature=21".to_vec(), b"ok".to_vec()]
+    );
+}
+
+#[test]
+fn receive() {
+    let kernel = fake::Kernel::new();
+    kernel.add_driver(&fake::Alarm::new(1000));
+    let radio = fake::Lora::new();
+    kernel.add_driver(&radio.spi());
+    kernel.add_driver(&radio.gpio());
+    assert_eq!(Lora::init(), Ok(()));
+
+    radio.inject(b"ping", -87, -3);
+    let mut buffer = [0; 16];
+    assert_eq!(
+        Lora::receive(&mut buffer),
+        Ok(Packet {
+            len: 4,
+            rssi: -87,
+            snr: -3,
+        })
+    );
+    assert_eq!(&buffer[..4], b"ping");
+    assert!(!radio.is_receiving());
+}
+
+#[test]
+fn receive_truncated() {
+    let kernel = fake::Kernel::new();
+    kernel.add_driver(&fake::Alarm::new(1000));
+    let radio = fake::Lora::new();
+    kernel.add_driver(&radio.spi());
+    kernel.add_driver(&radio.gpio());
+    assert_eq!(Lora::init(), Ok(()));
+
+    radio.inject(b"temperature=21", -60, 8);
+    let mut buffer = [0; 4];
+    assert_eq!(
+        Lora::receive(&mut buffer),
+        Ok(Packet {
+            len: 4,
+            rssi: -60,
+            snr: 8,
+        })
+    );
+    assert_eq!(buffer, *b"temp");
+}
+
+#[test]
+fn receive_corrupted() {
+    let kernel = fake::Kernel::new();
+    kernel.add_driver(&fake::Alarm::new(1000));
+    let radio = fake::Lora::new();
+    kernel.add_driver(&radio.spi());
+    kernel.add_driver(&radio.gpio());
+    assert_eq!(Lora::init(), Ok(()));
+
+    radio.inject_corrupted(b"p?ng");
+    radio.inject(b"ping", -87, -3);
+    let mut buffer = [0; 16];
+    assert_eq!(Lora::receive(&mut buffer), Err(ErrorCode::Fail));
+    assert_eq!(Lora::receive(&mut buffer).map(|packet| packet.len), Ok(4));
+    assert_eq!(&buffer[..4], b"ping");
+}
+
+#[test]
+fn loopback() {
+    let kernel = fake::Kernel::new();
+    kernel.add_driver(&fake::Alarm::new(1000));
+    let radio = fake::Lora::new();
+    kernel.add_driver(&radio.spi());
+    kernel.add_driver(&radio.gpio());
+    assert_eq!(Lora::init(), Ok(()));
+    radio.set_loopback(true);
+
+    assert_eq!(Lora::transmit(b"echo"), Ok(()));
+    let mut buffer = [0; 2];
+    assert_eq!(
+        Lora::receive(&mut buffer),
+        Ok(Packet {
+            len: 2,
+            rssi: fake::Lora::LOOPBACK_RSSI,
+            snr: fake::Lora::LOOPBACK_SNR,
+        })
+    );
+    assert_eq!(&buffer, b"ec");
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let radio = fake::Lora::new();
+    kernel.add_driver(&radio.spi());
+    kernel.add_driver(&radio.gpio());
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: gpio::DRIVER_NUM,
+        command_id: gpio::command::READ_INPUT,
+        argument0: gpio::BUSY,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::NoDevice)),
+    });
+
+    assert_eq!(Lora::set_frequency(868_100_000), Err(ErrorCode::NoDevice));
+}
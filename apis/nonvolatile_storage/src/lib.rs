@@ -0,0 +1,163 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform as platform;
+use libtock_platform::allow_ro::AllowRo;
+use libtock_platform::allow_rw::AllowRw;
+use libtock_platform::share;
+use libtock_platform::subscribe::Subscribe;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+mod record;
+
+pub use record::{Error, Record, RECORD_HEADER_LEN};
+
+/// The nonvolatile storage driver.
+///
+/// It gives a process access to its own region of nonvolatile storage, which
+/// is preserved across reboots. Offsets are relative to the start of the
+/// process' region.
+///
+/// In addition to raw reads and writes, `read_record` and `write_record` store
+/// typed values (see [`Record`]) protected by a CRC and tagged with a version.
+///
+/// # Example
+/// ```ignore
+/// use libtock::nonvolatile_storage::NonvolatileStorage;
+///
+/// let size = NonvolatileStorage::get_size()?;
+/// NonvolatileStorage::write(0, b"hello")?;
+///
+/// let mut buf = [0; 5];
+/// NonvolatileStorage::read(0, &mut buf)?;
+/// ```
+pub struct NonvolatileStorage<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> NonvolatileStorage<S, C> {
+    /// Run a check against the nonvolatile storage capsule to ensure it is
+    /// present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Returns the size, in bytes, of the process' storage region.
+    pub fn get_size() -> Result<usize, ErrorCode> {
+        let size: u32 = S::command(DRIVER_NUM, command::GET_SIZE, 0, 0).to_result()?;
+        Ok(size as usize)
+    }
+
+    /// Fills `buf` with the contents of storage starting at `offset`.
+    pub fn read(offset: usize, buf: &mut [u8]) -> Result<(), ErrorCode> {
+        let len = buf.len();
+        let called = Cell::new(Option::<(u32,)>::None);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, { allow_rw::READ }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::READ_DONE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            S::allow_rw::<C, DRIVER_NUM, { allow_rw::READ }>(allow_rw, buf)?;
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::READ_DONE }>(subscribe, &called)?;
+            Self::wait_for(command::READ, offset, len, &called)
+        })
+    }
+
+    /// Writes all of `buf` to storage starting at `offset`.
+    pub fn write(offset: usize, buf: &[u8]) -> Result<(), ErrorCode> {
+        let called = Cell::new(Option::<(u32,)>::None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, { allow_ro::WRITE }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::WRITE_DONE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<C, DRIVER_NUM, { allow_ro::WRITE }>(allow_ro, buf)?;
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::WRITE_DONE }>(subscribe, &called)?;
+            Self::wait_for(command::WRITE, offset, buf.len(), &called)
+        })
+    }
+}
+
+/// System call configuration trait for `NonvolatileStorage`.
+pub trait Config:
+    platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config
+{
+}
+impl<T: platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config>
+    Config for T
+{
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls, C: Config> NonvolatileStorage<S, C> {
+    // Starts a read or write of `len` bytes at `offset` using the buffer
+    // already shared with the kernel, and waits for it to complete.
+    fn wait_for(
+        command_id: u32,
+        offset: usize,
+        len: usize,
+        called: &Cell<Option<(u32,)>>,
+    ) -> Result<(), ErrorCode> {
+        let offset: u32 = offset.try_into().map_err(|_| ErrorCode::Invalid)?;
+
+        // When this fails, `called` is guaranteed unmodified,
+        // because upcalls are never processed until we call `yield`.
+        S::command(DRIVER_NUM, command_id, offset, len as u32).to_result::<(), ErrorCode>()?;
+
+        loop {
+            S::yield_wait();
+            if let Some((done,)) = called.get() {
+                // The kernel reports the number of bytes transferred, which
+                // is short if the operation did not complete.
+                if done as usize != len {
+                    return Err(ErrorCode::Fail);
+                }
+                return Ok(());
+            }
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x50001;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const GET_SIZE: u32 = 1;
+    pub const READ: u32 = 2;
+    pub const WRITE: u32 = 3;
+}
+
+mod subscribe {
+    pub const READ_DONE: u32 = 0;
+    pub const WRITE_DONE: u32 = 1;
+}
+
+mod allow_ro {
+    pub const WRITE: u32 = 0;
+}
+
+mod allow_rw {
+    pub const READ: u32 = 0;
+}
@@ -0,0 +1,173 @@
+//! Typed records stored in nonvolatile storage.
+//!
+//! A record is stored as a header followed by the payload produced by
+//! `Record::encode`. The header holds (all little-endian):
+//!
+//! | Bytes | Contents                                               |
+//! | ----- | ------------------------------------------------------ |
+//! | 0..2  | `MAGIC`, identifying a record                          |
+//! | 2..4  | The version of the payload encoding                    |
+//! | 4..6  | The payload length                                     |
+//! | 6..10 | CRC-32 of bytes 2..6 of the header followed by payload |
+//!
+//! The CRC detects records that were only partially written, for example
+//! because power was lost during the write.
+
+use crate::{Config, NonvolatileStorage};
+use libtock_platform::{ErrorCode, Syscalls};
+
+/// The number of bytes of storage a record uses in addition to its payload.
+pub const RECORD_HEADER_LEN: usize = 10;
+
+/// A value that can be stored with `NonvolatileStorage::write_record`.
+///
+/// # Example
+/// ```ignore
+/// struct Settings {
+///     brightness: u8,
+///     volume: u8,
+/// }
+///
+/// impl Record for Settings {
+///     const VERSION: u16 = 2;
+///     const MAX_LEN: usize = 2;
+///
+///     fn encode(&self, payload: &mut [u8]) -> usize {
+///         payload[0] = self.brightness;
+///         payload[1] = self.volume;
+///         2
+///     }
+///
+///     fn decode(version: u16, payload: &[u8]) -> Option<Settings> {
+///         match (version, payload) {
+///             // Version 1 had no volume setting.
+///             (1, &[brightness]) => Some(Settings { brightness, volume: 5 }),
+///             (2, &[brightness, volume]) => Some(Settings { brightness, volume }),
+///             _ => None,
+///         }
+///     }
+/// }
+/// ```
+pub trait Record: Sized {
+    /// The version of the encoding produced by `encode`. It should be
+    /// incremented whenever the encoding changes.
+    const VERSION: u16;
+
+    /// The maximum length of the encoded payload.
+    const MAX_LEN: usize;
+
+    /// Encodes `self` into `payload`, which is `MAX_LEN` bytes long, and
+    /// returns the number of bytes used.
+    fn encode(&self, payload: &mut [u8]) -> usize;
+
+    /// Decodes a payload that was encoded by `version` of the encoding. Only
+    /// versions up to `VERSION` are passed in, so older encodings can be
+    /// migrated here. Returns `None` if the payload is invalid.
+    fn decode(version: u16, payload: &[u8]) -> Option<Self>;
+}
+
+/// An error reading or writing a record.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// There is no record at the given offset.
+    NoRecord,
+    /// The record is damaged, for example because it was only partially
+    /// written, or `Record::decode` rejected it.
+    Corrupt,
+    /// The record was written by a newer version of the encoding than this
+    /// application supports.
+    UnsupportedVersion(u16),
+    /// The scratch buffer is too small, or the record does not fit in
+    /// storage.
+    Size,
+    /// Any other failure reported by the kernel.
+    Other(ErrorCode),
+}
+
+impl From<ErrorCode> for Error {
+    fn from(error: ErrorCode) -> Error {
+        match error {
+            ErrorCode::Size => Error::Size,
+            error => Error::Other(error),
+        }
+    }
+}
+
+impl<S: Syscalls, C: Config> NonvolatileStorage<S, C> {
+    /// Reads the record stored at `offset`.
+    ///
+    /// `scratch` must be at least `RECORD_HEADER_LEN + R::MAX_LEN` bytes long.
+    pub fn read_record<R: Record>(offset: usize, scratch: &mut [u8]) -> Result<R, Error> {
+        if scratch.len() < RECORD_HEADER_LEN + R::MAX_LEN {
+            return Err(Error::Size);
+        }
+        let (header, payload) = scratch.split_at_mut(RECORD_HEADER_LEN);
+        Self::read(offset, header)?;
+        if u16::from_le_bytes([header[0], header[1]]) != MAGIC {
+            return Err(Error::NoRecord);
+        }
+        let version = u16::from_le_bytes([header[2], header[3]]);
+        let len = u16::from_le_bytes([header[4], header[5]]) as usize;
+        let crc = u32::from_le_bytes([header[6], header[7], header[8], header[9]]);
+        if len > R::MAX_LEN {
+            return Err(Error::Corrupt);
+        }
+        let payload = &mut payload[..len];
+        Self::read(offset + RECORD_HEADER_LEN, payload)?;
+        if crc32(crc32(CRC_INIT, &header[2..6]), payload) ^ CRC_INIT != crc {
+            return Err(Error::Corrupt);
+        }
+        if version > R::VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        R::decode(version, payload).ok_or(Error::Corrupt)
+    }
+
+    /// Writes `record` to storage at `offset`, replacing any record already
+    /// stored there.
+    ///
+    /// `scratch` must be at least `RECORD_HEADER_LEN + R::MAX_LEN` bytes long.
+    pub fn write_record<R: Record>(
+        offset: usize,
+        record: &R,
+        scratch: &mut [u8],
+    ) -> Result<(), Error> {
+        if scratch.len() < RECORD_HEADER_LEN + R::MAX_LEN || R::MAX_LEN > u16::MAX as usize {
+            return Err(Error::Size);
+        }
+        let (header, payload) = scratch.split_at_mut(RECORD_HEADER_LEN);
+        let len = record.encode(&mut payload[..R::MAX_LEN]);
+        if len > R::MAX_LEN {
+            return Err(Error::Size);
+        }
+        header[0..2].copy_from_slice(&MAGIC.to_le_bytes());
+        header[2..4].copy_from_slice(&R::VERSION.to_le_bytes());
+        header[4..6].copy_from_slice(&(len as u16).to_le_bytes());
+        let crc = crc32(crc32(CRC_INIT, &header[2..6]), &payload[..len]) ^ CRC_INIT;
+        header[6..10].copy_from_slice(&crc.to_le_bytes());
+        Self::write(offset, &scratch[..RECORD_HEADER_LEN + len])?;
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+// Neither all-zeroes nor all-ones, so erased storage is not mistaken for a
+// record.
+const MAGIC: u16 = 0x5254;
+
+const CRC_INIT: u32 = 0xffff_ffff;
+
+// Updates a CRC-32 (the IEEE 802.3 polynomial, reflected) with `data`. The
+// caller is responsible for the initial value and final XOR.
+fn crc32(mut crc: u32, data: &[u8]) -> u32 {
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    crc
+}
@@ -0,0 +1,203 @@
+use super::*;
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type NonvolatileStorage = super::NonvolatileStorage<fake::Syscalls>;
+
+#[derive(Debug, PartialEq, Eq)]
+struct Settings {
+    brightness: u8,
+    volume: u8,
+}
+
+impl Record for Settings {
+    const VERSION: u16 = 2;
+    const MAX_LEN: usize = 2;
+
+    fn encode(&self, payload: &mut [u8]) -> usize {
+        payload[0] = self.brightness;
+        payload[1] = self.volume;
+        2
+    }
+
+    fn decode(version: u16, payload: &[u8]) -> Option<Settings> {
+        match (version, payload) {
+            (1, &[brightness]) => Some(Settings {
+                brightness,
+                volume: 5,
+            }),
+            (2, &[brightness, volume]) => Some(Settings { brightness, volume }),
+            _ => None,
+        }
+    }
+}
+
+const SETTINGS: Settings = Settings {
+    brightness: 7,
+    volume: 9,
+};
+
+// The encoding of `SETTINGS`, including the header.
+const SETTINGS_BYTES: [u8; 12] = [0x54, 0x52, 2, 0, 2, 0, 0x40, 0xe6, 0x9e, 0x60, 7, 9];
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!NonvolatileStorage::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NonvolatileStorage::new(64);
+    kernel.add_driver(&driver);
+
+    assert!(NonvolatileStorage::driver_check());
+    assert_eq!(NonvolatileStorage::get_size(), Ok(64));
+}
+
+#[test]
+fn read_write() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NonvolatileStorage::new(8);
+    kernel.add_driver(&driver);
+
+    assert_eq!(NonvolatileStorage::write(2, b"tock"), Ok(()));
+    assert_eq!(driver.contents(), *b"\xff\xfftock\xff\xff");
+
+    let mut buf = [0; 3];
+    assert_eq!(NonvolatileStorage::read(3, &mut buf), Ok(()));
+    assert_eq!(buf, *b"ock");
+
+    assert_eq!(
+        NonvolatileStorage::read(6, &mut buf),
+        Err(ErrorCode::Invalid)
+    );
+}
+
+#[test]
+fn power_loss() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NonvolatileStorage::new(8);
+    kernel.add_driver(&driver);
+
+    driver.lose_power_after(1);
+    assert_eq!(NonvolatileStorage::write(0, b"tock"), Err(ErrorCode::Fail));
+    assert_eq!(driver.contents(), *b"t\xff\xff\xff\xff\xff\xff\xff");
+}
+
+#[test]
+fn records() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NonvolatileStorage::new(32);
+    kernel.add_driver(&driver);
+    let mut scratch = [0; RECORD_HEADER_LEN + 2];
+
+    assert_eq!(
+        NonvolatileStorage::read_record::<Settings>(4, &mut scratch),
+        Err(Error::NoRecord)
+    );
+    assert_eq!(
+        NonvolatileStorage::write_record(4, &SETTINGS, &mut scratch),
+        Ok(())
+    );
+    assert_eq!(driver.contents()[4..16], SETTINGS_BYTES);
+    assert_eq!(
+        NonvolatileStorage::read_record(4, &mut scratch),
+        Ok(SETTINGS)
+    );
+
+    assert_eq!(
+        NonvolatileStorage::read_record::<Settings>(4, &mut [0; RECORD_HEADER_LEN + 1]),
+        Err(Error::Size)
+    );
+    assert_eq!(
+        NonvolatileStorage::write_record(24, &SETTINGS, &mut scratch),
+        Err(Error::Other(ErrorCode::Invalid))
+    );
+}
+
+#[test]
+fn record_versions() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NonvolatileStorage::new(32);
+    kernel.add_driver(&driver);
+    let mut scratch = [0; RECORD_HEADER_LEN + 2];
+
+    // A version 1 record is migrated by `decode`.
+    driver.set_contents(0, &[0x54, 0x52, 1, 0, 1, 0, 0x39, 0x21, 0xe4, 0x64, 7]);
+    assert_eq!(
+        NonvolatileStorage::read_record(0, &mut scratch),
+        Ok(Settings {
+            brightness: 7,
+            volume: 5
+        })
+    );
+
+    // A record from a newer version of the application.
+    driver.set_contents(0, &[0x54, 0x52, 3, 0, 2, 0, 0xe5, 0x35, 0xc2, 0xab, 7, 9]);
+    assert_eq!(
+        NonvolatileStorage::read_record::<Settings>(0, &mut scratch),
+        Err(Error::UnsupportedVersion(3))
+    );
+
+    // Changing any byte invalidates the CRC.
+    let mut damaged = SETTINGS_BYTES;
+    damaged[11] ^= 1;
+    driver.set_contents(0, &damaged);
+    assert_eq!(
+        NonvolatileStorage::read_record::<Settings>(0, &mut scratch),
+        Err(Error::Corrupt)
+    );
+}
+
+#[test]
+fn record_power_loss() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NonvolatileStorage::new(32);
+    kernel.add_driver(&driver);
+    let mut scratch = [0; RECORD_HEADER_LEN + 2];
+    NonvolatileStorage::write_record(0, &SETTINGS, &mut scratch).unwrap();
+
+    // Power is lost after the header has been rewritten, but before the new
+    // payload is stored.
+    driver.lose_power_after(RECORD_HEADER_LEN);
+    let update = Settings {
+        brightness: 1,
+        volume: 2,
+    };
+    assert_eq!(
+        NonvolatileStorage::write_record(0, &update, &mut scratch),
+        Err(Error::Other(ErrorCode::Fail))
+    );
+    assert_eq!(
+        NonvolatileStorage::read_record::<Settings>(0, &mut scratch),
+        Err(Error::Corrupt)
+    );
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NonvolatileStorage::new(8);
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_ro::WRITE,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::WRITE_DONE,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::WRITE,
+        argument0: 0,
+        argument1: 2,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+
+    assert_eq!(NonvolatileStorage::write(0, &[1, 2]), Err(ErrorCode::Busy));
+}
@@ -0,0 +1,141 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform as platform;
+use libtock_platform::share::{self, Handle};
+use libtock_platform::subscribe::{OneId, Subscribe};
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls, Upcall};
+
+/// The proximity sensor driver.
+///
+/// Readings range from 0 to 255; the higher the reading, the closer the
+/// object in front of the sensor. Besides plain readings, the sensor can wait
+/// for the reading to leave a window of thresholds, so that apps need not poll
+/// it to notice an object coming closer or moving away.
+///
+/// # Example
+/// ```ignore
+/// use libtock::proximity::Proximity;
+///
+/// let proximity = Proximity::read_sync()?;
+///
+/// // Wait for an object to come close, or for the sensor to be uncovered.
+/// let proximity = Proximity::wait_for_value_outside(10, 200)?;
+/// ```
+pub struct Proximity<S: Syscalls, C: platform::subscribe::Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: platform::subscribe::Config> Proximity<S, C> {
+    /// Run a check against the proximity capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Starts a reading and returns immediately. The reading is delivered to
+    /// the registered [`ProximityListener`].
+    pub fn read() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::READ, 0, 0).to_result()
+    }
+
+    /// Asks for a reading once the proximity drops below `lower` or rises
+    /// above `upper`, and returns immediately. The reading is delivered to the
+    /// registered [`ProximityListener`].
+    pub fn read_on_interrupt(lower: u8, upper: u8) -> Result<(), ErrorCode> {
+        if lower > upper {
+            return Err(ErrorCode::Invalid);
+        }
+        S::command(
+            DRIVER_NUM,
+            command::READ_ON_INTERRUPT,
+            lower as u32,
+            upper as u32,
+        )
+        .to_result()
+    }
+
+    /// Registers the listener for readings.
+    ///
+    /// There can be only one listener registered at a time. Each time this
+    /// function is used, it will replace the previously registered listener.
+    pub fn register_listener<'share, F: Fn(u8)>(
+        listener: &'share ProximityListener<F>,
+        subscribe: Handle<Subscribe<'share, S, DRIVER_NUM, 0>>,
+    ) -> Result<(), ErrorCode> {
+        S::subscribe::<_, _, C, DRIVER_NUM, 0>(subscribe, listener)
+    }
+
+    /// Unregister the listener for readings.
+    pub fn unregister_listener() {
+        S::unsubscribe(DRIVER_NUM, subscribe::READING)
+    }
+
+    /// Reads the proximity and waits for the result.
+    pub fn read_sync() -> Result<u8, ErrorCode> {
+        Self::wait_for_reading(Self::read)
+    }
+
+    /// Waits until the proximity drops below `lower` or rises above `upper`,
+    /// and returns the reading that crossed the threshold.
+    pub fn wait_for_value_outside(lower: u8, upper: u8) -> Result<u8, ErrorCode> {
+        Self::wait_for_reading(|| Self::read_on_interrupt(lower, upper))
+    }
+
+    fn wait_for_reading<F: FnOnce() -> Result<(), ErrorCode>>(start: F) -> Result<u8, ErrorCode> {
+        let reading = Cell::new(None);
+        let listener = ProximityListener(|proximity| reading.set(Some(proximity)));
+        share::scope(|subscribe| {
+            Self::register_listener(&listener, subscribe)?;
+
+            // When this fails, `reading` is guaranteed unmodified,
+            // because upcalls are never processed until we call `yield`.
+            start()?;
+
+            loop {
+                if let Some(proximity) = reading.get() {
+                    return Ok(proximity);
+                }
+                S::yield_wait();
+            }
+        })
+    }
+}
+
+/// A wrapper around a closure to be registered and called with each proximity
+/// reading.
+///
+/// ```ignore
+/// let listener = ProximityListener(|proximity| {
+///     // make use of the reading
+/// });
+/// ```
+pub struct ProximityListener<F: Fn(u8)>(pub F);
+
+impl<F: Fn(u8)> Upcall<OneId<DRIVER_NUM, 0>> for ProximityListener<F> {
+    fn upcall(&self, proximity: u32, _arg1: u32, _arg2: u32) {
+        self.0(proximity as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x60005;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const READ: u32 = 1;
+    pub const READ_ON_INTERRUPT: u32 = 2;
+}
+
+mod subscribe {
+    pub const READING: u32 = 0;
+}
@@ -0,0 +1,91 @@
+use super::*;
+use core::cell::Cell;
+use libtock_platform::{ErrorCode, YieldNoWaitReturn};
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type Proximity = super::Proximity<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Proximity::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Proximity::new();
+    kernel.add_driver(&driver);
+
+    assert!(Proximity::driver_check());
+}
+
+#[test]
+fn read() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Proximity::new();
+    kernel.add_driver(&driver);
+
+    let reading = Cell::new(None);
+    let listener = ProximityListener(|proximity| reading.set(Some(proximity)));
+    share::scope(|subscribe| {
+        assert_eq!(Proximity::register_listener(&listener, subscribe), Ok(()));
+        assert_eq!(Proximity::read(), Ok(()));
+        assert_eq!(Proximity::read(), Err(ErrorCode::Busy));
+        driver.set_value(120);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+    });
+    assert_eq!(reading.get(), Some(120));
+
+    driver.set_value_sync(30);
+    assert_eq!(Proximity::read_sync(), Ok(30));
+}
+
+#[test]
+fn read_on_interrupt() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Proximity::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(
+        Proximity::read_on_interrupt(200, 10),
+        Err(ErrorCode::Invalid)
+    );
+
+    let reading = Cell::new(None);
+    let listener = ProximityListener(|proximity| reading.set(Some(proximity)));
+    share::scope(|subscribe| {
+        assert_eq!(Proximity::register_listener(&listener, subscribe), Ok(()));
+        assert_eq!(Proximity::read_on_interrupt(10, 200), Ok(()));
+        assert_eq!(driver.thresholds(), Some((10, 200)));
+        driver.set_value(100);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        driver.set_value(220);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+    });
+    assert_eq!(reading.get(), Some(220));
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Proximity::new();
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::READING,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::READ_ON_INTERRUPT,
+        argument0: 10,
+        argument1: 200,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+
+    assert_eq!(
+        Proximity::wait_for_value_outside(10, 200),
+        Err(ErrorCode::Busy)
+    );
+}
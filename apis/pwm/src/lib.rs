@@ -0,0 +1,90 @@
+#![no_std]
+
+use libtock_platform::{ErrorCode, Syscalls};
+
+/// The PWM driver.
+///
+/// Duty cycles are expressed as a fraction of the maximum duty cycle returned
+/// by `get_max_duty_cycle`, which depends on the hardware.
+///
+/// # Example
+/// ```ignore
+/// use libtock::pwm::Pwm;
+///
+/// // Drive pin 0 at 1 kHz with a 25% duty cycle.
+/// Pwm::start_percent(0, 1000, 25)?;
+/// // ...
+/// Pwm::stop(0)?;
+/// ```
+pub struct Pwm<S: Syscalls>(S);
+
+impl<S: Syscalls> Pwm<S> {
+    /// Run a check against the PWM capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Returns the number of PWM pins.
+    pub fn count() -> Result<u32, ErrorCode> {
+        S::command(DRIVER_NUM, command::COUNT, 0, 0).to_result()
+    }
+
+    /// Starts the output on `pin`, at `frequency_hz` with a duty cycle of
+    /// `duty_cycle` out of `get_max_duty_cycle()`. Restarts it if it was
+    /// already running.
+    pub fn start(pin: u16, frequency_hz: u32, duty_cycle: u16) -> Result<(), ErrorCode> {
+        let pin_and_duty_cycle = (duty_cycle as u32) << 16 | pin as u32;
+        S::command(DRIVER_NUM, command::START, pin_and_duty_cycle, frequency_hz).to_result()
+    }
+
+    /// Starts the output on `pin`, at `frequency_hz` with a duty cycle of
+    /// `percent`%. Returns `ErrorCode::Invalid` if `percent` is over 100.
+    pub fn start_percent(pin: u16, frequency_hz: u32, percent: u8) -> Result<(), ErrorCode> {
+        if percent > 100 {
+            return Err(ErrorCode::Invalid);
+        }
+        let max_duty_cycle = Self::get_max_duty_cycle()?;
+        let duty_cycle = max_duty_cycle as u32 * percent as u32 / 100;
+        Self::start(pin, frequency_hz, duty_cycle as u16)
+    }
+
+    pub fn stop(pin: u16) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::STOP, pin.into(), 0).to_result()
+    }
+
+    /// Returns the highest frequency `pin` can output, in hertz.
+    pub fn get_max_frequency(pin: u16) -> Result<u32, ErrorCode> {
+        S::command(DRIVER_NUM, command::GET_MAX_FREQUENCY, pin.into(), 0).to_result()
+    }
+
+    /// Returns the duty cycle value of an output that is always high.
+    pub fn get_max_duty_cycle() -> Result<u16, ErrorCode> {
+        S::command(DRIVER_NUM, command::GET_MAX_DUTY_CYCLE, 0, 0)
+            .to_result()
+            .map(|max: u32| max as u16)
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x10;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const START: u32 = 1;
+    pub const STOP: u32 = 2;
+    pub const GET_MAX_FREQUENCY: u32 = 3;
+    pub const GET_MAX_DUTY_CYCLE: u32 = 4;
+    pub const COUNT: u32 = 5;
+}
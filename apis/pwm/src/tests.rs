@@ -0,0 +1,81 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::fake;
+
+type Pwm = super::Pwm<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Pwm::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Pwm::<2>::new();
+    kernel.add_driver(&driver);
+
+    assert!(Pwm::driver_check());
+    assert_eq!(Pwm::count(), Ok(2));
+}
+
+#[test]
+fn limits() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Pwm::<2>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Pwm::get_max_frequency(0), Ok(1_000_000));
+    assert_eq!(Pwm::get_max_frequency(2), Err(ErrorCode::Invalid));
+    assert_eq!(Pwm::get_max_duty_cycle(), Ok(10_000));
+}
+
+#[test]
+fn start_stop() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Pwm::<2>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Pwm::start(1, 50, 750), Ok(()));
+    assert_eq!(
+        driver.output(1),
+        Some(fake::PwmOutput {
+            frequency_hz: 50,
+            duty_cycle: 750
+        })
+    );
+    assert_eq!(Pwm::start(2, 50, 750), Err(ErrorCode::Invalid));
+    assert_eq!(Pwm::start(0, 50, 10_001), Err(ErrorCode::Invalid));
+    assert_eq!(Pwm::stop(1), Ok(()));
+    assert_eq!(driver.output(1), None);
+}
+
+#[test]
+fn start_percent() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Pwm::<2>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Pwm::start_percent(0, 1000, 25), Ok(()));
+    assert_eq!(Pwm::start_percent(0, 1000, 100), Ok(()));
+    assert_eq!(Pwm::start_percent(0, 1000, 101), Err(ErrorCode::Invalid));
+    assert_eq!(
+        driver.take_history(),
+        [
+            (
+                0,
+                Some(fake::PwmOutput {
+                    frequency_hz: 1000,
+                    duty_cycle: 2500
+                })
+            ),
+            (
+                0,
+                Some(fake::PwmOutput {
+                    frequency_hz: 1000,
+                    duty_cycle: 10_000
+                })
+            ),
+        ]
+    );
+}
@@ -0,0 +1,148 @@
+//! An implementation of `embedded-graphics`' `DrawTarget` on top of `Screen`.
+
+use crate::{Config, Frame, PixelFormat, Screen};
+use core::marker::PhantomData;
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::{Dimensions, OriginDimensions, Size};
+use embedded_graphics_core::pixelcolor::{IntoStorage, Rgb565};
+use embedded_graphics_core::primitives::{PointsIter, Rectangle};
+use embedded_graphics_core::Pixel;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+/// A screen that `embedded-graphics` can draw on, in 16-bit color.
+///
+/// Drawing outside the screen is silently clipped. Solid areas are drawn with
+/// a single fill, and contiguous areas are sent a row at a time, but
+/// individual pixels each take a round trip to the kernel, so prefer drawing
+/// filled shapes and images where possible.
+///
+/// # Example
+/// ```ignore
+/// use libtock::screen::embedded_graphics_core::pixelcolor::{Rgb565, RgbColor};
+/// use libtock::screen::Display;
+///
+/// let mut display = Display::new()?;
+/// display.clear(Rgb565::BLACK)?;
+/// ```
+pub struct Display<S: Syscalls, C: Config = DefaultConfig> {
+    size: Size,
+    _syscalls: PhantomData<(S, C)>,
+}
+
+impl<S: Syscalls, C: Config> Display<S, C> {
+    /// Switches the screen to `PixelFormat::Rgb565` if needed and reads its
+    /// resolution. If the screen is rotated or its resolution changed later, a
+    /// new `Display` must be created.
+    pub fn new() -> Result<Self, ErrorCode> {
+        if Screen::<S, C>::pixel_format()? != PixelFormat::Rgb565 {
+            Screen::<S, C>::set_pixel_format(PixelFormat::Rgb565)?;
+        }
+        let (width, height) = Screen::<S, C>::resolution()?;
+        Ok(Display {
+            size: Size::new(width, height),
+            _syscalls: PhantomData,
+        })
+    }
+
+    // Returns the part of `area` that is on the screen, if any.
+    fn clip(&self, area: &Rectangle) -> Option<Frame> {
+        let area = area.intersection(&self.bounding_box());
+        if area.is_zero_sized() {
+            return None;
+        }
+        Some(Frame::new(
+            area.top_left.x as u16,
+            area.top_left.y as u16,
+            area.size.width as u16,
+            area.size.height as u16,
+        ))
+    }
+}
+
+impl<S: Syscalls, C: Config> OriginDimensions for Display<S, C> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<S: Syscalls, C: Config> DrawTarget for Display<S, C> {
+    type Color = Rgb565;
+    type Error = ErrorCode;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), ErrorCode>
+    where
+        I: IntoIterator<Item = Pixel<Rgb565>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let Some(frame) = self.clip(&Rectangle::new(point, Size::new(1, 1))) {
+                Screen::<S, C>::write(&frame, &pixel_bytes(color))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), ErrorCode>
+    where
+        I: IntoIterator<Item = Rgb565>,
+    {
+        let frame = match self.clip(area) {
+            None => return Ok(()),
+            Some(frame) if area.size == Size::new(frame.width.into(), frame.height.into()) => frame,
+            // Areas that are partly off the screen are drawn pixel by pixel,
+            // which clips them.
+            Some(_) => {
+                return self.draw_iter(
+                    area.points()
+                        .zip(colors)
+                        .map(|(point, color)| Pixel(point, color)),
+                )
+            }
+        };
+
+        // Send each row in pieces of up to ROW_BUFFER_PIXELS pixels. Like the
+        // default implementation, stop early if `colors` runs out.
+        let mut colors = colors.into_iter();
+        let mut buffer = [0; 2 * ROW_BUFFER_PIXELS];
+        for y in frame.y..frame.y + frame.height {
+            let mut x = frame.x;
+            while x < frame.x + frame.width {
+                let mut width = 0;
+                for pixel in buffer
+                    .chunks_exact_mut(2)
+                    .take((frame.x + frame.width - x).into())
+                {
+                    match colors.next() {
+                        Some(color) => pixel.copy_from_slice(&pixel_bytes(color)),
+                        None => break,
+                    }
+                    width += 1;
+                }
+                if width == 0 {
+                    return Ok(());
+                }
+                Screen::<S, C>::write(&Frame::new(x, y, width, 1), &buffer[..2 * width as usize])?;
+                x += width;
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Rgb565) -> Result<(), ErrorCode> {
+        match self.clip(area) {
+            Some(frame) => Screen::<S, C>::fill(&frame, &pixel_bytes(color)),
+            None => Ok(()),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+// The number of pixels `fill_contiguous` sends to the kernel at once.
+const ROW_BUFFER_PIXELS: usize = 64;
+
+// The kernel expects RGB565 pixels most significant byte first.
+fn pixel_bytes(color: Rgb565) -> [u8; 2] {
+    color.into_storage().to_be_bytes()
+}
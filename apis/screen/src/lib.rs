@@ -0,0 +1,312 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform as platform;
+use libtock_platform::allow_ro::AllowRo;
+use libtock_platform::share;
+use libtock_platform::subscribe::Subscribe;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+mod graphics;
+
+pub use embedded_graphics_core;
+pub use graphics::Display;
+
+/// The screen driver.
+///
+/// It draws to a display by writing pixel data into rectangular regions
+/// ("frames") of the screen. Pixel data is laid out row by row, in the
+/// screen's current pixel format. Apps that want to draw shapes and text
+/// should use [`Display`], which implements `embedded-graphics`' `DrawTarget`.
+///
+/// # Example
+/// ```ignore
+/// use libtock::screen::{Frame, Screen};
+///
+/// Screen::set_power(true)?;
+/// let (width, height) = Screen::resolution()?;
+/// // Clear the screen to white (assuming a 16-bit pixel format).
+/// Screen::fill(&Frame::new(0, 0, width, height), &[0xff, 0xff])?;
+/// ```
+pub struct Screen<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> Screen<S, C> {
+    /// Run a check against the screen capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Turns the screen on or off.
+    pub fn set_power(on: bool) -> Result<(), ErrorCode> {
+        Self::run(command::SET_POWER, on as u32, 0)
+    }
+
+    /// Sets the brightness of the screen's backlight. 0 turns the backlight
+    /// off; the range of the remaining values depends on the screen.
+    pub fn set_brightness(brightness: u32) -> Result<(), ErrorCode> {
+        Self::run(command::SET_BRIGHTNESS, brightness, 0)
+    }
+
+    /// Returns the current resolution as `(width, height)`, in pixels. The
+    /// resolution reflects the current rotation.
+    pub fn resolution() -> Result<(u32, u32), ErrorCode> {
+        S::command(DRIVER_NUM, command::GET_RESOLUTION, 0, 0).to_result()
+    }
+
+    /// Changes the resolution. Only the resolutions listed by
+    /// `resolution_mode` are supported.
+    pub fn set_resolution(width: u32, height: u32) -> Result<(), ErrorCode> {
+        Self::run(command::SET_RESOLUTION, width, height)
+    }
+
+    /// Returns the number of resolutions the screen supports.
+    pub fn resolution_modes() -> Result<u32, ErrorCode> {
+        S::command(DRIVER_NUM, command::RESOLUTION_MODES, 0, 0).to_result()
+    }
+
+    /// Returns the supported resolution at `index` as `(width, height)`.
+    pub fn resolution_mode(index: u32) -> Result<(u32, u32), ErrorCode> {
+        S::command(DRIVER_NUM, command::RESOLUTION_MODE, index, 0).to_result()
+    }
+
+    /// Returns the current pixel format.
+    pub fn pixel_format() -> Result<PixelFormat, ErrorCode> {
+        S::command(DRIVER_NUM, command::GET_PIXEL_FORMAT, 0, 0)
+            .to_result()
+            .and_then(PixelFormat::from_u32)
+    }
+
+    /// Changes the pixel format. Only the formats listed by
+    /// `pixel_format_mode` are supported.
+    pub fn set_pixel_format(format: PixelFormat) -> Result<(), ErrorCode> {
+        Self::run(command::SET_PIXEL_FORMAT, format as u32, 0)
+    }
+
+    /// Returns the number of pixel formats the screen supports.
+    pub fn pixel_format_modes() -> Result<u32, ErrorCode> {
+        S::command(DRIVER_NUM, command::PIXEL_FORMAT_MODES, 0, 0).to_result()
+    }
+
+    /// Returns the supported pixel format at `index`.
+    pub fn pixel_format_mode(index: u32) -> Result<PixelFormat, ErrorCode> {
+        S::command(DRIVER_NUM, command::PIXEL_FORMAT_MODE, index, 0)
+            .to_result()
+            .and_then(PixelFormat::from_u32)
+    }
+
+    /// Returns the current rotation.
+    pub fn rotation() -> Result<Rotation, ErrorCode> {
+        S::command(DRIVER_NUM, command::GET_ROTATION, 0, 0)
+            .to_result()
+            .and_then(Rotation::from_u32)
+    }
+
+    /// Rotates the screen. Rotating by 90 or 270 degrees swaps the width and
+    /// height reported by `resolution`.
+    pub fn set_rotation(rotation: Rotation) -> Result<(), ErrorCode> {
+        Self::run(command::SET_ROTATION, rotation as u32, 0)
+    }
+
+    /// Writes `data` into `frame`, row by row, in the current pixel format.
+    /// `data` may cover only the start of the frame.
+    pub fn write(frame: &Frame, data: &[u8]) -> Result<(), ErrorCode> {
+        Self::set_frame(frame)?;
+        Self::run_with_buffer(data, command::WRITE, data.len() as u32)
+    }
+
+    /// Fills `frame` with a single color. `color` holds one pixel in the
+    /// current pixel format.
+    pub fn fill(frame: &Frame, color: &[u8]) -> Result<(), ErrorCode> {
+        Self::set_frame(frame)?;
+        Self::run_with_buffer(color, command::FILL, 0)
+    }
+}
+
+/// A rectangular region of the screen, in pixels.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Frame {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Frame {
+    pub const fn new(x: u16, y: u16, width: u16, height: u16) -> Frame {
+        Frame {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// The layout of a pixel in the screen's memory.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PixelFormat {
+    /// One bit per pixel.
+    Mono = 0,
+    /// 8 bits per pixel: 2 bits of red, 3 of green and 3 of blue.
+    Rgb233 = 1,
+    /// 16 bits per pixel: 5 bits of red, 6 of green and 5 of blue, most
+    /// significant byte first.
+    Rgb565 = 2,
+    /// 24 bits per pixel: 8 bits each of red, green and blue.
+    Rgb888 = 3,
+    /// 32 bits per pixel: 8 bits each of alpha, red, green and blue.
+    Argb8888 = 4,
+}
+
+impl PixelFormat {
+    /// Returns the size of a pixel, in bits.
+    pub fn bits_per_pixel(self) -> u32 {
+        match self {
+            PixelFormat::Mono => 1,
+            PixelFormat::Rgb233 => 8,
+            PixelFormat::Rgb565 => 16,
+            PixelFormat::Rgb888 => 24,
+            PixelFormat::Argb8888 => 32,
+        }
+    }
+
+    fn from_u32(value: u32) -> Result<PixelFormat, ErrorCode> {
+        match value {
+            0 => Ok(PixelFormat::Mono),
+            1 => Ok(PixelFormat::Rgb233),
+            2 => Ok(PixelFormat::Rgb565),
+            3 => Ok(PixelFormat::Rgb888),
+            4 => Ok(PixelFormat::Argb8888),
+            _ => Err(ErrorCode::Fail),
+        }
+    }
+}
+
+/// The rotation of the screen, clockwise from its native orientation.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Rotation {
+    Normal = 0,
+    Rotated90 = 1,
+    Rotated180 = 2,
+    Rotated270 = 3,
+}
+
+impl Rotation {
+    fn from_u32(value: u32) -> Result<Rotation, ErrorCode> {
+        match value {
+            0 => Ok(Rotation::Normal),
+            1 => Ok(Rotation::Rotated90),
+            2 => Ok(Rotation::Rotated180),
+            3 => Ok(Rotation::Rotated270),
+            _ => Err(ErrorCode::Fail),
+        }
+    }
+}
+
+/// System call configuration trait for `Screen`.
+pub trait Config: platform::allow_ro::Config + platform::subscribe::Config {}
+impl<T: platform::allow_ro::Config + platform::subscribe::Config> Config for T {}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls, C: Config> Screen<S, C> {
+    fn set_frame(frame: &Frame) -> Result<(), ErrorCode> {
+        Self::run(
+            command::SET_FRAME,
+            (frame.x as u32) << 16 | frame.y as u32,
+            (frame.width as u32) << 16 | frame.height as u32,
+        )
+    }
+
+    // Runs `command_id` and waits for its completion upcall.
+    fn run(command_id: u32, argument0: u32, argument1: u32) -> Result<(), ErrorCode> {
+        let called = Cell::new(Option::<(u32,)>::None);
+        share::scope(|subscribe| {
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::DONE }>(subscribe, &called)?;
+            Self::start_and_wait(&called, command_id, argument0, argument1)
+        })
+    }
+
+    // Shares `data`, runs `command_id`, and waits for its completion upcall.
+    fn run_with_buffer(data: &[u8], command_id: u32, argument0: u32) -> Result<(), ErrorCode> {
+        let called = Cell::new(Option::<(u32,)>::None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, { allow_ro::BUFFER }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::DONE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<C, DRIVER_NUM, { allow_ro::BUFFER }>(allow_ro, data)?;
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::DONE }>(subscribe, &called)?;
+            Self::start_and_wait(&called, command_id, argument0, 0)
+        })
+    }
+
+    fn start_and_wait(
+        called: &Cell<Option<(u32,)>>,
+        command_id: u32,
+        argument0: u32,
+        argument1: u32,
+    ) -> Result<(), ErrorCode> {
+        // When this fails, `called` is guaranteed unmodified,
+        // because upcalls are never processed until we call `yield`.
+        S::command(DRIVER_NUM, command_id, argument0, argument1).to_result::<(), ErrorCode>()?;
+
+        loop {
+            S::yield_wait();
+            if let Some((status,)) = called.get() {
+                return match status {
+                    0 => Ok(()),
+                    e_status => Err(e_status.try_into().unwrap_or(ErrorCode::Fail)),
+                };
+            }
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x90001;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const SET_POWER: u32 = 2;
+    pub const SET_BRIGHTNESS: u32 = 3;
+    pub const RESOLUTION_MODES: u32 = 11;
+    pub const RESOLUTION_MODE: u32 = 12;
+    pub const PIXEL_FORMAT_MODES: u32 = 13;
+    pub const PIXEL_FORMAT_MODE: u32 = 14;
+    pub const GET_ROTATION: u32 = 21;
+    pub const SET_ROTATION: u32 = 22;
+    pub const GET_RESOLUTION: u32 = 23;
+    pub const SET_RESOLUTION: u32 = 24;
+    pub const GET_PIXEL_FORMAT: u32 = 25;
+    pub const SET_PIXEL_FORMAT: u32 = 26;
+    pub const SET_FRAME: u32 = 100;
+    pub const WRITE: u32 = 200;
+    pub const FILL: u32 = 300;
+}
+
+mod subscribe {
+    pub const DONE: u32 = 0;
+}
+
+mod allow_ro {
+    pub const BUFFER: u32 = 0;
+}
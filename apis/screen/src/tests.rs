@@ -0,0 +1,145 @@
+use super::*;
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::{OriginDimensions, Point, Size};
+use embedded_graphics_core::pixelcolor::{IntoStorage, Rgb565, RgbColor};
+use embedded_graphics_core::primitives::Rectangle;
+use embedded_graphics_core::Pixel;
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type Screen = super::Screen<fake::Syscalls>;
+type Display = super::Display<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Screen::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Screen::new(4, 2);
+    kernel.add_driver(&driver);
+
+    assert!(Screen::driver_check());
+}
+
+#[test]
+fn settings() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Screen::new(4, 2);
+    kernel.add_driver(&driver);
+
+    assert_eq!(Screen::set_power(true), Ok(()));
+    assert!(driver.is_powered());
+    assert_eq!(Screen::set_brightness(80), Ok(()));
+    assert_eq!(driver.brightness(), 80);
+
+    assert_eq!(Screen::resolution_modes(), Ok(1));
+    assert_eq!(Screen::resolution_mode(0), Ok((4, 2)));
+    assert_eq!(Screen::resolution_mode(1), Err(ErrorCode::Invalid));
+    assert_eq!(Screen::set_resolution(4, 2), Ok(()));
+
+    assert_eq!(Screen::pixel_format_modes(), Ok(2));
+    assert_eq!(Screen::pixel_format_mode(1), Ok(PixelFormat::Rgb888));
+    assert_eq!(Screen::pixel_format(), Ok(PixelFormat::Rgb565));
+    assert_eq!(Screen::set_pixel_format(PixelFormat::Rgb888), Ok(()));
+    assert_eq!(Screen::pixel_format(), Ok(PixelFormat::Rgb888));
+    assert_eq!(PixelFormat::Rgb888.bits_per_pixel(), 24);
+    assert_eq!(
+        Screen::set_pixel_format(PixelFormat::Mono),
+        Err(ErrorCode::NoSupport)
+    );
+
+    assert_eq!(Screen::rotation(), Ok(Rotation::Normal));
+    assert_eq!(Screen::set_rotation(Rotation::Rotated270), Ok(()));
+    assert_eq!(Screen::rotation(), Ok(Rotation::Rotated270));
+    assert_eq!(Screen::resolution(), Ok((2, 4)));
+}
+
+#[test]
+fn write_and_fill() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Screen::new(3, 2);
+    kernel.add_driver(&driver);
+
+    assert_eq!(Screen::fill(&Frame::new(0, 0, 3, 2), &[0xaa, 0xbb]), Ok(()));
+    assert_eq!(
+        Screen::write(&Frame::new(1, 0, 2, 2), &[1, 2, 3, 4, 5, 6]),
+        Ok(())
+    );
+    assert_eq!(
+        driver.framebuffer(),
+        [0xaa, 0xbb, 1, 2, 3, 4, 0xaa, 0xbb, 5, 6, 0xaa, 0xbb]
+    );
+    assert_eq!(
+        Screen::write(&Frame::new(2, 0, 2, 1), &[0; 4]),
+        Err(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        Screen::write(&Frame::new(0, 0, 1, 1), &[0; 4]),
+        Err(ErrorCode::Size)
+    );
+}
+
+#[test]
+fn display() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Screen::new(70, 2);
+    kernel.add_driver(&driver);
+    assert_eq!(Screen::set_pixel_format(PixelFormat::Rgb888), Ok(()));
+
+    let mut display = Display::new().unwrap();
+    assert_eq!(Screen::pixel_format(), Ok(PixelFormat::Rgb565));
+    assert_eq!(display.size(), Size::new(70, 2));
+
+    assert_eq!(display.clear(Rgb565::RED), Ok(()));
+    assert_eq!(driver.pixel(69, 1), [0xf8, 0x00]);
+
+    // Pixels off the screen are clipped.
+    let pixels = [
+        Pixel(Point::new(1, 1), Rgb565::GREEN),
+        Pixel(Point::new(70, 1), Rgb565::GREEN),
+        Pixel(Point::new(-1, 0), Rgb565::GREEN),
+    ];
+    assert_eq!(display.draw_iter(pixels), Ok(()));
+    assert_eq!(driver.pixel(1, 1), [0x07, 0xe0]);
+
+    // A contiguous area wider than the row buffer.
+    let area = Rectangle::new(Point::new(0, 0), Size::new(70, 1));
+    let colors = (0..70).map(|i| Rgb565::new(0, i % 64, 0));
+    assert_eq!(display.fill_contiguous(&area, colors), Ok(()));
+    assert_eq!(
+        driver.pixel(65, 0),
+        Rgb565::new(0, 1, 0).into_storage().to_be_bytes()
+    );
+
+    // A contiguous area partly off the screen.
+    let area = Rectangle::new(Point::new(68, 1), Size::new(3, 1));
+    assert_eq!(display.fill_contiguous(&area, [Rgb565::BLUE; 3]), Ok(()));
+    assert_eq!(driver.pixel(68, 1), [0x00, 0x1f]);
+    assert_eq!(driver.pixel(69, 1), [0x00, 0x1f]);
+    assert_eq!(driver.pixel(67, 1), [0xf8, 0x00]);
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Screen::new(4, 2);
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::DONE,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::SET_BRIGHTNESS,
+        argument0: 10,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::Off)),
+    });
+
+    assert_eq!(Screen::set_brightness(10), Err(ErrorCode::Off));
+}
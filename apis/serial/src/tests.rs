@@ -0,0 +1,72 @@
+use super::*;
+use core::fmt::Write;
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+const UART: u32 = 0x90010;
+
+type Console = super::Serial<fake::Syscalls, 1>;
+type Uart = super::Serial<fake::Syscalls, UART>;
+
+#[test]
+fn no_driver() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Console::new();
+    kernel.add_driver(&driver);
+    assert!(!Uart::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Serial::<UART>::new();
+    kernel.add_driver(&driver);
+
+    assert!(Uart::driver_check());
+    assert!(!Console::driver_check());
+}
+
+#[test]
+fn separate_drivers() {
+    let kernel = fake::Kernel::new();
+    let console = fake::Console::new();
+    let uart = fake::Serial::<UART>::new_with_input(b"OK\r\n");
+    kernel.add_driver(&console);
+    kernel.add_driver(&uart);
+
+    Uart::write(b"AT\r\n").unwrap();
+    write!(Console::writer(), "sent AT").unwrap();
+    assert_eq!(uart.take_bytes(), b"AT\r\n");
+    assert_eq!(console.take_bytes(), b"sent AT");
+
+    let mut buf = [0; 8];
+    let (count, res) = Uart::read(&mut buf);
+    res.unwrap();
+    assert_eq!(&buf[..count], b"OK\r\n");
+}
+
+#[test]
+fn failed_print() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Serial::<UART>::new();
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
+        driver_num: UART,
+        buffer_num: allow_ro::WRITE,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: UART,
+        subscribe_num: subscribe::WRITE,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: UART,
+        command_id: command::WRITE,
+        argument0: 2,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::Fail)),
+    });
+
+    assert_eq!(Uart::write(b"AT"), Err(ErrorCode::Fail));
+}
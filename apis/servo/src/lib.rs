@@ -0,0 +1,67 @@
+#![no_std]
+
+use libtock_platform::{ErrorCode, Syscalls};
+
+/// The servo driver, which positions servomotors.
+///
+/// # Example
+/// ```ignore
+/// use libtock::servo::Servo;
+///
+/// // Turn servo 0 to 90 degrees.
+/// Servo::set_angle(0, 90)?;
+/// ```
+pub struct Servo<S: Syscalls>(S);
+
+impl<S: Syscalls> Servo<S> {
+    /// Run a check against the servo capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Returns the number of servos.
+    pub fn count() -> Result<u32, ErrorCode> {
+        S::command(DRIVER_NUM, command::COUNT, 0, 0).to_result()
+    }
+
+    /// Turns `servo` to `angle` degrees, from 0 to `MAX_ANGLE`.
+    pub fn set_angle(servo: u32, angle: u16) -> Result<(), ErrorCode> {
+        if angle > MAX_ANGLE {
+            return Err(ErrorCode::Invalid);
+        }
+        S::command(DRIVER_NUM, command::SET_ANGLE, servo, angle.into()).to_result()
+    }
+
+    /// Returns the angle `servo` was last turned to, in degrees. Fails if it
+    /// was never positioned.
+    pub fn get_angle(servo: u32) -> Result<u16, ErrorCode> {
+        S::command(DRIVER_NUM, command::GET_ANGLE, servo, 0)
+            .to_result()
+            .map(|angle: u32| angle as u16)
+    }
+}
+
+/// The largest angle servos can be turned to, in degrees.
+pub const MAX_ANGLE: u16 = 180;
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x90009;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const SET_ANGLE: u32 = 1;
+    pub const GET_ANGLE: u32 = 2;
+    pub const COUNT: u32 = 3;
+}
@@ -0,0 +1,36 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::fake;
+
+type Servo = super::Servo<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Servo::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Servo::<2>::new();
+    kernel.add_driver(&driver);
+
+    assert!(Servo::driver_check());
+    assert_eq!(Servo::count(), Ok(2));
+}
+
+#[test]
+fn angles() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Servo::<2>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Servo::get_angle(0), Err(ErrorCode::Fail));
+    assert_eq!(Servo::set_angle(0, 90), Ok(()));
+    assert_eq!(Servo::set_angle(0, super::MAX_ANGLE), Ok(()));
+    assert_eq!(Servo::get_angle(0), Ok(180));
+    assert_eq!(driver.angle(0), Some(180));
+    assert_eq!(Servo::set_angle(0, 181), Err(ErrorCode::Invalid));
+    assert_eq!(Servo::set_angle(2, 0), Err(ErrorCode::NoDevice));
+    assert_eq!(driver.take_history(), [(0, 90), (0, 180)]);
+}
@@ -0,0 +1,79 @@
+//! Implementations of the `digest` crate's traits on top of `Sha`.
+
+use crate::{Algorithm, Config, Sha};
+use core::marker::PhantomData;
+use digest::consts::{U32, U48, U64};
+use digest::{FixedOutput, HashMarker, Output, OutputSizeUser, Update};
+use libtock_platform::{DefaultConfig, Syscalls};
+
+macro_rules! hasher {
+    ($(#[$attr:meta])* $name:ident, $algorithm:expr, $output_size:ty) => {
+        $(#[$attr])*
+        ///
+        /// The kernel keeps one hash in progress per process, so only one
+        /// hasher may be in use at a time. The `digest` traits cannot report
+        /// errors, so `update` and `finalize` panic if the kernel fails.
+        pub struct $name<S: Syscalls, C: Config = DefaultConfig> {
+            // Whether the algorithm has been selected for this hash.
+            started: bool,
+            _syscalls: PhantomData<(S, C)>,
+        }
+
+        impl<S: Syscalls, C: Config> $name<S, C> {
+            fn start(&mut self) {
+                if !self.started {
+                    Sha::<S, C>::set_algorithm($algorithm).expect("SHA set_algorithm failed");
+                    self.started = true;
+                }
+            }
+        }
+
+        impl<S: Syscalls, C: Config> Default for $name<S, C> {
+            fn default() -> Self {
+                $name {
+                    started: false,
+                    _syscalls: PhantomData,
+                }
+            }
+        }
+
+        impl<S: Syscalls, C: Config> HashMarker for $name<S, C> {}
+
+        impl<S: Syscalls, C: Config> OutputSizeUser for $name<S, C> {
+            type OutputSize = $output_size;
+        }
+
+        impl<S: Syscalls, C: Config> Update for $name<S, C> {
+            fn update(&mut self, data: &[u8]) {
+                self.start();
+                Sha::<S, C>::update(data).expect("SHA update failed");
+            }
+        }
+
+        impl<S: Syscalls, C: Config> FixedOutput for $name<S, C> {
+            fn finalize_into(mut self, out: &mut Output<Self>) {
+                self.start();
+                Sha::<S, C>::finalize(out).expect("SHA finalize failed");
+            }
+        }
+    };
+}
+
+hasher!(
+    /// A SHA-256 hasher implementing the `digest` crate's traits.
+    Sha256,
+    Algorithm::Sha256,
+    U32
+);
+hasher!(
+    /// A SHA-384 hasher implementing the `digest` crate's traits.
+    Sha384,
+    Algorithm::Sha384,
+    U48
+);
+hasher!(
+    /// A SHA-512 hasher implementing the `digest` crate's traits.
+    Sha512,
+    Algorithm::Sha512,
+    U64
+);
@@ -0,0 +1,191 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform as platform;
+use libtock_platform::allow_ro::AllowRo;
+use libtock_platform::allow_rw::AllowRw;
+use libtock_platform::share;
+use libtock_platform::subscribe::Subscribe;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+mod hasher;
+
+pub use digest;
+pub use hasher::{Sha256, Sha384, Sha512};
+
+/// The SHA driver.
+///
+/// It computes SHA-2 digests using the kernel's (usually hardware-backed) SHA
+/// implementation. Data is hashed incrementally: select an algorithm with
+/// `set_algorithm`, pass the data to `update` in as many pieces as needed, and
+/// retrieve the digest with `finalize`. The kernel keeps one hash in progress
+/// per process.
+///
+/// The [`Sha256`], [`Sha384`] and [`Sha512`] types implement the `digest`
+/// crate's traits on top of this driver.
+///
+/// # Example
+/// ```ignore
+/// use libtock::sha::{Algorithm, Sha};
+///
+/// let mut digest = [0; 32];
+/// Sha::set_algorithm(Algorithm::Sha256)?;
+/// Sha::update(b"hello ")?;
+/// Sha::update(b"world")?;
+/// Sha::finalize(&mut digest)?;
+/// ```
+pub struct Sha<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> Sha<S, C> {
+    /// Run a check against the SHA capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    ///
+    /// The capsule has no separate check command: this selects SHA-256, so it
+    /// must not be called while a hash is in progress.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Selects the algorithm used by the next hash.
+    pub fn set_algorithm(algorithm: Algorithm) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::SET_ALGORITHM, algorithm as u32, 0).to_result()
+    }
+
+    /// Adds `data` to the hash in progress.
+    pub fn update(data: &[u8]) -> Result<(), ErrorCode> {
+        let called = Cell::new(Option::<(u32,)>::None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, { allow_ro::DATA }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::DONE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<C, DRIVER_NUM, { allow_ro::DATA }>(allow_ro, data)?;
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::DONE }>(subscribe, &called)?;
+            Self::wait_for(command::UPDATE, &called)
+        })
+    }
+
+    /// Completes the hash in progress and writes the digest into the start of
+    /// `digest`.
+    ///
+    /// Fails with `ErrorCode::Size` if `digest` is shorter than the selected
+    /// algorithm's output.
+    pub fn finalize(digest: &mut [u8]) -> Result<(), ErrorCode> {
+        let called = Cell::new(Option::<(u32,)>::None);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, { allow_rw::DIGEST }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::DONE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            S::allow_rw::<C, DRIVER_NUM, { allow_rw::DIGEST }>(allow_rw, digest)?;
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::DONE }>(subscribe, &called)?;
+            Self::wait_for(command::FINISH, &called)
+        })
+    }
+
+    /// Computes the digest of `data` using `algorithm` in one call.
+    pub fn digest(algorithm: Algorithm, data: &[u8], digest: &mut [u8]) -> Result<(), ErrorCode> {
+        Self::set_algorithm(algorithm)?;
+        Self::update(data)?;
+        Self::finalize(digest)
+    }
+}
+
+/// A SHA-2 algorithm.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Algorithm {
+    Sha256 = 0,
+    Sha384 = 1,
+    Sha512 = 2,
+}
+
+impl Algorithm {
+    /// Returns the length of the algorithm's digest, in bytes.
+    pub const fn output_len(self) -> usize {
+        match self {
+            Algorithm::Sha256 => 32,
+            Algorithm::Sha384 => 48,
+            Algorithm::Sha512 => 64,
+        }
+    }
+}
+
+/// System call configuration trait for `Sha`.
+pub trait Config:
+    platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config
+{
+}
+impl<T: platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config>
+    Config for T
+{
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls, C: Config> Sha<S, C> {
+    // Runs `command_id` and waits for its completion upcall.
+    fn wait_for(command_id: u32, called: &Cell<Option<(u32,)>>) -> Result<(), ErrorCode> {
+        // When this fails, `called` is guaranteed unmodified,
+        // because upcalls are never processed until we call `yield`.
+        S::command(DRIVER_NUM, command_id, 0, 0).to_result::<(), ErrorCode>()?;
+
+        loop {
+            S::yield_wait();
+            if let Some((status,)) = called.get() {
+                return match status {
+                    0 => Ok(()),
+                    e_status => Err(e_status.try_into().unwrap_or(ErrorCode::Fail)),
+                };
+            }
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x40005;
+
+// Command IDs
+mod command {
+    // Tock's SHA capsule has no separate driver check: command 0 is
+    // set_algorithm, followed by run (1), update (2) and finish (3). It only
+    // records the algorithm for the process's next hash, without touching the
+    // hardware, and succeeds with argument 0 (SHA-256). So `driver_check` is
+    // safe as long as no hash is in progress; `set_algorithm` is called before
+    // every hash anyway.
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const SET_ALGORITHM: u32 = 0;
+    pub const UPDATE: u32 = 2;
+    pub const FINISH: u32 = 3;
+}
+
+mod subscribe {
+    pub const DONE: u32 = 0;
+}
+
+mod allow_ro {
+    pub const DATA: u32 = 1;
+}
+
+mod allow_rw {
+    pub const DIGEST: u32 = 2;
+}
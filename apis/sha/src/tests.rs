@@ -0,0 +1,113 @@
+use super::*;
+use digest::Digest;
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type Sha = super::Sha<fake::Syscalls>;
+
+// Digests of "abc", from FIPS 180-2.
+const ABC_SHA256: [u8; 32] = [
+    0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae, 0x22, 0x23,
+    0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61, 0xf2, 0x00, 0x15, 0xad,
+];
+const ABC_SHA384: [u8; 48] = [
+    0xcb, 0x00, 0x75, 0x3f, 0x45, 0xa3, 0x5e, 0x8b, 0xb5, 0xa0, 0x3d, 0x69, 0x9a, 0xc6, 0x50, 0x07,
+    0x27, 0x2c, 0x32, 0xab, 0x0e, 0xde, 0xd1, 0x63, 0x1a, 0x8b, 0x60, 0x5a, 0x43, 0xff, 0x5b, 0xed,
+    0x80, 0x86, 0x07, 0x2b, 0xa1, 0xe7, 0xcc, 0x23, 0x58, 0xba, 0xec, 0xa1, 0x34, 0xc8, 0x25, 0xa7,
+];
+const ABC_SHA512: [u8; 64] = [
+    0xdd, 0xaf, 0x35, 0xa1, 0x93, 0x61, 0x7a, 0xba, 0xcc, 0x41, 0x73, 0x49, 0xae, 0x20, 0x41, 0x31,
+    0x12, 0xe6, 0xfa, 0x4e, 0x89, 0xa9, 0x7e, 0xa2, 0x0a, 0x9e, 0xee, 0xe6, 0x4b, 0x55, 0xd3, 0x9a,
+    0x21, 0x92, 0x99, 0x2a, 0x27, 0x4f, 0xc1, 0xa8, 0x36, 0xba, 0x3c, 0x23, 0xa3, 0xfe, 0xeb, 0xbd,
+    0x45, 0x4d, 0x44, 0x23, 0x64, 0x3c, 0xe8, 0x0e, 0x2a, 0x9a, 0xc9, 0x4f, 0xa5, 0x4c, 0xa4, 0x9f,
+];
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Sha::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Sha::new();
+    kernel.add_driver(&driver);
+
+    assert!(Sha::driver_check());
+}
+
+#[test]
+fn digest() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Sha::new();
+    kernel.add_driver(&driver);
+
+    let mut digest = [0; 64];
+    assert_eq!(Sha::digest(Algorithm::Sha256, b"abc", &mut digest), Ok(()));
+    assert_eq!(digest[..32], ABC_SHA256);
+    assert_eq!(Sha::digest(Algorithm::Sha384, b"abc", &mut digest), Ok(()));
+    assert_eq!(digest[..48], ABC_SHA384);
+    assert_eq!(Sha::digest(Algorithm::Sha512, b"abc", &mut digest), Ok(()));
+    assert_eq!(digest, ABC_SHA512);
+}
+
+#[test]
+fn streaming() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Sha::new();
+    kernel.add_driver(&driver);
+
+    let mut digest = [0; 32];
+    assert_eq!(Sha::set_algorithm(Algorithm::Sha256), Ok(()));
+    assert_eq!(Sha::update(b"a"), Ok(()));
+    assert_eq!(Sha::update(b""), Ok(()));
+    assert_eq!(Sha::update(b"bc"), Ok(()));
+    assert_eq!(Sha::finalize(&mut digest), Ok(()));
+    assert_eq!(digest, ABC_SHA256);
+
+    // The digest buffer is too small for SHA-512.
+    assert_eq!(Sha::set_algorithm(Algorithm::Sha512), Ok(()));
+    assert_eq!(Sha::finalize(&mut digest), Err(ErrorCode::Size));
+}
+
+#[test]
+fn digest_traits() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Sha::new();
+    kernel.add_driver(&driver);
+
+    let mut hasher = Sha256::<fake::Syscalls>::new();
+    hasher.update(b"ab");
+    hasher.update(b"c");
+    assert_eq!(hasher.finalize()[..], ABC_SHA256);
+    assert_eq!(Sha384::<fake::Syscalls>::digest(b"abc")[..], ABC_SHA384);
+    assert_eq!(Sha512::<fake::Syscalls>::digest(b"abc")[..], ABC_SHA512);
+    assert_eq!(driver.output_len(), Some(64));
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Sha::new();
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_ro::DATA,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::DONE,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::UPDATE,
+        argument0: 0,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+
+    assert_eq!(Sha::update(b"abc"), Err(ErrorCode::Busy));
+}
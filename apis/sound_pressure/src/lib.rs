@@ -0,0 +1,123 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform as platform;
+use libtock_platform::share::{self, Handle};
+use libtock_platform::subscribe::{OneId, Subscribe};
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls, Upcall};
+
+/// The sound pressure sensor driver.
+///
+/// It reads the sound pressure level around the board, in decibels. The
+/// microphone must be enabled before readings are started.
+///
+/// # Example
+/// ```ignore
+/// use libtock::sound_pressure::SoundPressure;
+///
+/// SoundPressure::enable()?;
+/// let decibels = SoundPressure::read_sync()?;
+/// SoundPressure::disable()?;
+/// ```
+pub struct SoundPressure<S: Syscalls, C: platform::subscribe::Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: platform::subscribe::Config> SoundPressure<S, C> {
+    /// Run a check against the sound pressure capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Powers up the microphone.
+    pub fn enable() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::ENABLE, 0, 0).to_result()
+    }
+
+    /// Powers down the microphone.
+    pub fn disable() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::DISABLE, 0, 0).to_result()
+    }
+
+    /// Starts a reading and returns immediately. The reading is delivered to
+    /// the registered [`SoundPressureListener`].
+    pub fn read() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::READ, 0, 0).to_result()
+    }
+
+    /// Registers the listener for readings.
+    ///
+    /// There can be only one listener registered at a time. Each time this
+    /// function is used, it will replace the previously registered listener.
+    pub fn register_listener<'share, F: Fn(u32)>(
+        listener: &'share SoundPressureListener<F>,
+        subscribe: Handle<Subscribe<'share, S, DRIVER_NUM, 0>>,
+    ) -> Result<(), ErrorCode> {
+        S::subscribe::<_, _, C, DRIVER_NUM, 0>(subscribe, listener)
+    }
+
+    /// Unregister the listener for readings.
+    pub fn unregister_listener() {
+        S::unsubscribe(DRIVER_NUM, subscribe::READING)
+    }
+
+    /// Reads the sound pressure level, in decibels, and waits for the result.
+    pub fn read_sync() -> Result<u32, ErrorCode> {
+        let reading = Cell::new(None);
+        let listener = SoundPressureListener(|decibels| reading.set(Some(decibels)));
+        share::scope(|subscribe| {
+            Self::register_listener(&listener, subscribe)?;
+
+            // When this fails, `reading` is guaranteed unmodified,
+            // because upcalls are never processed until we call `yield`.
+            Self::read()?;
+
+            loop {
+                if let Some(decibels) = reading.get() {
+                    return Ok(decibels);
+                }
+                S::yield_wait();
+            }
+        })
+    }
+}
+
+/// A wrapper around a closure to be registered and called with each sound
+/// pressure reading, in decibels.
+///
+/// ```ignore
+/// let listener = SoundPressureListener(|decibels| {
+///     // make use of the reading
+/// });
+/// ```
+pub struct SoundPressureListener<F: Fn(u32)>(pub F);
+
+impl<F: Fn(u32)> Upcall<OneId<DRIVER_NUM, 0>> for SoundPressureListener<F> {
+    fn upcall(&self, decibels: u32, _arg1: u32, _arg2: u32) {
+        self.0(decibels)
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x60006;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const READ: u32 = 1;
+    pub const ENABLE: u32 = 2;
+    pub const DISABLE: u32 = 3;
+}
+
+mod subscribe {
+    pub const READING: u32 = 0;
+}
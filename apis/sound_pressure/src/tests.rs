@@ -0,0 +1,88 @@
+use super::*;
+use core::cell::Cell;
+use libtock_platform::{ErrorCode, YieldNoWaitReturn};
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type SoundPressure = super::SoundPressure<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!SoundPressure::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SoundPressure::new();
+    kernel.add_driver(&driver);
+
+    assert!(SoundPressure::driver_check());
+}
+
+#[test]
+fn enable_disable() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SoundPressure::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(SoundPressure::enable(), Ok(()));
+    assert!(driver.is_enabled());
+    assert_eq!(SoundPressure::disable(), Ok(()));
+    assert!(!driver.is_enabled());
+    assert_eq!(SoundPressure::read(), Err(ErrorCode::Off));
+}
+
+#[test]
+fn read() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SoundPressure::new();
+    kernel.add_driver(&driver);
+    SoundPressure::enable().unwrap();
+
+    let reading = Cell::new(None);
+    let listener = SoundPressureListener(|decibels| reading.set(Some(decibels)));
+    share::scope(|subscribe| {
+        assert_eq!(
+            SoundPressure::register_listener(&listener, subscribe),
+            Ok(())
+        );
+        assert_eq!(SoundPressure::read(), Ok(()));
+        assert_eq!(SoundPressure::read(), Err(ErrorCode::Busy));
+        driver.set_value(48);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+    });
+    assert_eq!(reading.get(), Some(48));
+}
+
+#[test]
+fn read_sync() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SoundPressure::new();
+    kernel.add_driver(&driver);
+    SoundPressure::enable().unwrap();
+
+    driver.set_value_sync(72);
+    assert_eq!(SoundPressure::read_sync(), Ok(72));
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SoundPressure::new();
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::READING,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::READ,
+        argument0: 0,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+
+    assert_eq!(SoundPressure::read_sync(), Err(ErrorCode::Busy));
+}
@@ -0,0 +1,236 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform as platform;
+use libtock_platform::allow_ro::AllowRo;
+use libtock_platform::allow_rw::AllowRw;
+use libtock_platform::share;
+use libtock_platform::subscribe::Subscribe;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+/// The SPI controller driver.
+///
+/// It allows a process to act as the controller on an SPI bus. Transfers are
+/// full-duplex: every byte written to the selected peripheral clocks one byte
+/// back from it.
+///
+/// # Example
+/// ```ignore
+/// use libtock::spi_controller::{ClockPhase, ClockPolarity, SpiController};
+///
+/// SpiController::select(0)?;
+/// SpiController::set_rate(1_000_000)?;
+/// SpiController::set_polarity(ClockPolarity::IdleLow)?;
+/// SpiController::set_phase(ClockPhase::SampleLeading)?;
+///
+/// // Sends a read command and receives the two-byte response.
+/// let mut read = [0; 3];
+/// SpiController::read_write(&[0x9f, 0, 0], &mut read)?;
+/// ```
+pub struct SpiController<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> SpiController<S, C> {
+    /// Run a check against the SPI controller capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Selects the chip select line used by subsequent transfers.
+    pub fn select(chip_select: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::SET_CHIP_SELECT, chip_select, 0).to_result()
+    }
+
+    /// Returns the currently selected chip select line.
+    pub fn get_chip_select() -> Result<u32, ErrorCode> {
+        S::command(DRIVER_NUM, command::GET_CHIP_SELECT, 0, 0).to_result()
+    }
+
+    /// Sets the clock rate, in Hz. The hardware may not support the exact rate
+    /// requested; use `get_rate` to find the rate actually used.
+    pub fn set_rate(rate_hz: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::SET_RATE, rate_hz, 0).to_result()
+    }
+
+    /// Returns the clock rate, in Hz.
+    pub fn get_rate() -> Result<u32, ErrorCode> {
+        S::command(DRIVER_NUM, command::GET_RATE, 0, 0).to_result()
+    }
+
+    pub fn set_phase(phase: ClockPhase) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::SET_PHASE, phase as u32, 0).to_result()
+    }
+
+    pub fn get_phase() -> Result<ClockPhase, ErrorCode> {
+        let phase: u32 = S::command(DRIVER_NUM, command::GET_PHASE, 0, 0).to_result()?;
+        Ok(phase.into())
+    }
+
+    pub fn set_polarity(polarity: ClockPolarity) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::SET_POLARITY, polarity as u32, 0).to_result()
+    }
+
+    pub fn get_polarity() -> Result<ClockPolarity, ErrorCode> {
+        let polarity: u32 = S::command(DRIVER_NUM, command::GET_POLARITY, 0, 0).to_result()?;
+        Ok(polarity.into())
+    }
+
+    /// Writes all of `write` to the selected peripheral, storing the bytes
+    /// clocked back into the start of `read`.
+    ///
+    /// Fails with `ErrorCode::Size` if `read` is shorter than `write`.
+    pub fn read_write(write: &[u8], read: &mut [u8]) -> Result<(), ErrorCode> {
+        if read.len() < write.len() {
+            return Err(ErrorCode::Size);
+        }
+        let called = Cell::new(Option::<(u32, u32)>::None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, { allow_ro::WRITE }>,
+                AllowRw<_, DRIVER_NUM, { allow_rw::READ }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::COMPLETE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, allow_rw, subscribe) = handle.split();
+            S::allow_ro::<C, DRIVER_NUM, { allow_ro::WRITE }>(allow_ro, write)?;
+            S::allow_rw::<C, DRIVER_NUM, { allow_rw::READ }>(allow_rw, read)?;
+            Self::transfer(subscribe, &called, write.len())
+        })
+    }
+
+    /// Writes all of `write` to the selected peripheral, discarding the bytes
+    /// clocked back.
+    pub fn write(write: &[u8]) -> Result<(), ErrorCode> {
+        let called = Cell::new(Option::<(u32, u32)>::None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, { allow_ro::WRITE }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::COMPLETE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<C, DRIVER_NUM, { allow_ro::WRITE }>(allow_ro, write)?;
+            Self::transfer(subscribe, &called, write.len())
+        })
+    }
+}
+
+/// The clock edge on which data is sampled.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ClockPhase {
+    /// Data is sampled on the first clock edge (CPHA = 0).
+    SampleLeading = 0,
+    /// Data is sampled on the second clock edge (CPHA = 1).
+    SampleTrailing = 1,
+}
+
+impl From<u32> for ClockPhase {
+    fn from(value: u32) -> ClockPhase {
+        match value {
+            0 => ClockPhase::SampleLeading,
+            _ => ClockPhase::SampleTrailing,
+        }
+    }
+}
+
+/// The level of the clock line while the bus is idle.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ClockPolarity {
+    /// The clock idles low (CPOL = 0).
+    IdleLow = 0,
+    /// The clock idles high (CPOL = 1).
+    IdleHigh = 1,
+}
+
+impl From<u32> for ClockPolarity {
+    fn from(value: u32) -> ClockPolarity {
+        match value {
+            0 => ClockPolarity::IdleLow,
+            _ => ClockPolarity::IdleHigh,
+        }
+    }
+}
+
+/// System call configuration trait for `SpiController`.
+pub trait Config:
+    platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config
+{
+}
+impl<T: platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config>
+    Config for T
+{
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls, C: Config> SpiController<S, C> {
+    // Starts a transfer of `len` bytes using the buffers already shared with
+    // the kernel, and waits for it to complete.
+    fn transfer<'share>(
+        subscribe: share::Handle<Subscribe<'share, S, DRIVER_NUM, { subscribe::COMPLETE }>>,
+        called: &'share Cell<Option<(u32, u32)>>,
+        len: usize,
+    ) -> Result<(), ErrorCode> {
+        S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::COMPLETE }>(subscribe, called)?;
+
+        // When this fails, `called` is guaranteed unmodified,
+        // because upcalls are never processed until we call `yield`.
+        S::command(DRIVER_NUM, command::READ_WRITE, len as u32, 0).to_result::<(), ErrorCode>()?;
+
+        loop {
+            S::yield_wait();
+            if let Some((_len, status)) = called.get() {
+                return match status {
+                    0 => Ok(()),
+                    e_status => Err(e_status.try_into().unwrap_or(ErrorCode::Fail)),
+                };
+            }
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x20001;
+
+// Command IDs
+#[allow(unused)]
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const READ_WRITE: u32 = 2;
+    pub const SET_CHIP_SELECT: u32 = 3;
+    pub const GET_CHIP_SELECT: u32 = 4;
+    pub const SET_RATE: u32 = 5;
+    pub const GET_RATE: u32 = 6;
+    pub const SET_PHASE: u32 = 7;
+    pub const GET_PHASE: u32 = 8;
+    pub const SET_POLARITY: u32 = 9;
+    pub const GET_POLARITY: u32 = 10;
+}
+
+mod subscribe {
+    pub const COMPLETE: u32 = 0;
+}
+
+mod allow_ro {
+    pub const WRITE: u32 = 0;
+}
+
+mod allow_rw {
+    pub const READ: u32 = 0;
+}
@@ -0,0 +1,119 @@
+use super::*;
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type SpiController = super::SpiController<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!SpiController::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SpiController::<2>::new();
+    kernel.add_driver(&driver);
+
+    assert!(SpiController::driver_check());
+    assert_eq!(driver.take_transfers(), []);
+}
+
+#[test]
+fn configure() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SpiController::<2>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(SpiController::select(1), Ok(()));
+    assert_eq!(SpiController::get_chip_select(), Ok(1));
+    assert_eq!(SpiController::select(2), Err(ErrorCode::Invalid));
+    assert_eq!(driver.get_chip_select(), 1);
+
+    assert_eq!(SpiController::set_rate(8_000_000), Ok(()));
+    assert_eq!(SpiController::get_rate(), Ok(8_000_000));
+
+    assert_eq!(SpiController::get_polarity(), Ok(ClockPolarity::IdleLow));
+    assert_eq!(SpiController::set_polarity(ClockPolarity::IdleHigh), Ok(()));
+    assert_eq!(SpiController::get_polarity(), Ok(ClockPolarity::IdleHigh));
+
+    assert_eq!(SpiController::get_phase(), Ok(ClockPhase::SampleLeading));
+    assert_eq!(SpiController::set_phase(ClockPhase::SampleTrailing), Ok(()));
+    assert_eq!(SpiController::get_phase(), Ok(ClockPhase::SampleTrailing));
+}
+
+#[test]
+fn read_write_loopback() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SpiController::<1>::new();
+    kernel.add_driver(&driver);
+
+    let mut read = [0; 4];
+    SpiController::read_write(b"abc", &mut read).unwrap();
+    assert_eq!(&read, b"abc\0");
+    assert_eq!(
+        driver.take_transfers(),
+        [fake::Transfer {
+            chip_select: 0,
+            written: b"abc".to_vec(),
+        }]
+    );
+}
+
+#[test]
+fn read_write_device() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SpiController::<1>::new();
+    kernel.add_driver(&driver);
+    driver.add_response(&[0xff, 0xef, 0x40]);
+
+    let mut read = [0; 3];
+    SpiController::read_write(&[0x9f, 0, 0], &mut read).unwrap();
+    assert_eq!(read, [0xff, 0xef, 0x40]);
+
+    assert_eq!(
+        SpiController::read_write(&[0; 4], &mut read),
+        Err(ErrorCode::Size)
+    );
+}
+
+#[test]
+fn write() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SpiController::<1>::new();
+    kernel.add_driver(&driver);
+
+    SpiController::write(b"foo").unwrap();
+    SpiController::write(b"bar").unwrap();
+    let transfers = driver.take_transfers();
+    assert_eq!(transfers.len(), 2);
+    assert_eq!(transfers[0].written, b"foo");
+    assert_eq!(transfers[1].written, b"bar");
+}
+
+#[test]
+fn failed_transfer() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SpiController::<1>::new();
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_ro::WRITE,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::COMPLETE,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::READ_WRITE,
+        argument0: 2,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+
+    assert_eq!(SpiController::write(b"ab"), Err(ErrorCode::Busy));
+}
@@ -0,0 +1,199 @@
+#![no_std]
+
+use core::cell::Cell;
+use core::marker::PhantomData;
+use libtock_platform as platform;
+use libtock_platform::allow_ro::AllowRo;
+use libtock_platform::share;
+use libtock_platform::subscribe::Subscribe;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+/// The text screen driver.
+///
+/// It drives character displays, such as HD44780-style LCDs, which show a grid
+/// of characters. Text is written at the cursor, which moves forward with each
+/// character written.
+///
+/// # Example
+/// ```ignore
+/// use libtock::text_screen::TextScreen;
+///
+/// TextScreen::display_on()?;
+/// TextScreen::clear()?;
+/// TextScreen::write(b"Hello")?;
+/// TextScreen::set_cursor(0, 1)?;
+/// ufmt::uwrite!(TextScreen::writer(), "{} apps", 3)?;
+/// ```
+pub struct TextScreen<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> TextScreen<S, C> {
+    /// Run a check against the text screen capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Returns the size of the screen as `(columns, rows)`, in characters.
+    pub fn size() -> Result<(u32, u32), ErrorCode> {
+        S::command(DRIVER_NUM, command::GET_SIZE, 0, 0).to_result()
+    }
+
+    /// Shows the text on the screen. The text is kept while the display is
+    /// off.
+    pub fn display_on() -> Result<(), ErrorCode> {
+        Self::run(command::DISPLAY_ON, 0, 0)
+    }
+
+    pub fn display_off() -> Result<(), ErrorCode> {
+        Self::run(command::DISPLAY_OFF, 0, 0)
+    }
+
+    /// Makes the character under the cursor blink.
+    pub fn blink_on() -> Result<(), ErrorCode> {
+        Self::run(command::BLINK_ON, 0, 0)
+    }
+
+    pub fn blink_off() -> Result<(), ErrorCode> {
+        Self::run(command::BLINK_OFF, 0, 0)
+    }
+
+    /// Shows the cursor as an underline.
+    pub fn show_cursor() -> Result<(), ErrorCode> {
+        Self::run(command::SHOW_CURSOR, 0, 0)
+    }
+
+    pub fn hide_cursor() -> Result<(), ErrorCode> {
+        Self::run(command::HIDE_CURSOR, 0, 0)
+    }
+
+    /// Moves the cursor to `column` of `row`, both counted from 0.
+    pub fn set_cursor(column: u32, row: u32) -> Result<(), ErrorCode> {
+        Self::run(command::SET_CURSOR, column, row)
+    }
+
+    /// Blanks the screen and moves the cursor to the top left corner.
+    pub fn clear() -> Result<(), ErrorCode> {
+        Self::run(command::CLEAR, 0, 0)
+    }
+
+    /// Moves the cursor to the top left corner, keeping the text.
+    pub fn home() -> Result<(), ErrorCode> {
+        Self::run(command::HOME, 0, 0)
+    }
+
+    /// Writes `text` at the cursor. Text that runs past the end of a row
+    /// continues on the next one.
+    pub fn write(text: &[u8]) -> Result<(), ErrorCode> {
+        let called = Cell::new(Option::<(u32,)>::None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, { allow_ro::BUFFER }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::DONE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<C, DRIVER_NUM, { allow_ro::BUFFER }>(allow_ro, text)?;
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::DONE }>(subscribe, &called)?;
+            Self::start_and_wait(&called, command::WRITE, text.len() as u32, 0)
+        })
+    }
+
+    /// Returns a writer for use with `ufmt`'s `uwrite!` and `uwriteln!`.
+    pub fn writer() -> TextScreenWriter<S, C> {
+        TextScreenWriter {
+            syscalls: PhantomData,
+        }
+    }
+}
+
+/// Writes formatted text at the cursor of the text screen.
+pub struct TextScreenWriter<S: Syscalls, C: Config = DefaultConfig> {
+    syscalls: PhantomData<(S, C)>,
+}
+
+impl<S: Syscalls, C: Config> ufmt::uWrite for TextScreenWriter<S, C> {
+    type Error = ErrorCode;
+
+    fn write_str(&mut self, s: &str) -> Result<(), ErrorCode> {
+        TextScreen::<S, C>::write(s.as_bytes())
+    }
+}
+
+/// System call configuration trait for `TextScreen`.
+pub trait Config: platform::allow_ro::Config + platform::subscribe::Config {}
+impl<T: platform::allow_ro::Config + platform::subscribe::Config> Config for T {}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls, C: Config> TextScreen<S, C> {
+    // Runs `command_id` and waits for its completion upcall.
+    fn run(command_id: u32, argument0: u32, argument1: u32) -> Result<(), ErrorCode> {
+        let called = Cell::new(Option::<(u32,)>::None);
+        share::scope(|subscribe| {
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::DONE }>(subscribe, &called)?;
+            Self::start_and_wait(&called, command_id, argument0, argument1)
+        })
+    }
+
+    fn start_and_wait(
+        called: &Cell<Option<(u32,)>>,
+        command_id: u32,
+        argument0: u32,
+        argument1: u32,
+    ) -> Result<(), ErrorCode> {
+        // When this fails, `called` is guaranteed unmodified,
+        // because upcalls are never processed until we call `yield`.
+        S::command(DRIVER_NUM, command_id, argument0, argument1).to_result::<(), ErrorCode>()?;
+
+        loop {
+            S::yield_wait();
+            if let Some((status,)) = called.get() {
+                return match status {
+                    0 => Ok(()),
+                    e_status => Err(e_status.try_into().unwrap_or(ErrorCode::Fail)),
+                };
+            }
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x90003;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const GET_SIZE: u32 = 1;
+    pub const WRITE: u32 = 2;
+    pub const DISPLAY_ON: u32 = 3;
+    pub const DISPLAY_OFF: u32 = 4;
+    pub const BLINK_ON: u32 = 5;
+    pub const BLINK_OFF: u32 = 6;
+    pub const SHOW_CURSOR: u32 = 7;
+    pub const HIDE_CURSOR: u32 = 8;
+    pub const CLEAR: u32 = 9;
+    pub const HOME: u32 = 10;
+    pub const SET_CURSOR: u32 = 11;
+}
+
+mod subscribe {
+    pub const DONE: u32 = 0;
+}
+
+mod allow_ro {
+    pub const BUFFER: u32 = 0;
+}
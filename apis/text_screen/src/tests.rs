@@ -0,0 +1,99 @@
+use super::*;
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type TextScreen = super::TextScreen<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!TextScreen::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::TextScreen::new(16, 2);
+    kernel.add_driver(&driver);
+
+    assert!(TextScreen::driver_check());
+    assert_eq!(TextScreen::size(), Ok((16, 2)));
+}
+
+#[test]
+fn display_control() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::TextScreen::new(16, 2);
+    kernel.add_driver(&driver);
+
+    assert_eq!(TextScreen::display_on(), Ok(()));
+    assert!(driver.is_display_on());
+    assert_eq!(TextScreen::blink_on(), Ok(()));
+    assert!(driver.is_blinking());
+    assert_eq!(TextScreen::show_cursor(), Ok(()));
+    assert!(driver.is_cursor_shown());
+    assert_eq!(TextScreen::hide_cursor(), Ok(()));
+    assert!(!driver.is_cursor_shown());
+    assert_eq!(TextScreen::blink_off(), Ok(()));
+    assert!(!driver.is_blinking());
+    assert_eq!(TextScreen::display_off(), Ok(()));
+    assert!(!driver.is_display_on());
+}
+
+#[test]
+fn write() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::TextScreen::new(8, 2);
+    kernel.add_driver(&driver);
+
+    assert_eq!(TextScreen::write(b"Tock"), Ok(()));
+    assert_eq!(TextScreen::set_cursor(2, 1), Ok(()));
+    assert_eq!(TextScreen::write(b"OS"), Ok(()));
+    assert_eq!(TextScreen::set_cursor(8, 1), Err(ErrorCode::Invalid));
+    assert_eq!(driver.rows(), ["Tock    ", "  OS    "]);
+
+    assert_eq!(TextScreen::home(), Ok(()));
+    assert_eq!(TextScreen::write(b"R"), Ok(()));
+    assert_eq!(driver.rows(), ["Rock    ", "  OS    "]);
+    assert_eq!(TextScreen::clear(), Ok(()));
+    assert_eq!(driver.rows(), ["        ", "        "]);
+    assert_eq!(driver.cursor(), (0, 0));
+}
+
+#[test]
+fn writer() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::TextScreen::new(8, 2);
+    kernel.add_driver(&driver);
+
+    assert_eq!(ufmt::uwrite!(TextScreen::writer(), "{} apps", 3), Ok(()));
+    assert_eq!(TextScreen::set_cursor(0, 1), Ok(()));
+    assert_eq!(ufmt::uwrite!(TextScreen::writer(), "up {}s", 42), Ok(()));
+    assert_eq!(driver.rows(), ["3 apps  ", "up 42s  "]);
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::TextScreen::new(16, 2);
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_ro::BUFFER,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::DONE,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::WRITE,
+        argument0: 4,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+
+    assert_eq!(TextScreen::write(b"Tock"), Err(ErrorCode::Busy));
+}
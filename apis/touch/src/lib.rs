@@ -0,0 +1,321 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform as platform;
+use libtock_platform::allow_rw::AllowRw;
+use libtock_platform::share::{self, Handle};
+use libtock_platform::subscribe::{OneId, Subscribe};
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls, Upcall};
+
+mod queue;
+
+pub use queue::{Event, EventQueue};
+
+/// The touch panel driver.
+///
+/// It reports three kinds of events, each to its own listener:
+/// - single-touch events, for the first finger on the panel, once enabled
+///   with `enable_single_touch`;
+/// - gestures, such as swipes and zooms, on panels that recognize them;
+/// - multi-touch events, which report every finger on the panel at once, once
+///   enabled with `enable_multi_touch`. These are read with
+///   `wait_multi_touch`.
+///
+/// Listeners are called as upcalls, during `yield`. Apps that would rather
+/// handle events in their main loop can register an [`EventQueue`] as both
+/// the touch and the gesture listener, and pop events from it.
+///
+/// # Example
+/// ```ignore
+/// use libtock::touch::{Touch, TouchListener};
+///
+/// let listener = TouchListener(|event| {
+///     // make use of event.x, event.y and event.status
+/// });
+/// share::scope(|subscribe| {
+///     Touch::register_touch_listener(&listener, subscribe)?;
+///     Touch::enable_single_touch()?;
+///     loop {
+///         TockSyscalls::yield_wait();
+///     }
+/// });
+/// ```
+pub struct Touch<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> Touch<S, C> {
+    /// Run a check against the touch capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Returns the number of fingers the panel can track at once.
+    pub fn number_of_touches() -> Result<u32, ErrorCode> {
+        S::command(DRIVER_NUM, command::NUMBER_OF_TOUCHES, 0, 0).to_result()
+    }
+
+    /// Starts reporting single-touch events to the touch listener.
+    pub fn enable_single_touch() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::ENABLE_SINGLE_TOUCH, 0, 0).to_result()
+    }
+
+    pub fn disable_single_touch() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::DISABLE_SINGLE_TOUCH, 0, 0).to_result()
+    }
+
+    /// Starts reporting multi-touch events. Until this is called,
+    /// `wait_multi_touch` waits forever.
+    pub fn enable_multi_touch() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::ENABLE_MULTI_TOUCH, 0, 0).to_result()
+    }
+
+    pub fn disable_multi_touch() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::DISABLE_MULTI_TOUCH, 0, 0).to_result()
+    }
+
+    /// Registers the single-touch events listener, which is either a
+    /// [`TouchListener`] or an [`EventQueue`].
+    ///
+    /// There can be only one listener registered at a time. Each time this
+    /// function is used, it will replace the previously registered listener.
+    pub fn register_touch_listener<'share, L: Upcall<OneId<DRIVER_NUM, 0>>>(
+        listener: &'share L,
+        subscribe: Handle<Subscribe<'share, S, DRIVER_NUM, 0>>,
+    ) -> Result<(), ErrorCode> {
+        S::subscribe::<_, _, C, DRIVER_NUM, 0>(subscribe, listener)
+    }
+
+    /// Unregister the single-touch events listener.
+    pub fn unregister_touch_listener() {
+        S::unsubscribe(DRIVER_NUM, subscribe::TOUCH)
+    }
+
+    /// Registers the gesture listener, which is either a [`GestureListener`]
+    /// or an [`EventQueue`].
+    ///
+    /// There can be only one listener registered at a time. Each time this
+    /// function is used, it will replace the previously registered listener.
+    pub fn register_gesture_listener<'share, L: Upcall<OneId<DRIVER_NUM, 1>>>(
+        listener: &'share L,
+        subscribe: Handle<Subscribe<'share, S, DRIVER_NUM, 1>>,
+    ) -> Result<(), ErrorCode> {
+        S::subscribe::<_, _, C, DRIVER_NUM, 1>(subscribe, listener)
+    }
+
+    /// Unregister the gesture listener.
+    pub fn unregister_gesture_listener() {
+        S::unsubscribe(DRIVER_NUM, subscribe::GESTURE)
+    }
+
+    /// Waits for the next multi-touch event, and returns the touches it
+    /// reports. `buffer` holds `TOUCH_RECORD_SIZE` bytes per touch; touches
+    /// that do not fit are left out.
+    pub fn wait_multi_touch(buffer: &mut [u8]) -> Result<MultiTouch<'_>, ErrorCode> {
+        let called = Cell::new(Option::<(u32, u32)>::None);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, { allow_rw::MULTI_TOUCH }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::MULTI_TOUCH }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            S::allow_rw::<C, DRIVER_NUM, { allow_rw::MULTI_TOUCH }>(allow_rw, buffer)?;
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::MULTI_TOUCH }>(subscribe, &called)?;
+
+            // Tells the kernel we are ready for the next event. When this
+            // fails, `called` is guaranteed unmodified, because upcalls are
+            // never processed until we call `yield`.
+            S::command(DRIVER_NUM, command::MULTI_TOUCH_NEXT, 0, 0).to_result::<(), ErrorCode>()?;
+
+            while called.get().is_none() {
+                S::yield_wait();
+            }
+            Ok(())
+        })?;
+
+        let (touches, dropped) = called.get().unwrap_or_default();
+        let len = (touches as usize).min(buffer.len() / TOUCH_RECORD_SIZE);
+        Ok(MultiTouch {
+            records: &buffer[..len * TOUCH_RECORD_SIZE],
+            dropped,
+        })
+    }
+}
+
+/// The size of a touch in the buffer passed to `Touch::wait_multi_touch`.
+pub const TOUCH_RECORD_SIZE: usize = 8;
+
+/// The state of a finger on the panel.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TouchStatus {
+    Released,
+    Pressed,
+    Moved,
+    Unstarted,
+}
+
+impl From<u32> for TouchStatus {
+    fn from(value: u32) -> TouchStatus {
+        match value {
+            0 => TouchStatus::Released,
+            1 => TouchStatus::Pressed,
+            2 => TouchStatus::Moved,
+            _ => TouchStatus::Unstarted,
+        }
+    }
+}
+
+/// A touch on the panel. `size` and `pressure` are 0 on panels that do not
+/// measure them.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TouchEvent {
+    /// Identifies the finger across events. Always 0 for single-touch events.
+    pub id: u8,
+    pub status: TouchStatus,
+    pub x: u16,
+    pub y: u16,
+    pub size: u16,
+    pub pressure: u16,
+}
+
+/// A gesture recognized by the panel.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Gesture {
+    SwipeUp = 0,
+    SwipeDown = 1,
+    SwipeLeft = 2,
+    SwipeRight = 3,
+    ZoomIn = 4,
+    ZoomOut = 5,
+}
+
+impl Gesture {
+    fn from_u32(value: u32) -> Option<Gesture> {
+        match value {
+            0 => Some(Gesture::SwipeUp),
+            1 => Some(Gesture::SwipeDown),
+            2 => Some(Gesture::SwipeLeft),
+            3 => Some(Gesture::SwipeRight),
+            4 => Some(Gesture::ZoomIn),
+            5 => Some(Gesture::ZoomOut),
+            _ => None,
+        }
+    }
+}
+
+/// The touches reported by a multi-touch event.
+pub struct MultiTouch<'a> {
+    records: &'a [u8],
+    dropped: u32,
+}
+
+impl<'a> MultiTouch<'a> {
+    /// Returns the touches, in the order the kernel reported them.
+    pub fn iter(&self) -> impl Iterator<Item = TouchEvent> + 'a {
+        self.records
+            .chunks_exact(TOUCH_RECORD_SIZE)
+            .map(|record| TouchEvent {
+                id: record[0],
+                status: (record[1] as u32).into(),
+                x: u16::from_le_bytes([record[2], record[3]]),
+                y: u16::from_le_bytes([record[4], record[5]]),
+                size: record[6].into(),
+                pressure: record[7].into(),
+            })
+    }
+
+    /// Returns the number of touches.
+    pub fn count(&self) -> usize {
+        self.records.len() / TOUCH_RECORD_SIZE
+    }
+
+    /// Returns the number of multi-touch events the kernel dropped since the
+    /// previous one, because the app was not waiting for them.
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+}
+
+/// A wrapper around a closure to be registered and called when a single-touch
+/// event occurs.
+///
+/// ```ignore
+/// let listener = TouchListener(|event| {
+///     // make use of the event
+/// });
+/// ```
+pub struct TouchListener<F: Fn(TouchEvent)>(pub F);
+
+impl<F: Fn(TouchEvent)> Upcall<OneId<DRIVER_NUM, 0>> for TouchListener<F> {
+    fn upcall(&self, status: u32, position: u32, size_pressure: u32) {
+        self.0(touch_event(status, position, size_pressure))
+    }
+}
+
+/// A wrapper around a closure to be registered and called when a gesture is
+/// recognized.
+pub struct GestureListener<F: Fn(Gesture)>(pub F);
+
+impl<F: Fn(Gesture)> Upcall<OneId<DRIVER_NUM, 1>> for GestureListener<F> {
+    fn upcall(&self, gesture: u32, _arg1: u32, _arg2: u32) {
+        if let Some(gesture) = Gesture::from_u32(gesture) {
+            self.0(gesture)
+        }
+    }
+}
+
+/// System call configuration trait for `Touch`.
+pub trait Config: platform::allow_rw::Config + platform::subscribe::Config {}
+impl<T: platform::allow_rw::Config + platform::subscribe::Config> Config for T {}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+// Decodes the arguments of a single-touch upcall.
+fn touch_event(status: u32, position: u32, size_pressure: u32) -> TouchEvent {
+    TouchEvent {
+        id: 0,
+        status: status.into(),
+        x: (position >> 16) as u16,
+        y: position as u16,
+        size: size_pressure as u16,
+        pressure: (size_pressure >> 16) as u16,
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x90002;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const ENABLE_SINGLE_TOUCH: u32 = 1;
+    pub const DISABLE_SINGLE_TOUCH: u32 = 2;
+    pub const MULTI_TOUCH_NEXT: u32 = 10;
+    pub const ENABLE_MULTI_TOUCH: u32 = 11;
+    pub const DISABLE_MULTI_TOUCH: u32 = 12;
+    pub const NUMBER_OF_TOUCHES: u32 = 100;
+}
+
+mod subscribe {
+    pub const TOUCH: u32 = 0;
+    pub const GESTURE: u32 = 1;
+    pub const MULTI_TOUCH: u32 = 2;
+}
+
+mod allow_rw {
+    pub const MULTI_TOUCH: u32 = 2;
+}
@@ -0,0 +1,112 @@
+//! A queue that collects touch and gesture events for the app to handle
+//! outside of upcalls.
+
+use crate::{touch_event, Gesture, TouchEvent, DRIVER_NUM};
+use core::cell::{Cell, RefCell};
+use libtock_platform::subscribe::OneId;
+use libtock_platform::Upcall;
+
+/// An event reported by the touch driver.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Event {
+    Touch(TouchEvent),
+    Gesture(Gesture),
+}
+
+/// A queue of up to `N` events, filled by the touch driver's upcalls.
+///
+/// Register it as both the touch and the gesture listener, then pop events
+/// after each `yield`. When the queue is full, new events are dropped and
+/// counted.
+///
+/// # Example
+/// ```ignore
+/// use libtock::touch::{Event, EventQueue, Touch};
+///
+/// let queue = EventQueue::<8>::new();
+/// // The touch driver is number 0x90002.
+/// share::scope::<(Subscribe<_, 0x90002, 0>, Subscribe<_, 0x90002, 1>), _, _>(|handle| {
+///     let (touch, gesture) = handle.split();
+///     Touch::register_touch_listener(&queue, touch)?;
+///     Touch::register_gesture_listener(&queue, gesture)?;
+///     Touch::enable_single_touch()?;
+///     loop {
+///         TockSyscalls::yield_wait();
+///         while let Some(event) = queue.pop() {
+///             // handle the event
+///         }
+///     }
+/// });
+/// ```
+pub struct EventQueue<const N: usize> {
+    events: RefCell<[Option<Event>; N]>,
+    // Index of the oldest event.
+    head: Cell<usize>,
+    len: Cell<usize>,
+    dropped: Cell<usize>,
+}
+
+impl<const N: usize> EventQueue<N> {
+    pub const fn new() -> EventQueue<N> {
+        EventQueue {
+            events: RefCell::new([None; N]),
+            head: Cell::new(0),
+            len: Cell::new(0),
+            dropped: Cell::new(0),
+        }
+    }
+
+    /// Removes and returns the oldest event, if any.
+    pub fn pop(&self) -> Option<Event> {
+        if self.len.get() == 0 {
+            return None;
+        }
+        let event = self.events.borrow_mut()[self.head.get()].take();
+        self.head.set((self.head.get() + 1) % N);
+        self.len.set(self.len.get() - 1);
+        event
+    }
+
+    /// Returns the number of events waiting in the queue.
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len.get() == 0
+    }
+
+    /// Returns the number of events dropped because the queue was full.
+    pub fn dropped(&self) -> usize {
+        self.dropped.get()
+    }
+
+    fn push(&self, event: Event) {
+        if self.len.get() == N {
+            self.dropped.set(self.dropped.get() + 1);
+            return;
+        }
+        self.events.borrow_mut()[(self.head.get() + self.len.get()) % N] = Some(event);
+        self.len.set(self.len.get() + 1);
+    }
+}
+
+impl<const N: usize> Default for EventQueue<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Upcall<OneId<DRIVER_NUM, 0>> for EventQueue<N> {
+    fn upcall(&self, status: u32, position: u32, size_pressure: u32) {
+        self.push(Event::Touch(touch_event(status, position, size_pressure)));
+    }
+}
+
+impl<const N: usize> Upcall<OneId<DRIVER_NUM, 1>> for EventQueue<N> {
+    fn upcall(&self, gesture: u32, _arg1: u32, _arg2: u32) {
+        if let Some(gesture) = Gesture::from_u32(gesture) {
+            self.push(Event::Gesture(gesture));
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use super::*;
+use core::cell::Cell;
+use libtock_platform::{share, ErrorCode, YieldNoWaitReturn};
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type Touch = super::Touch<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Touch::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Touch::new(2);
+    kernel.add_driver(&driver);
+
+    assert!(Touch::driver_check());
+    assert_eq!(Touch::number_of_touches(), Ok(2));
+}
+
+#[test]
+fn touch_listener() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Touch::new(1);
+    kernel.add_driver(&driver);
+
+    let last = Cell::new(None);
+    let listener = TouchListener(|event| last.set(Some(event)));
+    share::scope(|subscribe| {
+        assert_eq!(Touch::register_touch_listener(&listener, subscribe), Ok(()));
+        assert_eq!(Touch::enable_single_touch(), Ok(()));
+        driver.inject_touch(fake::TouchEvent {
+            id: 0,
+            status: fake::TouchStatus::Pressed,
+            x: 100,
+            y: 200,
+            size: 3,
+            pressure: 4,
+        });
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(
+            last.take(),
+            Some(TouchEvent {
+                id: 0,
+                status: TouchStatus::Pressed,
+                x: 100,
+                y: 200,
+                size: 3,
+                pressure: 4,
+            })
+        );
+
+        assert_eq!(Touch::disable_single_touch(), Ok(()));
+        driver.inject_touch(fake::TouchEvent::new(fake::TouchStatus::Released, 1, 2));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+    });
+}
+
+#[test]
+fn gesture_listener() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Touch::new(1);
+    kernel.add_driver(&driver);
+
+    let last = Cell::new(None);
+    let listener = GestureListener(|gesture| last.set(Some(gesture)));
+    share::scope(|subscribe| {
+        assert_eq!(
+            Touch::register_gesture_listener(&listener, subscribe),
+            Ok(())
+        );
+        driver.inject_gesture(fake::Gesture::ZoomOut);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(last.get(), Some(Gesture::ZoomOut));
+
+        Touch::unregister_gesture_listener();
+        driver.inject_gesture(fake::Gesture::SwipeUp);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+    });
+}
+
+#[test]
+fn event_queue() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Touch::new(1);
+    kernel.add_driver(&driver);
+
+    let queue = EventQueue::<4>::new();
+    share::scope::<
+        (
+            Subscribe<_, DRIVER_NUM, { subscribe::TOUCH }>,
+            Subscribe<_, DRIVER_NUM, { subscribe::GESTURE }>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (touch, gesture) = handle.split();
+        assert_eq!(Touch::register_touch_listener(&queue, touch), Ok(()));
+        assert_eq!(Touch::register_gesture_listener(&queue, gesture), Ok(()));
+        assert_eq!(Touch::enable_single_touch(), Ok(()));
+
+        driver.inject_swipe((10, 50), (90, 50), 2);
+        driver.inject_gesture(fake::Gesture::SwipeRight);
+        while fake::Syscalls::yield_no_wait() == YieldNoWaitReturn::Upcall {}
+    });
+
+    let touch = |status, x| {
+        Some(Event::Touch(TouchEvent {
+            id: 0,
+            status,
+            x,
+            y: 50,
+            size: 0,
+            pressure: 0,
+        }))
+    };
+    assert_eq!(queue.len(), 4);
+    assert_eq!(queue.dropped(), 1);
+    assert_eq!(queue.pop(), touch(TouchStatus::Pressed, 10));
+    assert_eq!(queue.pop(), touch(TouchStatus::Moved, 50));
+    assert_eq!(queue.pop(), touch(TouchStatus::Moved, 90));
+    assert_eq!(queue.pop(), touch(TouchStatus::Released, 90));
+    assert_eq!(queue.pop(), None);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn multi_touch() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Touch::new(2);
+    kernel.add_driver(&driver);
+
+    assert_eq!(Touch::enable_multi_touch(), Ok(()));
+    driver.inject_multi_touch(&[
+        fake::TouchEvent::new(fake::TouchStatus::Pressed, 10, 20),
+        fake::TouchEvent {
+            id: 1,
+            status: fake::TouchStatus::Moved,
+            x: 300,
+            y: 400,
+            size: 5,
+            pressure: 6,
+        },
+    ]);
+    driver.inject_multi_touch(&[fake::TouchEvent::new(fake::TouchStatus::Released, 1, 2)]);
+
+    let mut buffer = [0; 2 * TOUCH_RECORD_SIZE];
+    let touches = Touch::wait_multi_touch(&mut buffer).unwrap();
+    assert_eq!(touches.count(), 2);
+    assert_eq!(touches.dropped(), 0);
+    let mut touches = touches.iter();
+    assert_eq!(
+        touches.next(),
+        Some(TouchEvent {
+            id: 0,
+            status: TouchStatus::Pressed,
+            x: 10,
+            y: 20,
+            size: 0,
+            pressure: 0,
+        })
+    );
+    assert_eq!(
+        touches.next(),
+        Some(TouchEvent {
+            id: 1,
+            status: TouchStatus::Moved,
+            x: 300,
+            y: 400,
+            size: 5,
+            pressure: 6,
+        })
+    );
+    assert_eq!(touches.next(), None);
+
+    // Touches that do not fit in the buffer are left out.
+    let mut buffer = [0; TOUCH_RECORD_SIZE + 1];
+    let touches = Touch::wait_multi_touch(&mut buffer).unwrap();
+    assert_eq!(touches.count(), 1);
+    assert_eq!(
+        touches.iter().next().map(|touch| touch.status),
+        Some(TouchStatus::Released)
+    );
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Touch::new(2);
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRw {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_rw::MULTI_TOUCH,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::MULTI_TOUCH,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::MULTI_TOUCH_NEXT,
+        argument0: 0,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::Off)),
+    });
+
+    let mut buffer = [0; TOUCH_RECORD_SIZE];
+    assert_eq!(
+        Touch::wait_multi_touch(&mut buffer).map(|touches| touches.count()),
+        Err(ErrorCode::Off)
+    );
+}
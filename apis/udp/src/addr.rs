@@ -0,0 +1,62 @@
+//! IPv6 socket addresses, and their layout in the buffers shared with the
+//! kernel.
+
+/// An IPv6 address.
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Ipv6Addr(pub [u8; 16]);
+
+impl Ipv6Addr {
+    /// The unspecified address, `::`.
+    pub const UNSPECIFIED: Ipv6Addr = Ipv6Addr([0; 16]);
+
+    /// Creates an address from its eight 16-bit segments, most significant
+    /// first, as written in `fe80:0:0:0:0:0:0:1`.
+    pub const fn from_segments(segments: [u16; 8]) -> Ipv6Addr {
+        let mut octets = [0; 16];
+        let mut i = 0;
+        while i < 8 {
+            octets[2 * i] = (segments[i] >> 8) as u8;
+            octets[2 * i + 1] = segments[i] as u8;
+            i += 1;
+        }
+        Ipv6Addr(octets)
+    }
+
+    pub const fn octets(&self) -> [u8; 16] {
+        self.0
+    }
+}
+
+/// An IPv6 address and UDP port.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SocketAddr {
+    pub addr: Ipv6Addr,
+    pub port: u16,
+}
+
+/// The length of a socket address in the buffers shared with the kernel: the
+/// address, followed by the port in native (little-endian) byte order.
+pub const SOCKET_ADDR_LEN: usize = 18;
+
+impl SocketAddr {
+    pub const fn new(addr: Ipv6Addr, port: u16) -> SocketAddr {
+        SocketAddr { addr, port }
+    }
+
+    pub(crate) fn to_bytes(self) -> [u8; SOCKET_ADDR_LEN] {
+        let mut bytes = [0; SOCKET_ADDR_LEN];
+        bytes[..16].copy_from_slice(&self.addr.0);
+        bytes[16..].copy_from_slice(&self.port.to_le_bytes());
+        bytes
+    }
+
+    pub(crate) fn from_bytes(bytes: &[u8; SOCKET_ADDR_LEN]) -> SocketAddr {
+        let mut addr = [0; 16];
+        addr.copy_from_slice(&bytes[..16]);
+        SocketAddr {
+            addr: Ipv6Addr(addr),
+            port: u16::from_le_bytes([bytes[16], bytes[17]]),
+        }
+    }
+}
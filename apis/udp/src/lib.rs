@@ -0,0 +1,203 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform as platform;
+use libtock_platform::allow_ro::AllowRo;
+use libtock_platform::allow_rw::AllowRw;
+use libtock_platform::share;
+use libtock_platform::subscribe::Subscribe;
+use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+
+mod addr;
+
+pub use addr::{Ipv6Addr, SocketAddr, SOCKET_ADDR_LEN};
+
+/// The UDP driver.
+///
+/// An app binds one local port, from which it sends datagrams and on which it
+/// receives them. Sending requires a source address, which must be one of the
+/// addresses of the device's interfaces.
+///
+/// # Example
+/// ```ignore
+/// use libtock::udp::{Ipv6Addr, SocketAddr, Udp};
+///
+/// let mut interfaces = [Ipv6Addr::UNSPECIFIED; 2];
+/// let count = Udp::interfaces(&mut interfaces)?;
+/// let local = SocketAddr::new(interfaces[0], 16123);
+/// Udp::bind(local)?;
+///
+/// let destination = SocketAddr::new(Ipv6Addr::from_segments([0xfe80, 0, 0, 0, 0, 0, 0, 1]), 16124);
+/// Udp::send_to(b"hello", local, destination)?;
+///
+/// let mut buffer = [0; 64];
+/// let (len, source) = Udp::receive(&mut buffer)?;
+/// ```
+pub struct Udp<S: Syscalls, C: Config = DefaultConfig>(S, C);
+
+impl<S: Syscalls, C: Config> Udp<S, C> {
+    /// Run a check against the UDP capsule to ensure it is present.
+    ///
+    /// Returns `true` if the driver was present. This does not necessarily mean
+    /// that the driver is working, as it may still fail to allocate grant
+    /// memory.
+    #[inline(always)]
+    pub fn driver_check() -> bool {
+        S::command(DRIVER_NUM, command::DRIVER_CHECK, 0, 0).is_success()
+    }
+
+    /// Writes the addresses of the device's interfaces into `addresses`, and
+    /// returns how many were written.
+    pub fn interfaces(addresses: &mut [Ipv6Addr]) -> Result<usize, ErrorCode> {
+        let len = addresses.len();
+        // Safety: `Ipv6Addr` is a `repr(transparent)` `[u8; 16]`, so the
+        // addresses are a contiguous array of bytes, every pattern of which is
+        // a valid address.
+        let bytes =
+            unsafe { core::slice::from_raw_parts_mut(addresses.as_mut_ptr() as *mut u8, len * 16) };
+        share::scope::<AllowRw<_, DRIVER_NUM, { allow_rw::CONFIG }>, _, _>(|handle| {
+            S::allow_rw::<C, DRIVER_NUM, { allow_rw::CONFIG }>(handle, bytes)?;
+            S::command(DRIVER_NUM, command::GET_INTERFACES, len as u32, 0)
+                .to_result()
+                .map(|count: u32| count as usize)
+        })
+    }
+
+    /// Returns the largest payload `send_to` accepts, in bytes.
+    pub fn max_payload_len() -> Result<usize, ErrorCode> {
+        S::command(DRIVER_NUM, command::GET_MAX_PAYLOAD_LEN, 0, 0)
+            .to_result()
+            .map(|len: u32| len as usize)
+    }
+
+    /// Binds the app to `local`, replacing any previous binding. Use
+    /// `Ipv6Addr::UNSPECIFIED` to receive on every interface.
+    ///
+    /// Returns `ErrorCode::Busy` if the port is bound by another app.
+    pub fn bind(local: SocketAddr) -> Result<(), ErrorCode> {
+        let mut config = [0; 2 * SOCKET_ADDR_LEN];
+        config[SOCKET_ADDR_LEN..].copy_from_slice(&local.to_bytes());
+        share::scope::<AllowRw<_, DRIVER_NUM, { allow_rw::RX_CONFIG }>, _, _>(|handle| {
+            S::allow_rw::<C, DRIVER_NUM, { allow_rw::RX_CONFIG }>(handle, &mut config)?;
+            S::command(DRIVER_NUM, command::BIND, 0, 0).to_result()
+        })
+    }
+
+    /// Sends `payload` from `source`, which must be the bound port on one of
+    /// the interfaces, to `destination`, and waits until it is sent.
+    pub fn send_to(
+        payload: &[u8],
+        source: SocketAddr,
+        destination: SocketAddr,
+    ) -> Result<(), ErrorCode> {
+        let mut config = [0; 2 * SOCKET_ADDR_LEN];
+        config[..SOCKET_ADDR_LEN].copy_from_slice(&source.to_bytes());
+        config[SOCKET_ADDR_LEN..].copy_from_slice(&destination.to_bytes());
+        let called = Cell::new(Option::<(u32,)>::None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, { allow_ro::TX }>,
+                AllowRw<_, DRIVER_NUM, { allow_rw::CONFIG }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::TX }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, allow_rw, subscribe) = handle.split();
+            S::allow_ro::<C, DRIVER_NUM, { allow_ro::TX }>(allow_ro, payload)?;
+            S::allow_rw::<C, DRIVER_NUM, { allow_rw::CONFIG }>(allow_rw, &mut config)?;
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::TX }>(subscribe, &called)?;
+
+            // When this fails, `called` is guaranteed unmodified,
+            // because upcalls are never processed until we call `yield`.
+            S::command(DRIVER_NUM, command::SEND, 0, 0).to_result::<(), ErrorCode>()?;
+
+            loop {
+                S::yield_wait();
+                if let Some((status,)) = called.get() {
+                    return match status {
+                        0 => Ok(()),
+                        e_status => Err(e_status.try_into().unwrap_or(ErrorCode::Fail)),
+                    };
+                }
+            }
+        })
+    }
+
+    /// Waits until a datagram is received on the bound port, and returns its
+    /// length and source. Datagrams longer than `buffer` are truncated.
+    pub fn receive(buffer: &mut [u8]) -> Result<(usize, SocketAddr), ErrorCode> {
+        let mut config = [0; 2 * SOCKET_ADDR_LEN];
+        let called = Cell::new(Option::<(u32,)>::None);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, { allow_rw::RX }>,
+                AllowRw<_, DRIVER_NUM, { allow_rw::RX_CONFIG }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::RX }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rx, allow_config, subscribe) = handle.split();
+            // Subscribe first, so no datagram arrives before we are listening.
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::RX }>(subscribe, &called)?;
+            S::allow_rw::<C, DRIVER_NUM, { allow_rw::RX_CONFIG }>(allow_config, &mut config)?;
+            S::allow_rw::<C, DRIVER_NUM, { allow_rw::RX }>(allow_rx, buffer)?;
+
+            loop {
+                S::yield_wait();
+                if let Some((len,)) = called.get() {
+                    return Ok(len as usize);
+                }
+            }
+        })
+        .map(|len| {
+            let mut source = [0; SOCKET_ADDR_LEN];
+            source.copy_from_slice(&config[..SOCKET_ADDR_LEN]);
+            (len.min(buffer.len()), SocketAddr::from_bytes(&source))
+        })
+    }
+}
+
+/// System call configuration trait for `Udp`.
+pub trait Config:
+    platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config
+{
+}
+impl<T: platform::allow_ro::Config + platform::allow_rw::Config + platform::subscribe::Config>
+    Config for T
+{
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x30002;
+
+// Command IDs
+mod command {
+    pub const DRIVER_CHECK: u32 = 0;
+    pub const GET_INTERFACES: u32 = 1;
+    pub const SEND: u32 = 2;
+    pub const BIND: u32 = 3;
+    pub const GET_MAX_PAYLOAD_LEN: u32 = 4;
+}
+
+mod subscribe {
+    pub const RX: u32 = 0;
+    pub const TX: u32 = 1;
+}
+
+mod allow_ro {
+    pub const TX: u32 = 0;
+}
+
+mod allow_rw {
+    pub const RX: u32 = 0;
+    pub const CONFIG: u32 = 1;
+    pub const RX_CONFIG: u32 = 2;
+}
@@ -0,0 +1,145 @@
+use super::*;
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type Udp = super::Udp<fake::Syscalls>;
+
+const APP_ADDR: Ipv6Addr = Ipv6Addr::from_segments([0xfe80, 0, 0, 0, 0, 0, 0, 1]);
+const PEER_ADDR: Ipv6Addr = Ipv6Addr::from_segments([0xfe80, 0, 0, 0, 0, 0, 0, 2]);
+
+fn endpoint(addr: SocketAddr) -> fake::Endpoint {
+    fake::Endpoint::new(addr.addr.octets(), addr.port)
+}
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(!Udp::driver_check());
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Udp::new(&[APP_ADDR.octets()]);
+    kernel.add_driver(&driver);
+
+    assert!(Udp::driver_check());
+}
+
+#[test]
+fn socket_addr_layout() {
+    assert_eq!(
+        APP_ADDR.octets(),
+        [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]
+    );
+    let addr = SocketAddr::new(APP_ADDR, 0x1234);
+    let bytes = addr.to_bytes();
+    assert_eq!(bytes[16..], [0x34, 0x12]);
+    assert_eq!(SocketAddr::from_bytes(&bytes), addr);
+}
+
+#[test]
+fn interfaces() {
+    let kernel = fake::Kernel::new();
+    let second = Ipv6Addr::from_segments([0xfd00, 0, 0, 0, 0, 0, 0, 1]);
+    let driver = fake::Udp::new(&[APP_ADDR.octets(), second.octets()]);
+    kernel.add_driver(&driver);
+
+    let mut addresses = [Ipv6Addr::UNSPECIFIED; 3];
+    assert_eq!(Udp::interfaces(&mut addresses), Ok(2));
+    assert_eq!(addresses, [APP_ADDR, second, Ipv6Addr::UNSPECIFIED]);
+    let mut addresses = [Ipv6Addr::UNSPECIFIED; 1];
+    assert_eq!(Udp::interfaces(&mut addresses), Ok(1));
+    assert_eq!(addresses, [APP_ADDR]);
+    assert_eq!(Udp::max_payload_len(), Ok(1232));
+}
+
+#[test]
+fn send_and_receive() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Udp::new(&[APP_ADDR.octets()]);
+    kernel.add_driver(&driver);
+    let network = fake::UdpNetwork::new();
+    network.connect(&driver);
+    let local = SocketAddr::new(APP_ADDR, 16123);
+    let remote = SocketAddr::new(PEER_ADDR, 16124);
+    let peer = network.socket(endpoint(remote));
+
+    // Sending requires binding the source port first.
+    assert_eq!(
+        Udp::send_to(b"hello", local, remote),
+        Err(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        Udp::bind(SocketAddr::new(PEER_ADDR, 16123)),
+        Err(ErrorCode::Invalid)
+    );
+    assert_eq!(Udp::bind(local), Ok(()));
+    assert_eq!(driver.bound(), Some(endpoint(local)));
+    assert_eq!(Udp::send_to(b"hello", local, remote), Ok(()));
+    assert_eq!(
+        peer.take_received(),
+        [fake::Datagram {
+            source: endpoint(local),
+            destination: endpoint(remote),
+            payload: b"hello".to_vec(),
+        }]
+    );
+
+    assert!(peer.send_to(endpoint(local), b"hi there"));
+    let mut buffer = [0; 16];
+    assert_eq!(Udp::receive(&mut buffer), Ok((8, remote)));
+    assert_eq!(buffer[..8], *b"hi there");
+
+    // Longer datagrams are truncated.
+    assert!(peer.send_to(endpoint(local), b"hi there"));
+    let mut buffer = [0; 2];
+    assert_eq!(Udp::receive(&mut buffer), Ok((2, remote)));
+    assert_eq!(buffer, *b"hi");
+}
+
+#[test]
+fn bind_busy() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Udp::new(&[APP_ADDR.octets()]);
+    kernel.add_driver(&driver);
+    driver.reserve_port(16123);
+
+    assert_eq!(
+        Udp::bind(SocketAddr::new(Ipv6Addr::UNSPECIFIED, 16123)),
+        Err(ErrorCode::Busy)
+    );
+}
+
+#[test]
+fn failed_command() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Udp::new(&[APP_ADDR.octets()]);
+    kernel.add_driver(&driver);
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_ro::TX,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRw {
+        driver_num: DRIVER_NUM,
+        buffer_num: allow_rw::CONFIG,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: subscribe::TX,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: command::SEND,
+        argument0: 0,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+
+    let local = SocketAddr::new(APP_ADDR, 16123);
+    let remote = SocketAddr::new(PEER_ADDR, 16124);
+    assert_eq!(Udp::send_to(b"hello", local, remote), Err(ErrorCode::Busy));
+}
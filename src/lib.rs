@@ -6,25 +6,177 @@ extern crate libtock_debug_panic;
 pub use libtock_platform as platform;
 pub use libtock_runtime as runtime;
 
+pub mod aes {
+    use libtock_aes as aes;
+    pub type Aes = aes::Aes<super::runtime::TockSyscalls>;
+    pub use aes::{Mode, BLOCK_SIZE, CCM_NONCE_SIZE, KEY_SIZE};
+}
 pub mod alarm {
     use libtock_alarm as alarm;
     pub type Alarm = alarm::Alarm<super::runtime::TockSyscalls>;
     pub use alarm::{Convert, Hz, Milliseconds, Ticks};
 }
+pub mod analog_comparator {
+    use libtock_analog_comparator as analog_comparator;
+    pub type AnalogComparator = analog_comparator::AnalogComparator<super::runtime::TockSyscalls>;
+    pub use analog_comparator::AnalogComparatorListener;
+}
+pub mod app_flash {
+    use libtock_app_flash as app_flash;
+    pub type AppFlash = app_flash::AppFlash<super::runtime::TockSyscalls>;
+    pub use app_flash::{app_flash_region, AppFlashRegion, PAGE_SIZE};
+}
+pub mod ble {
+    use libtock_ble as ble;
+    pub type Ble = ble::Ble<super::runtime::TockSyscalls>;
+    pub use ble::{
+        ad_type, flags, AdStructure, AdStructures, AdvertisingData, AdvertisingDataBuilder,
+        AdvertisingType, ScanReport, MAX_ADVERTISING_DATA_LEN, SCAN_BUFFER_LEN,
+    };
+}
 pub mod buttons {
     use libtock_buttons as buttons;
     pub type Buttons = buttons::Buttons<super::runtime::TockSyscalls>;
 }
+pub mod buzzer {
+    use libtock_buzzer as buzzer;
+    pub type Buzzer = buzzer::Buzzer<super::runtime::TockSyscalls>;
+    pub use buzzer::{pitch, Note};
+}
+pub mod can {
+    use libtock_can as can;
+    pub type Can = can::Can<super::runtime::TockSyscalls>;
+    pub use can::{Frame, Id, Mode, RxRingBuffer, FRAME_SLOT_LEN, MAX_DATA_LEN};
+}
 pub mod console {
     use libtock_console as console;
     pub type Console = console::Console<super::runtime::TockSyscalls>;
 }
+pub mod crc {
+    use libtock_crc as crc;
+    pub type Crc = crc::Crc<super::runtime::TockSyscalls>;
+    pub use crc::Algorithm;
+}
+pub mod dac {
+    use libtock_dac as dac;
+    pub type Dac = dac::Dac<super::runtime::TockSyscalls>;
+}
+pub mod date_time {
+    use libtock_date_time as date_time;
+    pub type Rtc = date_time::Rtc<super::runtime::TockSyscalls>;
+    pub use date_time::{days_in_month, is_leap_year, DateTime, DayOfWeek};
+}
+pub mod hmac {
+    use libtock_hmac as hmac;
+    pub type Hmac = hmac::Hmac<super::runtime::TockSyscalls>;
+    pub use hmac::Algorithm;
+}
+pub mod i2c_master {
+    use libtock_i2c_master as i2c_master;
+    pub type I2cMaster = i2c_master::I2cMaster<super::runtime::TockSyscalls>;
+    pub type I2cBus = i2c_master::I2cBus<super::runtime::TockSyscalls>;
+    pub use i2c_master::Error;
+}
+pub mod ieee802154 {
+    use libtock_ieee802154 as ieee802154;
+    pub type Ieee802154 = ieee802154::Ieee802154<super::runtime::TockSyscalls>;
+    pub use ieee802154::{
+        Address, Frame, RxRingBuffer, FRAME_META_LEN, MAX_FRAME_LEN, RX_FRAME_SLOT_LEN,
+    };
+}
+pub mod ipc {
+    use libtock_ipc as ipc;
+    pub type Ipc = ipc::Ipc<super::runtime::TockSyscalls>;
+    pub use ipc::{IpcListener, SharedBuffer};
+}
+pub mod kv {
+    use libtock_kv as kv;
+    pub type KeyValue = kv::KeyValue<super::runtime::TockSyscalls>;
+    pub use kv::Error;
+}
 pub mod leds {
     use libtock_leds as leds;
     pub type Leds = leds::Leds<super::runtime::TockSyscalls>;
 }
+pub mod lora {
+    use libtock_lora as lora;
+    pub type Lora = lora::Lora<super::runtime::TockSyscalls>;
+    pub use lora::{
+        Bandwidth, Packet, MAX_FREQUENCY_HZ, MAX_PACKET_LEN, MAX_SPREADING_FACTOR,
+        MIN_FREQUENCY_HZ, MIN_SPREADING_FACTOR,
+    };
+}
 pub mod low_level_debug {
     use libtock_low_level_debug as lldb;
     pub type LowLevelDebug = lldb::LowLevelDebug<super::runtime::TockSyscalls>;
     pub use lldb::AlertCode;
 }
+pub mod nonvolatile_storage {
+    use libtock_nonvolatile_storage as nonvolatile_storage;
+    pub type NonvolatileStorage =
+        nonvolatile_storage::NonvolatileStorage<super::runtime::TockSyscalls>;
+    pub use nonvolatile_storage::{Error, Record, RECORD_HEADER_LEN};
+}
+pub mod proximity {
+    use libtock_proximity as proximity;
+    pub type Proximity = proximity::Proximity<super::runtime::TockSyscalls>;
+    pub use proximity::ProximityListener;
+}
+pub mod pwm {
+    use libtock_pwm as pwm;
+    pub type Pwm = pwm::Pwm<super::runtime::TockSyscalls>;
+}
+pub mod screen {
+    use libtock_screen as screen;
+    pub type Screen = screen::Screen<super::runtime::TockSyscalls>;
+    pub type Display = screen::Display<super::runtime::TockSyscalls>;
+    pub use screen::{embedded_graphics_core, Frame, PixelFormat, Rotation};
+}
+pub mod serial {
+    use libtock_serial as serial;
+    pub type Serial<const DRIVER_NUM: u32> =
+        serial::Serial<super::runtime::TockSyscalls, DRIVER_NUM>;
+    pub type SerialWriter<const DRIVER_NUM: u32> =
+        serial::SerialWriter<super::runtime::TockSyscalls, DRIVER_NUM>;
+}
+pub mod servo {
+    use libtock_servo as servo;
+    pub type Servo = servo::Servo<super::runtime::TockSyscalls>;
+    pub use servo::MAX_ANGLE;
+}
+pub mod sha {
+    use libtock_sha as sha;
+    pub type Sha = sha::Sha<super::runtime::TockSyscalls>;
+    pub type Sha256 = sha::Sha256<super::runtime::TockSyscalls>;
+    pub type Sha384 = sha::Sha384<super::runtime::TockSyscalls>;
+    pub type Sha512 = sha::Sha512<super::runtime::TockSyscalls>;
+    pub use sha::{digest, Algorithm};
+}
+pub mod sound_pressure {
+    use libtock_sound_pressure as sound_pressure;
+    pub type SoundPressure = sound_pressure::SoundPressure<super::runtime::TockSyscalls>;
+    pub use sound_pressure::SoundPressureListener;
+}
+pub mod spi_controller {
+    use libtock_spi_controller as spi_controller;
+    pub type SpiController = spi_controller::SpiController<super::runtime::TockSyscalls>;
+    pub use spi_controller::{ClockPhase, ClockPolarity};
+}
+pub mod text_screen {
+    use libtock_text_screen as text_screen;
+    pub type TextScreen = text_screen::TextScreen<super::runtime::TockSyscalls>;
+    pub type TextScreenWriter = text_screen::TextScreenWriter<super::runtime::TockSyscalls>;
+}
+pub mod touch {
+    use libtock_touch as touch;
+    pub type Touch = touch::Touch<super::runtime::TockSyscalls>;
+    pub use touch::{
+        Event, EventQueue, Gesture, GestureListener, MultiTouch, TouchEvent, TouchListener,
+        TouchStatus, TOUCH_RECORD_SIZE,
+    };
+}
+pub mod udp {
+    use libtock_udp as udp;
+    pub type Udp = udp::Udp<super::runtime::TockSyscalls>;
+    pub use udp::{Ipv6Addr, SocketAddr, SOCKET_ADDR_LEN};
+}
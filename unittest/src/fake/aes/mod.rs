@@ -0,0 +1,296 @@
+//! Fake implementation of the AES API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/40006_aes.md
+//!
+//! `Aes` implements AES-128 in software, so tests can check ciphertexts
+//! against known values. If no source buffer is shared, the destination buffer
+//! is processed in place.
+
+use aes::cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+use aes::Aes128;
+use ccm::aead::AeadInPlace;
+use ccm::consts::{U13, U16, U4, U8};
+use ccm::Ccm;
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+
+use crate::upcall;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+
+pub struct Aes {
+    mode: Cell<Option<u32>>,
+    setup: RefCell<Option<Setup>>,
+    aad_len: Cell<usize>,
+    mic_len: Cell<usize>,
+
+    key: RefCell<RoAllowBuffer>,
+    iv: RefCell<RoAllowBuffer>,
+    source: RefCell<RoAllowBuffer>,
+    dest: RefCell<RwAllowBuffer>,
+}
+
+// The key and IV copied when an operation is set up, and whether it encrypts.
+struct Setup {
+    key: Vec<u8>,
+    iv: Vec<u8>,
+    encrypting: bool,
+}
+
+impl Aes {
+    pub fn new() -> std::rc::Rc<Aes> {
+        std::rc::Rc::new(Aes {
+            mode: Cell::new(None),
+            setup: Default::default(),
+            aad_len: Cell::new(0),
+            mic_len: Cell::new(0),
+            key: Default::default(),
+            iv: Default::default(),
+            source: Default::default(),
+            dest: Default::default(),
+        })
+    }
+
+    // Copies the key and IV, as the kernel does when an operation is set up.
+    fn setup(&self, encrypting: bool) -> Result<(), ErrorCode> {
+        let iv_len = match self.mode.get() {
+            None => return Err(ErrorCode::Reserve),
+            Some(MODE_CCM) => CCM_NONCE_SIZE,
+            Some(_) => BLOCK_SIZE,
+        };
+        let (key, iv) = (self.key.borrow(), self.iv.borrow());
+        if key.len() != BLOCK_SIZE || iv.len() != iv_len {
+            return Err(ErrorCode::Invalid);
+        }
+        self.setup.replace(Some(Setup {
+            key: Vec::from(&**key),
+            iv: Vec::from(&**iv),
+            encrypting,
+        }));
+        Ok(())
+    }
+
+    fn crypt(&self) -> Result<(), ErrorCode> {
+        let setup = self.setup.borrow();
+        let Setup {
+            key,
+            iv,
+            encrypting,
+        } = setup.as_ref().ok_or(ErrorCode::Reserve)?;
+        let mut dest = self.dest.borrow_mut();
+        let source = self.source.borrow();
+        if !source.is_empty() {
+            if source.len() != dest.len() {
+                return Err(ErrorCode::Invalid);
+            }
+            dest.copy_from_slice(&source);
+        }
+        let cipher = Aes128::new(key[..].into());
+        match self.mode.get() {
+            Some(MODE_CTR) => ctr(&cipher, iv, &mut dest),
+            Some(MODE_CBC) if *encrypting => cbc_encrypt(&cipher, iv, &mut dest),
+            Some(MODE_CBC) => cbc_decrypt(&cipher, iv, &mut dest),
+            Some(MODE_ECB) => ecb(&cipher, *encrypting, &mut dest),
+            _ => self.ccm(key, iv, *encrypting, &mut dest),
+        }
+    }
+
+    fn ccm(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        encrypting: bool,
+        buffer: &mut [u8],
+    ) -> Result<(), ErrorCode> {
+        let (aad_len, mic_len) = (self.aad_len.get(), self.mic_len.get());
+        if aad_len + mic_len > buffer.len() {
+            return Err(ErrorCode::Size);
+        }
+        let (aad, rest) = buffer.split_at_mut(aad_len);
+        let (message, mic) = rest.split_at_mut(rest.len() - mic_len);
+        match mic_len {
+            4 => ccm::<U4>(key, nonce, encrypting, aad, message, mic),
+            8 => ccm::<U8>(key, nonce, encrypting, aad, message, mic),
+            16 => ccm::<U16>(key, nonce, encrypting, aad, message, mic),
+            _ => Err(ErrorCode::Invalid),
+        }
+    }
+}
+
+impl crate::fake::SyscallDriver for Aes {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        match buffer_num {
+            ALLOW_KEY => Ok(self.key.replace(buffer)),
+            ALLOW_IV => Ok(self.iv.replace(buffer)),
+            ALLOW_SOURCE => Ok(self.source.replace(buffer)),
+            _ => Err((buffer, ErrorCode::Invalid)),
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_DEST {
+            Ok(self.dest.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => crate::command_return::success(),
+            SET_ALGORITHM => {
+                if argument0 > MODE_CCM {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.mode.set(Some(argument0));
+                self.setup.replace(None);
+                crate::command_return::success()
+            }
+            SETUP => match self.setup(argument0 != 0) {
+                Ok(()) => crate::command_return::success(),
+                Err(error) => crate::command_return::failure(error),
+            },
+            CRYPT => {
+                let status = match self.crypt() {
+                    Ok(()) => 0,
+                    Err(error) => error as u32,
+                };
+                upcall::schedule(DRIVER_NUM, SUBSCRIBE_DONE, (status, 0, 0))
+                    .expect("Unable to schedule upcall {}");
+                crate::command_return::success()
+            }
+            CCM_SET_AAD_LEN => {
+                self.aad_len.set(argument0 as usize);
+                crate::command_return::success()
+            }
+            CCM_SET_MIC_LEN => {
+                self.mic_len.set(argument0 as usize);
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+fn ecb(cipher: &Aes128, encrypting: bool, data: &mut [u8]) -> Result<(), ErrorCode> {
+    if data.len() % BLOCK_SIZE != 0 {
+        return Err(ErrorCode::Invalid);
+    }
+    for block in data.chunks_exact_mut(BLOCK_SIZE) {
+        if encrypting {
+            cipher.encrypt_block(block.into());
+        } else {
+            cipher.decrypt_block(block.into());
+        }
+    }
+    Ok(())
+}
+
+fn cbc_encrypt(cipher: &Aes128, iv: &[u8], data: &mut [u8]) -> Result<(), ErrorCode> {
+    if data.len() % BLOCK_SIZE != 0 {
+        return Err(ErrorCode::Invalid);
+    }
+    let mut previous = Vec::from(iv);
+    for block in data.chunks_exact_mut(BLOCK_SIZE) {
+        block.iter_mut().zip(&previous).for_each(|(b, p)| *b ^= p);
+        cipher.encrypt_block(block.into());
+        previous.copy_from_slice(block);
+    }
+    Ok(())
+}
+
+fn cbc_decrypt(cipher: &Aes128, iv: &[u8], data: &mut [u8]) -> Result<(), ErrorCode> {
+    if data.len() % BLOCK_SIZE != 0 {
+        return Err(ErrorCode::Invalid);
+    }
+    let mut previous = Vec::from(iv);
+    for block in data.chunks_exact_mut(BLOCK_SIZE) {
+        let ciphertext = Vec::from(&*block);
+        cipher.decrypt_block(block.into());
+        block.iter_mut().zip(&previous).for_each(|(b, p)| *b ^= p);
+        previous = ciphertext;
+    }
+    Ok(())
+}
+
+// CTR mode with a 128-bit big-endian counter starting at `iv`.
+fn ctr(cipher: &Aes128, iv: &[u8], data: &mut [u8]) -> Result<(), ErrorCode> {
+    let mut counter = u128::from_be_bytes(iv.try_into().unwrap());
+    for chunk in data.chunks_mut(BLOCK_SIZE) {
+        let mut keystream = counter.to_be_bytes();
+        cipher.encrypt_block((&mut keystream).into());
+        chunk.iter_mut().zip(keystream).for_each(|(b, k)| *b ^= k);
+        counter = counter.wrapping_add(1);
+    }
+    Ok(())
+}
+
+fn ccm<M>(
+    key: &[u8],
+    nonce: &[u8],
+    encrypting: bool,
+    aad: &[u8],
+    message: &mut [u8],
+    mic: &mut [u8],
+) -> Result<(), ErrorCode>
+where
+    M: ccm::aead::generic_array::ArrayLength<u8> + ccm::TagSize,
+{
+    let ccm = Ccm::<Aes128, M, U13>::new(key.into());
+    if encrypting {
+        let tag = ccm
+            .encrypt_in_place_detached(nonce.into(), aad, message)
+            .map_err(|_| ErrorCode::Size)?;
+        mic.copy_from_slice(&tag);
+        Ok(())
+    } else {
+        ccm.decrypt_in_place_detached(nonce.into(), aad, message, (&*mic).into())
+            .map_err(|_| ErrorCode::Fail)
+    }
+}
+
+const DRIVER_NUM: u32 = 0x40006;
+
+const BLOCK_SIZE: usize = 16;
+const CCM_NONCE_SIZE: usize = 13;
+
+// Algorithm numbers
+const MODE_CTR: u32 = 0;
+const MODE_CBC: u32 = 1;
+const MODE_ECB: u32 = 2;
+const MODE_CCM: u32 = 3;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const SET_ALGORITHM: u32 = 1;
+const SETUP: u32 = 2;
+const CRYPT: u32 = 3;
+const CCM_SET_AAD_LEN: u32 = 5;
+const CCM_SET_MIC_LEN: u32 = 6;
+
+const SUBSCRIBE_DONE: u32 = 0;
+const ALLOW_KEY: u32 = 0;
+const ALLOW_IV: u32 = 1;
+const ALLOW_SOURCE: u32 = 2;
+const ALLOW_DEST: u32 = 0;
@@ -0,0 +1,111 @@
+use crate::fake;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+use fake::aes::*;
+use libtock_platform::YieldNoWaitReturn;
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let aes = Aes::new();
+    assert!(aes.command(DRIVER_CHECK, 1, 2).is_success());
+    for buffer_num in [ALLOW_KEY, ALLOW_IV, ALLOW_SOURCE] {
+        assert!(aes
+            .allow_readonly(buffer_num, RoAllowBuffer::default())
+            .is_ok());
+    }
+    assert!(aes.allow_readonly(3, RoAllowBuffer::default()).is_err());
+    assert!(aes
+        .allow_readwrite(ALLOW_DEST, RwAllowBuffer::default())
+        .is_ok());
+    assert!(aes.allow_readwrite(1, RwAllowBuffer::default()).is_err());
+
+    // No algorithm has been selected.
+    assert_eq!(
+        aes.command(SETUP, 1, 0).get_failure(),
+        Some(ErrorCode::Reserve)
+    );
+    assert_eq!(
+        aes.command(SET_ALGORITHM, 4, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert!(aes.command(SET_ALGORITHM, MODE_ECB, 0).is_success());
+    // No key has been shared.
+    assert_eq!(
+        aes.command(SETUP, 1, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        aes.command(7, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies Aes works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let aes = Aes::new();
+    kernel.add_driver(&aes);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+
+    // The first block of the ECB-AES128 example from NIST SP 800-38A.
+    let key = [
+        0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f,
+        0x3c,
+    ];
+    let plaintext = [
+        0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17,
+        0x2a,
+    ];
+    let mut dest = [0; 16];
+    let called = core::cell::Cell::new(Option::<(u32,)>::None);
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_KEY>,
+            AllowRo<_, DRIVER_NUM, ALLOW_IV>,
+            AllowRo<_, DRIVER_NUM, ALLOW_SOURCE>,
+            AllowRw<_, DRIVER_NUM, ALLOW_DEST>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_DONE>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_key, allow_iv, allow_source, allow_dest, subscribe) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_KEY>(allow_key, &key).unwrap();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_IV>(allow_iv, &[0; 16])
+            .unwrap();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_SOURCE>(
+            allow_source,
+            &plaintext,
+        )
+        .unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_DEST>(allow_dest, &mut dest)
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_DONE>(
+            subscribe, &called,
+        )
+        .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, SET_ALGORITHM, MODE_ECB, 0).is_success());
+        // An operation must be set up before it is run.
+        assert!(fake::Syscalls::command(DRIVER_NUM, CRYPT, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((ErrorCode::Reserve as u32,)));
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, SETUP, 1, 0).is_success());
+        assert!(fake::Syscalls::command(DRIVER_NUM, CRYPT, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((0,)));
+    });
+    assert_eq!(
+        dest,
+        [
+            0x3a, 0xd7, 0x7b, 0xb4, 0x0d, 0x7a, 0x36, 0x60, 0xa8, 0x9e, 0xca, 0xf3, 0x24, 0x66,
+            0xef, 0x97
+        ]
+    );
+}
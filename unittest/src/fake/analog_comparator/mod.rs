@@ -0,0 +1,124 @@
+//! Fake implementation of the analog comparator API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/00007_analog_comparator.md
+//!
+//! Tests set the result of each channel's comparison with `set_output`, and
+//! make a channel's positive input rise above the negative one with `cross`.
+//! Crossings can also be scripted ahead of time with `queue_crossings`; they
+//! are delivered as soon as the app starts comparing on their channel.
+
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+
+use crate::upcall;
+
+pub struct AnalogComparator<const CHANNELS: usize> {
+    outputs: [Cell<bool>; CHANNELS],
+    comparing: [Cell<bool>; CHANNELS],
+    queued_crossings: RefCell<Vec<u32>>,
+}
+
+impl<const CHANNELS: usize> AnalogComparator<CHANNELS> {
+    pub fn new() -> std::rc::Rc<AnalogComparator<CHANNELS>> {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const LOW: Cell<bool> = Cell::new(false);
+        std::rc::Rc::new(AnalogComparator {
+            outputs: [LOW; CHANNELS],
+            comparing: [LOW; CHANNELS],
+            queued_crossings: Default::default(),
+        })
+    }
+
+    /// Sets whether the positive input of `channel` is higher than the
+    /// negative one, without raising an interrupt.
+    pub fn set_output(&self, channel: u32, positive_higher: bool) {
+        self.outputs[channel as usize].set(positive_higher);
+    }
+
+    pub fn is_comparing(&self, channel: u32) -> bool {
+        self.comparing[channel as usize].get()
+    }
+
+    /// Makes the positive input of `channel` rise above the negative one.
+    /// Returns `true` if the app was comparing on `channel`, and so got an
+    /// interrupt.
+    pub fn cross(&self, channel: u32) -> bool {
+        self.set_output(channel, true);
+        if !self.is_comparing(channel) {
+            return false;
+        }
+        upcall::schedule(DRIVER_NUM, SUBSCRIBE_INTERRUPT, (channel, 0, 0))
+            .expect("Unable to schedule upcall {}");
+        true
+    }
+
+    /// Scripts a crossing on each of `channels`, in order. Each crossing is
+    /// delivered once the app starts comparing on its channel.
+    pub fn queue_crossings(&self, channels: &[u32]) {
+        self.queued_crossings
+            .borrow_mut()
+            .extend_from_slice(channels);
+        self.deliver_queued();
+    }
+
+    fn deliver_queued(&self) {
+        let crossings = self.queued_crossings.take();
+        let (ready, waiting): (Vec<u32>, Vec<u32>) = crossings
+            .into_iter()
+            .partition(|&channel| self.is_comparing(channel));
+        self.queued_crossings.replace(waiting);
+        for channel in ready {
+            self.cross(channel);
+        }
+    }
+}
+
+impl<const CHANNELS: usize> crate::fake::SyscallDriver for AnalogComparator<CHANNELS> {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        if command_num == COUNT {
+            return crate::command_return::success_u32(CHANNELS as u32);
+        }
+        if argument0 as usize >= CHANNELS {
+            return crate::command_return::failure(ErrorCode::Invalid);
+        }
+        match command_num {
+            COMPARE => {
+                crate::command_return::success_u32(self.outputs[argument0 as usize].get() as u32)
+            }
+            START_COMPARING => {
+                self.comparing[argument0 as usize].set(true);
+                self.deliver_queued();
+                crate::command_return::success()
+            }
+            STOP_COMPARING => {
+                self.comparing[argument0 as usize].set(false);
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const DRIVER_NUM: u32 = 0x7;
+
+// Command numbers
+const COUNT: u32 = 0;
+const COMPARE: u32 = 1;
+const START_COMPARING: u32 = 2;
+const STOP_COMPARING: u32 = 3;
+
+const SUBSCRIBE_INTERRUPT: u32 = 0;
@@ -0,0 +1,67 @@
+use crate::fake;
+use fake::analog_comparator::*;
+use libtock_platform::{share, DefaultConfig, ErrorCode, YieldNoWaitReturn};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let kernel = fake::Kernel::new();
+    let comparator = AnalogComparator::<2>::new();
+    kernel.add_driver(&comparator);
+    assert_eq!(comparator.command(COUNT, 0, 0).get_success_u32(), Some(2));
+
+    assert_eq!(comparator.command(COMPARE, 1, 0).get_success_u32(), Some(0));
+    comparator.set_output(1, true);
+    assert_eq!(comparator.command(COMPARE, 1, 0).get_success_u32(), Some(1));
+    assert_eq!(
+        comparator.command(COMPARE, 2, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+
+    assert!(!comparator.cross(0));
+    assert!(comparator.command(START_COMPARING, 0, 0).is_success());
+    assert!(comparator.is_comparing(0));
+    assert!(comparator.cross(0));
+    assert!(comparator.command(STOP_COMPARING, 0, 0).is_success());
+    assert!(!comparator.is_comparing(0));
+    assert_eq!(
+        comparator.command(START_COMPARING, 2, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        comparator.command(4, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies AnalogComparator works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let comparator = AnalogComparator::<2>::new();
+    kernel.add_driver(&comparator);
+    assert_eq!(
+        fake::Syscalls::command(DRIVER_NUM, COUNT, 0, 0).get_success_u32(),
+        Some(2)
+    );
+
+    comparator.queue_crossings(&[1, 0]);
+    let interrupt = core::cell::Cell::new(None);
+    share::scope(|subscribe| {
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_INTERRUPT>(
+            subscribe, &interrupt,
+        )
+        .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, START_COMPARING, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(interrupt.get(), Some((0,)));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        assert!(fake::Syscalls::command(DRIVER_NUM, START_COMPARING, 1, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(interrupt.get(), Some((1,)));
+    });
+}
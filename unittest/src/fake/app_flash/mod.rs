@@ -0,0 +1,119 @@
+//! Fake implementation of the app flash API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/50000_app_flash.md
+//!
+//! `AppFlash` models a writeable flash region of `pages` pages starting at
+//! `base`. Like real flash, writing a page takes an erase, which sets every
+//! bit to 1, followed by programming, which can only clear bits. The number of
+//! times each page has been erased can be retrieved with `erase_count`.
+
+use core::cell::RefCell;
+use libtock_platform::{CommandReturn, ErrorCode};
+
+use crate::upcall;
+use crate::RoAllowBuffer;
+
+pub struct AppFlash {
+    base: u32,
+    flash: RefCell<Vec<u8>>,
+    erase_counts: RefCell<Vec<u32>>,
+
+    buffer: RefCell<RoAllowBuffer>,
+}
+
+impl AppFlash {
+    pub fn new(base: u32, pages: usize) -> std::rc::Rc<AppFlash> {
+        std::rc::Rc::new(AppFlash {
+            base,
+            flash: RefCell::new(vec![0xff; pages * PAGE_SIZE]),
+            erase_counts: RefCell::new(vec![0; pages]),
+            buffer: Default::default(),
+        })
+    }
+
+    /// Returns the contents of the region.
+    pub fn contents(&self) -> Vec<u8> {
+        self.flash.borrow().clone()
+    }
+
+    /// Returns the number of times page `index` of the region has been erased.
+    pub fn erase_count(&self, index: usize) -> u32 {
+        self.erase_counts.borrow()[index]
+    }
+
+    fn erase(&self, index: usize) {
+        self.flash.borrow_mut()[index * PAGE_SIZE..(index + 1) * PAGE_SIZE].fill(0xff);
+        self.erase_counts.borrow_mut()[index] += 1;
+    }
+
+    fn program(&self, index: usize, data: &[u8]) {
+        let mut flash = self.flash.borrow_mut();
+        let page = &mut flash[index * PAGE_SIZE..(index + 1) * PAGE_SIZE];
+        for (bits, &byte) in page.iter_mut().zip(data) {
+            *bits &= byte;
+        }
+    }
+}
+
+impl crate::fake::SyscallDriver for AppFlash {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_BUFFER {
+            Ok(self.buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => crate::command_return::success(),
+            WRITE => {
+                let buffer = self.buffer.borrow();
+                let offset = argument0.wrapping_sub(self.base) as usize;
+                if buffer.len() != PAGE_SIZE
+                    || argument0 < self.base
+                    || offset % PAGE_SIZE != 0
+                    || offset >= self.flash.borrow().len()
+                {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                let index = offset / PAGE_SIZE;
+                self.erase(index);
+                self.program(index, &buffer);
+                upcall::schedule(DRIVER_NUM, SUBSCRIBE_WRITE_DONE, (0, 0, 0))
+                    .expect("Unable to schedule upcall {}");
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const DRIVER_NUM: u32 = 0x50000;
+
+const PAGE_SIZE: usize = 512;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const WRITE: u32 = 1;
+
+const SUBSCRIBE_WRITE_DONE: u32 = 0;
+const ALLOW_BUFFER: u32 = 0;
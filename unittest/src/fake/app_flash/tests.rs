@@ -0,0 +1,86 @@
+use crate::fake;
+use crate::RoAllowBuffer;
+use fake::app_flash::*;
+use libtock_platform::{share, AllowRo, DefaultConfig, ErrorCode, Subscribe, YieldNoWaitReturn};
+
+const BASE: u32 = 0x40000;
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let app_flash = AppFlash::new(BASE, 2);
+    assert!(app_flash.command(DRIVER_CHECK, 1, 2).is_success());
+    assert!(app_flash
+        .allow_readonly(ALLOW_BUFFER, RoAllowBuffer::default())
+        .is_ok());
+    assert!(app_flash
+        .allow_readonly(1, RoAllowBuffer::default())
+        .is_err());
+
+    // The shared buffer is not a whole page.
+    assert_eq!(
+        app_flash.command(WRITE, BASE, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        app_flash.command(2, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+    assert_eq!(app_flash.erase_count(0), 0);
+}
+
+#[test]
+fn program() {
+    let app_flash = AppFlash::new(BASE, 1);
+    app_flash.program(0, &[0x0f, 0xf0]);
+    app_flash.program(0, &[0x3c, 0x3c]);
+    // Programming only clears bits.
+    assert_eq!(app_flash.contents()[..3], [0x0c, 0x30, 0xff]);
+    app_flash.erase(0);
+    assert_eq!(app_flash.contents(), [0xff; PAGE_SIZE]);
+    assert_eq!(app_flash.erase_count(0), 1);
+}
+
+// Integration test that verifies AppFlash works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let app_flash = AppFlash::new(BASE, 2);
+    kernel.add_driver(&app_flash);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+
+    let page = [0x5a; PAGE_SIZE];
+    let called = core::cell::Cell::new(Option::<(u32,)>::None);
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_BUFFER>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_WRITE_DONE>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_ro, subscribe) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_BUFFER>(allow_ro, &page)
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_WRITE_DONE>(
+            subscribe, &called,
+        )
+        .unwrap();
+
+        let second_page = BASE + PAGE_SIZE as u32;
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE, second_page, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((0,)));
+
+        // Not page-aligned, and outside the region.
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE, BASE + 4, 0).is_failure());
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE, BASE - 512, 0).is_failure());
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE, BASE + 1024, 0).is_failure());
+    });
+    assert_eq!(app_flash.contents()[..PAGE_SIZE], [0xff; PAGE_SIZE]);
+    assert_eq!(app_flash.contents()[PAGE_SIZE..], [0x5a; PAGE_SIZE]);
+    assert_eq!((app_flash.erase_count(0), app_flash.erase_count(1)), (0, 1));
+}
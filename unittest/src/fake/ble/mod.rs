@@ -0,0 +1,195 @@
+//! Fake implementation of the BLE advertising API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/30000_ble_advertising.md
+//!
+//! `Ble` records the advertising state for tests to inspect, and reports the
+//! advertisements tests inject with `inject_advertisement` while scanning.
+//! Unlike the kernel, which drops advertisements when the app has no buffer
+//! shared, the fake queues them until the app shares one.
+
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::collections::VecDeque;
+
+use crate::upcall;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+
+pub struct Ble {
+    advertising: Cell<Option<Advertising>>,
+    scanning: Cell<bool>,
+    tx_power: Cell<i8>,
+    pending: RefCell<VecDeque<Vec<u8>>>,
+    // Whether an advertisement was written into the shared scan buffer.
+    scan_buffer_full: Cell<bool>,
+
+    advertisement: RefCell<RoAllowBuffer>,
+    scan_buffer: RefCell<RwAllowBuffer>,
+}
+
+/// The advertising parameters set by the app.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Advertising {
+    pub pdu_type: u8,
+    pub interval_ms: u32,
+}
+
+impl Ble {
+    pub fn new() -> std::rc::Rc<Ble> {
+        std::rc::Rc::new(Ble {
+            advertising: Cell::new(None),
+            scanning: Cell::new(false),
+            tx_power: Cell::new(0),
+            pending: Default::default(),
+            scan_buffer_full: Cell::new(false),
+            advertisement: Default::default(),
+            scan_buffer: Default::default(),
+        })
+    }
+
+    /// Returns the advertising parameters, if advertising.
+    pub fn advertising(&self) -> Option<Advertising> {
+        self.advertising.get()
+    }
+
+    /// Returns the advertising data currently shared by the app.
+    pub fn advertising_data(&self) -> Vec<u8> {
+        self.advertisement.borrow().to_vec()
+    }
+
+    pub fn is_scanning(&self) -> bool {
+        self.scanning.get()
+    }
+
+    pub fn tx_power(&self) -> i8 {
+        self.tx_power.get()
+    }
+
+    /// Reports an advertisement of type `pdu_type` from `address` (least
+    /// significant byte first) to the app, once it is scanning.
+    pub fn inject_advertisement(&self, pdu_type: u8, address: [u8; 6], data: &[u8]) {
+        assert!(
+            data.len() <= MAX_ADVERTISING_DATA_LEN,
+            "Advertising data too long"
+        );
+        let mut pdu = vec![pdu_type, (address.len() + data.len()) as u8];
+        pdu.extend_from_slice(&address);
+        pdu.extend_from_slice(data);
+        self.pending.borrow_mut().push_back(pdu);
+        self.deliver();
+    }
+
+    // Writes the next pending advertisement into the scan buffer, if the app
+    // is scanning and has shared an empty buffer.
+    fn deliver(&self) {
+        if !self.scanning.get() || self.scan_buffer_full.get() {
+            return;
+        }
+        let mut buffer = self.scan_buffer.borrow_mut();
+        let len = match self.pending.borrow().front() {
+            Some(pdu) if pdu.len() <= buffer.len() => pdu.len(),
+            _ => return,
+        };
+        let pdu = self.pending.borrow_mut().pop_front().unwrap();
+        buffer[..len].copy_from_slice(&pdu);
+        self.scan_buffer_full.set(true);
+        upcall::schedule(DRIVER_NUM, SUBSCRIBE_SCAN, (0, len as u32, 0))
+            .expect("Unable to schedule upcall {}");
+    }
+}
+
+impl crate::fake::SyscallDriver for Ble {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_ADVERTISEMENT {
+            Ok(self.advertisement.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_SCAN {
+            let previous = self.scan_buffer.replace(buffer);
+            self.scan_buffer_full.set(false);
+            self.deliver();
+            Ok(previous)
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => {}
+            START_ADVERTISING => {
+                if self.scanning.get() || self.advertising.get().is_some() {
+                    return crate::command_return::failure(ErrorCode::Busy);
+                }
+                if !(MIN_INTERVAL_MS..=MAX_INTERVAL_MS).contains(&argument1)
+                    || !matches!(argument0, 0x00 | 0x01 | 0x02 | 0x06)
+                {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                if self.advertisement.borrow().len() > MAX_ADVERTISING_DATA_LEN {
+                    return crate::command_return::failure(ErrorCode::Size);
+                }
+                self.advertising.set(Some(Advertising {
+                    pdu_type: argument0 as u8,
+                    interval_ms: argument1,
+                }));
+            }
+            STOP => {
+                self.advertising.set(None);
+                self.scanning.set(false);
+            }
+            SET_TX_POWER => self.tx_power.set(argument0 as i8),
+            PASSIVE_SCAN => {
+                if self.scanning.get() || self.advertising.get().is_some() {
+                    return crate::command_return::failure(ErrorCode::Busy);
+                }
+                self.scanning.set(true);
+                self.deliver();
+            }
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        }
+        crate::command_return::success()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const MAX_ADVERTISING_DATA_LEN: usize = 31;
+const MIN_INTERVAL_MS: u32 = 20;
+const MAX_INTERVAL_MS: u32 = 10240;
+
+const DRIVER_NUM: u32 = 0x30000;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const START_ADVERTISING: u32 = 1;
+const STOP: u32 = 2;
+const SET_TX_POWER: u32 = 3;
+const PASSIVE_SCAN: u32 = 5;
+
+const SUBSCRIBE_SCAN: u32 = 0;
+const ALLOW_ADVERTISEMENT: u32 = 0;
+const ALLOW_SCAN: u32 = 0;
@@ -0,0 +1,107 @@
+use crate::fake;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+use fake::ble::*;
+use libtock_platform::{
+    share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe, YieldNoWaitReturn,
+};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let ble = Ble::new();
+    assert!(ble.command(DRIVER_CHECK, 1, 2).is_success());
+    assert!(ble
+        .allow_readonly(ALLOW_ADVERTISEMENT, RoAllowBuffer::default())
+        .is_ok());
+    assert!(ble.allow_readonly(1, RoAllowBuffer::default()).is_err());
+    assert!(ble
+        .allow_readwrite(ALLOW_SCAN, RwAllowBuffer::default())
+        .is_ok());
+    assert!(ble.allow_readwrite(1, RwAllowBuffer::default()).is_err());
+
+    assert_eq!(
+        ble.command(START_ADVERTISING, 0x02, 19).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        ble.command(START_ADVERTISING, 0x03, 100).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert!(ble.command(START_ADVERTISING, 0x02, 100).is_success());
+    assert_eq!(
+        ble.advertising(),
+        Some(Advertising {
+            pdu_type: 0x02,
+            interval_ms: 100
+        })
+    );
+    assert_eq!(
+        ble.command(PASSIVE_SCAN, 0, 0).get_failure(),
+        Some(ErrorCode::Busy)
+    );
+    assert!(ble.command(STOP, 0, 0).is_success());
+    assert_eq!(ble.advertising(), None);
+
+    assert!(ble.command(PASSIVE_SCAN, 0, 0).is_success());
+    assert!(ble.is_scanning());
+    assert!(ble.command(STOP, 0, 0).is_success());
+    assert!(!ble.is_scanning());
+
+    assert!(ble.command(SET_TX_POWER, -4i32 as u32, 0).is_success());
+    assert_eq!(ble.tx_power(), -4);
+    assert_eq!(
+        ble.command(4, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies Ble works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let ble = Ble::new();
+    kernel.add_driver(&ble);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+
+    let data = [2, 0x01, 0x06];
+    share::scope::<AllowRo<_, DRIVER_NUM, ALLOW_ADVERTISEMENT>, _, _>(|handle| {
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_ADVERTISEMENT>(handle, &data)
+            .unwrap();
+        assert!(fake::Syscalls::command(DRIVER_NUM, START_ADVERTISING, 0x02, 100).is_success());
+        assert_eq!(ble.advertising_data(), data);
+        assert!(fake::Syscalls::command(DRIVER_NUM, STOP, 0, 0).is_success());
+    });
+
+    // Advertisements injected before the scan starts are queued.
+    ble.inject_advertisement(0x00, [1, 2, 3, 4, 5, 6], &[2, 0x01, 0x06]);
+    ble.inject_advertisement(0x02, [6, 5, 4, 3, 2, 1], &[]);
+    let scanned = core::cell::Cell::new(Option::<(u32, u32)>::None);
+    let mut buffer = [0; 39];
+    share::scope::<
+        (
+            AllowRw<_, DRIVER_NUM, ALLOW_SCAN>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_SCAN>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_rw, subscribe) = handle.split();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_SCAN>(
+            subscribe, &scanned,
+        )
+        .unwrap();
+        assert!(fake::Syscalls::command(DRIVER_NUM, PASSIVE_SCAN, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_SCAN>(allow_rw, &mut buffer)
+            .unwrap();
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(scanned.get(), Some((0, 11)));
+        // The second advertisement waits until the buffer is shared again.
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        assert_eq!(ble.pending.borrow().len(), 1);
+    });
+    assert_eq!(buffer[..11], [0x00, 9, 1, 2, 3, 4, 5, 6, 2, 0x01, 0x06]);
+}
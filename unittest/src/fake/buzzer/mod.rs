@@ -0,0 +1,91 @@
+//! Fake implementation of the Buzzer API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/90000_buzzer.md
+//!
+//! `Buzzer` records every tone it is asked to play, for tests to inspect with
+//! `take_tones`. Like the fake Alarm, it does not wait: each tone's completion
+//! upcall is scheduled as soon as the tone starts.
+
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+
+use crate::upcall;
+
+pub struct Buzzer {
+    tones: RefCell<Vec<Tone>>,
+    stops: Cell<usize>,
+}
+
+/// A tone played by the fake buzzer.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Tone {
+    pub frequency_hz: u32,
+    pub duration_ms: u32,
+}
+
+impl Buzzer {
+    pub fn new() -> std::rc::Rc<Buzzer> {
+        std::rc::Rc::new(Buzzer {
+            tones: Default::default(),
+            stops: Cell::new(0),
+        })
+    }
+
+    /// Returns the tones played since the last call, in order.
+    pub fn take_tones(&self) -> Vec<Tone> {
+        self.tones.take()
+    }
+
+    /// Returns the number of stop commands received so far.
+    pub fn stops(&self) -> usize {
+        self.stops.get()
+    }
+}
+
+impl crate::fake::SyscallDriver for Buzzer {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => crate::command_return::success(),
+            TONE => {
+                if argument0 == 0 || argument1 == 0 {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.tones.borrow_mut().push(Tone {
+                    frequency_hz: argument0,
+                    duration_ms: argument1,
+                });
+                upcall::schedule(DRIVER_NUM, SUBSCRIBE_DONE, (0, 0, 0))
+                    .expect("Unable to schedule upcall {}");
+                crate::command_return::success()
+            }
+            STOP => {
+                self.stops.set(self.stops.get() + 1);
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const DRIVER_NUM: u32 = 0x90000;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const TONE: u32 = 1;
+const STOP: u32 = 2;
+
+const SUBSCRIBE_DONE: u32 = 0;
@@ -0,0 +1,415 @@
+//! Fake implementation of the CAN API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/20007_can.md
+//!
+//! `Can` is the app's CAN controller. Tests connect it to a `CanBus`, on which
+//! they add `CanNode`s that stand in for other devices. Every frame sent on
+//! the bus reaches every other node and receiving controller running at the
+//! bus's bitrate, and a send succeeds if one of them acknowledges the frame.
+//! Like the capsule, the controller rejects frames until the app starts
+//! receiving; frames it accepts are queued until the shared receive buffer has
+//! room for them.
+
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::collections::VecDeque;
+use std::rc::{Rc, Weak};
+
+use crate::upcall;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+
+/// A CAN identifier.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CanId {
+    Standard(u16),
+    Extended(u32),
+}
+
+impl CanId {
+    fn from_raw(raw: u32) -> Option<CanId> {
+        match raw & EXTENDED_FLAG {
+            0 if raw <= MAX_STANDARD_ID => Some(CanId::Standard(raw as u16)),
+            0 => None,
+            _ => Some(CanId::Extended(raw & !EXTENDED_FLAG)),
+        }
+    }
+
+    fn to_raw(self) -> u32 {
+        match self {
+            CanId::Standard(id) => id as u32,
+            CanId::Extended(id) => id | EXTENDED_FLAG,
+        }
+    }
+}
+
+/// A frame sent on a `CanBus`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CanFrame {
+    pub id: CanId,
+    pub data: Vec<u8>,
+}
+
+impl CanFrame {
+    pub fn new(id: CanId, data: &[u8]) -> CanFrame {
+        CanFrame {
+            id,
+            data: data.to_vec(),
+        }
+    }
+}
+
+/// The operating mode of a `Can` controller.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CanMode {
+    Normal = 0,
+    Loopback = 1,
+    Monitoring = 2,
+}
+
+pub struct Can {
+    bitrate: Cell<u32>,
+    mode: Cell<CanMode>,
+    enabled: Cell<bool>,
+    receiving: Cell<bool>,
+    bus: RefCell<Option<Rc<CanBus>>>,
+    pending: RefCell<VecDeque<CanFrame>>,
+
+    tx: RefCell<RoAllowBuffer>,
+    rx: RefCell<RwAllowBuffer>,
+}
+
+impl Can {
+    /// Creates a disabled controller, in normal mode at 500 kbit/s.
+    pub fn new() -> Rc<Can> {
+        Rc::new(Can {
+            bitrate: Cell::new(500_000),
+            mode: Cell::new(CanMode::Normal),
+            enabled: Cell::new(false),
+            receiving: Cell::new(false),
+            bus: Default::default(),
+            pending: Default::default(),
+            tx: Default::default(),
+            rx: Default::default(),
+        })
+    }
+
+    pub fn bitrate(&self) -> u32 {
+        self.bitrate.get()
+    }
+
+    pub fn mode(&self) -> CanMode {
+        self.mode.get()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.get()
+    }
+
+    pub fn is_receiving(&self) -> bool {
+        self.receiving.get()
+    }
+
+    // Receives `frame` from the bus, if the controller listens to it, and
+    // returns whether it acknowledged the frame.
+    fn receive(&self, frame: CanFrame, bitrate: u32) -> bool {
+        if !self.receiving.get() || self.bitrate.get() != bitrate {
+            return false;
+        }
+        match self.mode.get() {
+            CanMode::Loopback => return false,
+            CanMode::Normal | CanMode::Monitoring => {}
+        }
+        self.pending.borrow_mut().push_back(frame);
+        self.deliver();
+        self.mode.get() == CanMode::Normal
+    }
+
+    // Moves pending frames into free slots of the shared ring buffer.
+    fn deliver(&self) {
+        let mut buffer = self.rx.borrow_mut();
+        let slots = buffer.len().saturating_sub(RING_META_LEN) / FRAME_SLOT_LEN;
+        if slots == 0 {
+            return;
+        }
+        loop {
+            let (read, write) = (buffer[0] as usize, buffer[1] as usize % slots);
+            let next = (write + 1) % slots;
+            if next == read {
+                return;
+            }
+            let frame = match self.pending.borrow_mut().pop_front() {
+                Some(frame) => frame,
+                None => return,
+            };
+            let slot = &mut buffer[RING_META_LEN + write * FRAME_SLOT_LEN..][..FRAME_SLOT_LEN];
+            slot[..4].copy_from_slice(&frame.id.to_raw().to_le_bytes());
+            slot[4] = frame.data.len() as u8;
+            slot[5..5 + frame.data.len()].copy_from_slice(&frame.data);
+            buffer[1] = next as u8;
+            upcall::schedule(DRIVER_NUM, SUBSCRIBE_RECEIVED, (0, 0, 0))
+                .expect("Unable to schedule upcall {}");
+        }
+    }
+
+    // Stops receiving, dropping the frames that did not fit in the receive
+    // buffer.
+    fn stop_receive(&self) {
+        self.receiving.set(false);
+        self.pending.borrow_mut().clear();
+    }
+
+    // Sends the shared data in a frame with identifier `raw_id`, and returns
+    // whether it was acknowledged.
+    fn send(&self, raw_id: u32, len: u32) -> Result<bool, ErrorCode> {
+        if !self.enabled.get() {
+            return Err(ErrorCode::Off);
+        }
+        let id = CanId::from_raw(raw_id).ok_or(ErrorCode::Invalid)?;
+        let data = match self.tx.borrow().get(..len as usize) {
+            Some(data) if data.len() <= MAX_DATA_LEN => data.to_vec(),
+            _ => return Err(ErrorCode::Size),
+        };
+        let frame = CanFrame { id, data };
+        match self.mode.get() {
+            CanMode::Monitoring => Err(ErrorCode::Invalid),
+            // The controller acknowledges its own frame, but only keeps it if
+            // it is receiving.
+            CanMode::Loopback => {
+                if self.receiving.get() {
+                    self.pending.borrow_mut().push_back(frame);
+                    self.deliver();
+                }
+                Ok(true)
+            }
+            CanMode::Normal => match self.bus.borrow().as_ref() {
+                Some(bus) if bus.bitrate == self.bitrate.get() => {
+                    Ok(bus.route(frame, Sender::Controller(self)))
+                }
+                _ => Ok(false),
+            },
+        }
+    }
+}
+
+/// A bus connecting the app's `Can` controller to `CanNode`s.
+pub struct CanBus {
+    bitrate: u32,
+    controllers: RefCell<Vec<Weak<Can>>>,
+    nodes: RefCell<Vec<Rc<CanNode>>>,
+}
+
+// The sender of a frame, which does not receive it back.
+enum Sender<'a> {
+    Controller(&'a Can),
+    Node(&'a CanNode),
+}
+
+impl CanBus {
+    /// Creates a bus running at `bitrate` bits per second.
+    pub fn new(bitrate: u32) -> Rc<CanBus> {
+        Rc::new(CanBus {
+            bitrate,
+            controllers: Default::default(),
+            nodes: Default::default(),
+        })
+    }
+
+    /// Attaches the app's controller to the bus.
+    pub fn connect(self: &Rc<Self>, can: &Rc<Can>) {
+        self.controllers.borrow_mut().push(Rc::downgrade(can));
+        can.bus.replace(Some(self.clone()));
+    }
+
+    /// Adds a node to the bus.
+    pub fn node(self: &Rc<Self>) -> Rc<CanNode> {
+        let node = Rc::new(CanNode {
+            bus: Rc::downgrade(self),
+            received: Default::default(),
+            queued: Default::default(),
+        });
+        self.nodes.borrow_mut().push(node.clone());
+        node
+    }
+
+    // Sends the frames nodes queued with `send_on_receive`.
+    fn send_queued(&self) {
+        let nodes = self.nodes.borrow().clone();
+        for node in nodes {
+            for frame in node.queued.take() {
+                self.route(frame, Sender::Node(&node));
+            }
+        }
+    }
+
+    // Delivers `frame` to every node and controller but its sender, and
+    // returns whether any acknowledged it.
+    fn route(&self, frame: CanFrame, sender: Sender) -> bool {
+        let mut acked = false;
+        for node in self.nodes.borrow().iter() {
+            if let Sender::Node(sender) = sender {
+                if core::ptr::eq(sender, node.as_ref()) {
+                    continue;
+                }
+            }
+            node.received.borrow_mut().push(frame.clone());
+            acked = true;
+        }
+        for can in self.controllers.borrow().iter().filter_map(Weak::upgrade) {
+            if let Sender::Controller(sender) = sender {
+                if core::ptr::eq(sender, can.as_ref()) {
+                    continue;
+                }
+            }
+            acked |= can.receive(frame.clone(), self.bitrate);
+        }
+        acked
+    }
+}
+
+/// A node on a `CanBus`, standing in for another device. Nodes acknowledge
+/// every frame they receive.
+pub struct CanNode {
+    bus: Weak<CanBus>,
+    received: RefCell<Vec<CanFrame>>,
+    queued: RefCell<Vec<CanFrame>>,
+}
+
+impl CanNode {
+    /// Sends `frame` on the bus. Returns `false` if no other node or
+    /// controller acknowledged it.
+    ///
+    /// Frames the app's controller accepts wait until the shared receive
+    /// buffer has a free slot.
+    pub fn send(&self, frame: CanFrame) -> bool {
+        match self.bus.upgrade() {
+            Some(bus) => bus.route(frame, Sender::Node(self)),
+            None => false,
+        }
+    }
+
+    /// Sends `frame` when the app's controller next starts receiving,
+    /// standing in for a frame that arrives while the app waits for one.
+    pub fn send_on_receive(&self, frame: CanFrame) {
+        self.queued.borrow_mut().push(frame);
+    }
+
+    /// Returns the frames received since the last call, in order.
+    pub fn take_received(&self) -> Vec<CanFrame> {
+        self.received.take()
+    }
+}
+
+impl crate::fake::SyscallDriver for Can {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        2
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_TX {
+            Ok(self.tx.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_RX {
+            let previous = self.rx.replace(buffer);
+            self.deliver();
+            Ok(previous)
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        let configuring = matches!(command_num, SET_BITRATE | SET_MODE);
+        if configuring && self.enabled.get() {
+            return crate::command_return::failure(ErrorCode::Busy);
+        }
+        match command_num {
+            DRIVER_CHECK => {}
+            SET_BITRATE => match argument0 {
+                1..=MAX_BITRATE => self.bitrate.set(argument0),
+                _ => return crate::command_return::failure(ErrorCode::Invalid),
+            },
+            SET_MODE => match argument0 {
+                0 => self.mode.set(CanMode::Normal),
+                1 => self.mode.set(CanMode::Loopback),
+                2 => self.mode.set(CanMode::Monitoring),
+                _ => return crate::command_return::failure(ErrorCode::Invalid),
+            },
+            ENABLE => self.enabled.set(true),
+            DISABLE => {
+                self.enabled.set(false);
+                self.stop_receive();
+            }
+            SEND => match self.send(argument0, argument1) {
+                Ok(acked) => {
+                    let status = match acked {
+                        true => 0,
+                        false => ErrorCode::Fail as u32,
+                    };
+                    upcall::schedule(DRIVER_NUM, SUBSCRIBE_TX_DONE, (status, 0, 0))
+                        .expect("Unable to schedule upcall {}");
+                }
+                Err(error) => return crate::command_return::failure(error),
+            },
+            START_RECEIVE => {
+                if !self.enabled.get() {
+                    return crate::command_return::failure(ErrorCode::Off);
+                }
+                self.receiving.set(true);
+                let bus = self.bus.borrow().clone();
+                if let Some(bus) = bus {
+                    bus.send_queued();
+                }
+            }
+            STOP_RECEIVE => self.stop_receive(),
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        }
+        crate::command_return::success()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const MAX_BITRATE: u32 = 1_000_000;
+const MAX_DATA_LEN: usize = 8;
+const MAX_STANDARD_ID: u32 = 0x7ff;
+const EXTENDED_FLAG: u32 = 1 << 31;
+const RING_META_LEN: usize = 2;
+const FRAME_SLOT_LEN: usize = 4 + 1 + MAX_DATA_LEN;
+
+const DRIVER_NUM: u32 = 0x20007;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const SET_BITRATE: u32 = 1;
+const SET_MODE: u32 = 2;
+const ENABLE: u32 = 3;
+const DISABLE: u32 = 4;
+const SEND: u32 = 5;
+const START_RECEIVE: u32 = 7;
+const STOP_RECEIVE: u32 = 8;
+
+const SUBSCRIBE_RECEIVED: u32 = 0;
+const SUBSCRIBE_TX_DONE: u32 = 1;
+const ALLOW_TX: u32 = 0;
+const ALLOW_RX: u32 = 0;
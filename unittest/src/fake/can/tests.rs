@@ -0,0 +1,199 @@
+use crate::fake;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+use fake::can::*;
+use libtock_platform::{
+    share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe, YieldNoWaitReturn,
+};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let can = Can::new();
+    assert!(can.command(DRIVER_CHECK, 1, 2).is_success());
+    assert!(can
+        .allow_readonly(ALLOW_TX, RoAllowBuffer::default())
+        .is_ok());
+    assert!(can.allow_readonly(1, RoAllowBuffer::default()).is_err());
+    assert!(can
+        .allow_readwrite(ALLOW_RX, RwAllowBuffer::default())
+        .is_ok());
+    assert!(can.allow_readwrite(1, RwAllowBuffer::default()).is_err());
+
+    assert!(can.command(SET_BITRATE, 250_000, 0).is_success());
+    assert_eq!(can.bitrate(), 250_000);
+    assert_eq!(
+        can.command(SET_BITRATE, 0, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        can.command(SET_BITRATE, MAX_BITRATE + 1, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert!(can.command(SET_MODE, 2, 0).is_success());
+    assert_eq!(can.mode(), CanMode::Monitoring);
+    assert_eq!(
+        can.command(SET_MODE, 3, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+
+    assert_eq!(can.command(SEND, 1, 0).get_failure(), Some(ErrorCode::Off));
+    assert!(can.command(ENABLE, 0, 0).is_success());
+    assert!(can.is_enabled());
+    assert_eq!(
+        can.command(SET_BITRATE, 125_000, 0).get_failure(),
+        Some(ErrorCode::Busy)
+    );
+    assert_eq!(
+        can.command(SET_MODE, 0, 0).get_failure(),
+        Some(ErrorCode::Busy)
+    );
+    assert_eq!(
+        can.command(SEND, 1, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert!(can.command(START_RECEIVE, 0, 0).is_success());
+    assert!(can.is_receiving());
+    assert!(can.command(STOP_RECEIVE, 0, 0).is_success());
+    assert!(!can.is_receiving());
+    assert!(can.command(START_RECEIVE, 0, 0).is_success());
+    assert!(can.command(DISABLE, 0, 0).is_success());
+    assert!(!can.is_enabled());
+    assert!(!can.is_receiving());
+    assert_eq!(
+        can.command(START_RECEIVE, 0, 0).get_failure(),
+        Some(ErrorCode::Off)
+    );
+    assert_eq!(
+        can.command(6, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+#[test]
+fn bus() {
+    let bus = CanBus::new(500_000);
+    let first = bus.node();
+    let second = bus.node();
+    let frame = CanFrame::new(CanId::Extended(0x1234_5678), &[1, 2]);
+    assert!(first.send(frame.clone()));
+    assert_eq!(first.take_received(), []);
+    assert_eq!(second.take_received(), [frame]);
+
+    // A controller that is not receiving neither receives nor acknowledges
+    // frames.
+    let lonely = CanBus::new(500_000);
+    let can = Can::new();
+    lonely.connect(&can);
+    let node = lonely.node();
+    assert!(!node.send(CanFrame::new(CanId::Standard(1), &[])));
+    can.receiving.set(true);
+    can.bitrate.set(125_000);
+    assert!(!node.send(CanFrame::new(CanId::Standard(1), &[])));
+    can.bitrate.set(500_000);
+    assert!(node.send(CanFrame::new(CanId::Standard(1), &[])));
+    can.mode.set(CanMode::Monitoring);
+    assert!(!node.send(CanFrame::new(CanId::Standard(1), &[])));
+    assert_eq!(can.pending.borrow().len(), 2);
+}
+
+// The controller rejects frames until the app starts receiving, and again
+// once it stops.
+#[test]
+fn receive_start_stop() {
+    use fake::SyscallDriver;
+    let bus = CanBus::new(500_000);
+    let can = Can::new();
+    bus.connect(&can);
+    let node = bus.node();
+    assert!(can.command(ENABLE, 0, 0).is_success());
+    assert!(!node.send(CanFrame::new(CanId::Standard(1), b"early")));
+    assert!(can.pending.borrow().is_empty());
+
+    node.send_on_receive(CanFrame::new(CanId::Standard(2), b"queued"));
+    assert!(can.pending.borrow().is_empty());
+    assert!(can.command(START_RECEIVE, 0, 0).is_success());
+    assert!(node.send(CanFrame::new(CanId::Standard(3), b"on time")));
+    assert_eq!(
+        can.pending.borrow().iter().cloned().collect::<Vec<_>>(),
+        [
+            CanFrame::new(CanId::Standard(2), b"queued"),
+            CanFrame::new(CanId::Standard(3), b"on time"),
+        ]
+    );
+
+    assert!(can.command(STOP_RECEIVE, 0, 0).is_success());
+    assert!(can.pending.borrow().is_empty());
+    assert!(!node.send(CanFrame::new(CanId::Standard(4), b"late")));
+}
+
+// Integration test that verifies Can works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let can = Can::new();
+    kernel.add_driver(&can);
+    let bus = CanBus::new(500_000);
+    bus.connect(&can);
+    let node = bus.node();
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, ENABLE, 0, 0).is_success());
+
+    let received = core::cell::Cell::new(false);
+    let sent = core::cell::Cell::new(Option::<(u32,)>::None);
+    let mut buffer = [0; RING_META_LEN + 2 * FRAME_SLOT_LEN];
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_TX>,
+            AllowRw<_, DRIVER_NUM, ALLOW_RX>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_RECEIVED>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_TX_DONE>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_ro, allow_rw, subscribe_received, subscribe_sent) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_TX>(allow_ro, b"ping").unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RX>(allow_rw, &mut buffer)
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_RECEIVED>(
+            subscribe_received,
+            &received,
+        )
+        .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_TX_DONE>(
+            subscribe_sent,
+            &sent,
+        )
+        .unwrap();
+        assert!(fake::Syscalls::command(DRIVER_NUM, START_RECEIVE, 0, 0).is_success());
+
+        assert_eq!(
+            fake::Syscalls::command(DRIVER_NUM, SEND, 0x800, 4).get_failure(),
+            Some(ErrorCode::Invalid)
+        );
+        assert_eq!(
+            fake::Syscalls::command(DRIVER_NUM, SEND, 0x123, 5).get_failure(),
+            Some(ErrorCode::Size)
+        );
+        assert!(fake::Syscalls::command(DRIVER_NUM, SEND, 0x123, 4).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(sent.get(), Some((0,)));
+        assert_eq!(
+            node.take_received(),
+            [CanFrame::new(CanId::Standard(0x123), b"ping")]
+        );
+
+        // The ring buffer holds one frame, so the second one stays queued.
+        assert!(node.send(CanFrame::new(CanId::Extended(0x42), b"pong")));
+        assert!(node.send(CanFrame::new(CanId::Standard(0x42), b"pong")));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert!(received.get());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        assert_eq!(can.pending.borrow().len(), 1);
+    });
+    assert_eq!(&buffer[..RING_META_LEN], [0, 1]);
+    assert_eq!(buffer[RING_META_LEN..][..9], *b"\x42\x00\x00\x80\x04pong");
+}
@@ -0,0 +1,145 @@
+//! Fake implementation of the CRC API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/40002_crc.md
+//!
+//! `Crc` computes reference CRCs on the host. Like the kernel, it consumes at
+//! most a limited number of bytes per input command (`DEFAULT_CHUNK_SIZE`
+//! unless changed with `set_chunk_size`), so tests exercise the chunking of
+//! long inputs.
+
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+
+use crate::upcall;
+use crate::RoAllowBuffer;
+
+pub struct Crc {
+    chunk_size: Cell<usize>,
+    digest: RefCell<Option<Digest>>,
+    inputs: Cell<usize>,
+
+    data: RefCell<RoAllowBuffer>,
+}
+
+// A CRC computation in progress.
+enum Digest {
+    Crc32(crc::Digest<'static, u32>),
+    Crc32c(crc::Digest<'static, u32>),
+    Crc16Ccitt(crc::Digest<'static, u16>),
+}
+
+impl Crc {
+    pub fn new() -> std::rc::Rc<Crc> {
+        std::rc::Rc::new(Crc {
+            chunk_size: Cell::new(DEFAULT_CHUNK_SIZE),
+            digest: Default::default(),
+            inputs: Cell::new(0),
+            data: Default::default(),
+        })
+    }
+
+    /// Sets the maximum number of bytes consumed by each input command.
+    pub fn set_chunk_size(&self, chunk_size: usize) {
+        self.chunk_size.set(chunk_size);
+    }
+
+    /// Returns the number of input commands processed so far.
+    pub fn inputs(&self) -> usize {
+        self.inputs.get()
+    }
+
+    // Adds up to `len` bytes of the shared buffer, and returns the number of
+    // bytes consumed.
+    fn input(&self, len: usize) -> Result<u32, ErrorCode> {
+        let data = self.data.borrow();
+        if len > data.len() {
+            return Err(ErrorCode::Size);
+        }
+        let data = &data[..len.min(self.chunk_size.get())];
+        match self.digest.borrow_mut().as_mut() {
+            None => return Err(ErrorCode::Reserve),
+            Some(Digest::Crc32(digest) | Digest::Crc32c(digest)) => digest.update(data),
+            Some(Digest::Crc16Ccitt(digest)) => digest.update(data),
+        }
+        self.inputs.set(self.inputs.get() + 1);
+        Ok(data.len() as u32)
+    }
+
+    fn compute(&self) -> Result<u32, ErrorCode> {
+        match self.digest.take() {
+            None => Err(ErrorCode::Reserve),
+            Some(Digest::Crc32(digest) | Digest::Crc32c(digest)) => Ok(digest.finalize()),
+            Some(Digest::Crc16Ccitt(digest)) => Ok(digest.finalize() as u32),
+        }
+    }
+}
+
+impl crate::fake::SyscallDriver for Crc {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_DATA {
+            Ok(self.data.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        let result = match command_num {
+            DRIVER_CHECK => return crate::command_return::success(),
+            SET_ALGORITHM => {
+                let digest = match argument0 {
+                    0 => Digest::Crc32(CRC_32.digest()),
+                    1 => Digest::Crc32c(CRC_32C.digest()),
+                    2 => Digest::Crc16Ccitt(CRC_16_CCITT.digest()),
+                    _ => return crate::command_return::failure(ErrorCode::Invalid),
+                };
+                self.digest.replace(Some(digest));
+                return crate::command_return::success();
+            }
+            INPUT => self.input(argument0 as usize),
+            COMPUTE => self.compute(),
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        };
+        let args = match result {
+            Ok(value) => (0, value, 0),
+            Err(error) => (error as u32, 0, 0),
+        };
+        upcall::schedule(DRIVER_NUM, SUBSCRIBE_DONE, args).expect("Unable to schedule upcall {}");
+        crate::command_return::success()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+static CRC_32: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+static CRC_32C: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISCSI);
+static CRC_16_CCITT: crc::Crc<u16> = crc::Crc::<u16>::new(&crc::CRC_16_IBM_3740);
+
+const DEFAULT_CHUNK_SIZE: usize = 64;
+
+const DRIVER_NUM: u32 = 0x40002;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const SET_ALGORITHM: u32 = 1;
+const INPUT: u32 = 2;
+const COMPUTE: u32 = 3;
+
+const SUBSCRIBE_DONE: u32 = 0;
+const ALLOW_DATA: u32 = 0;
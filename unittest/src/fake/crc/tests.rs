@@ -0,0 +1,78 @@
+use crate::fake;
+use crate::RoAllowBuffer;
+use fake::crc::*;
+use libtock_platform::{share, AllowRo, DefaultConfig, ErrorCode, Subscribe, YieldNoWaitReturn};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let crc = Crc::new();
+    assert!(crc.command(DRIVER_CHECK, 1, 2).is_success());
+    assert!(crc
+        .allow_readonly(ALLOW_DATA, RoAllowBuffer::default())
+        .is_ok());
+    assert!(crc.allow_readonly(1, RoAllowBuffer::default()).is_err());
+
+    assert_eq!(
+        crc.command(SET_ALGORITHM, 3, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert!(crc.command(SET_ALGORITHM, 0, 0).is_success());
+    assert_eq!(
+        crc.command(4, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// The standard check values, computed over "123456789".
+#[test]
+fn check_values() {
+    assert_eq!(CRC_32.checksum(b"123456789"), 0xcbf43926);
+    assert_eq!(CRC_32C.checksum(b"123456789"), 0xe3069283);
+    assert_eq!(CRC_16_CCITT.checksum(b"123456789"), 0x29b1);
+}
+
+// Integration test that verifies Crc works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let crc = Crc::new();
+    kernel.add_driver(&crc);
+    crc.set_chunk_size(5);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+
+    let called = core::cell::Cell::new(Option::<(u32, u32)>::None);
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_DATA>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_DONE>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_ro, subscribe) = handle.split();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_DONE>(
+            subscribe, &called,
+        )
+        .unwrap();
+
+        // No algorithm has been selected.
+        assert!(fake::Syscalls::command(DRIVER_NUM, COMPUTE, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((ErrorCode::Reserve as u32, 0)));
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, SET_ALGORITHM, 0, 0).is_success());
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_DATA>(allow_ro, b"123456789")
+            .unwrap();
+        assert!(fake::Syscalls::command(DRIVER_NUM, INPUT, 9, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((0, 5)));
+        assert!(fake::Syscalls::command(DRIVER_NUM, INPUT, 10, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((ErrorCode::Size as u32, 0)));
+    });
+    assert_eq!(crc.inputs(), 1);
+}
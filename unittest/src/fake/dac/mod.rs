@@ -0,0 +1,89 @@
+//! Fake implementation of the DAC API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/00006_dac.md
+//!
+//! `Dac` models a 10-bit converter, which must be initialized before its
+//! output is set. It records the current output, which
+//! tests retrieve with `value`, and every value set, which tests retrieve with
+//! `take_history`.
+
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+
+pub struct Dac {
+    initialized: Cell<bool>,
+    value: Cell<Option<u32>>,
+    history: RefCell<Vec<u32>>,
+}
+
+impl Dac {
+    /// The full scale of the converter.
+    pub const MAX_VALUE: u32 = 1023;
+
+    pub fn new() -> std::rc::Rc<Dac> {
+        std::rc::Rc::new(Dac {
+            initialized: Cell::new(false),
+            value: Cell::new(None),
+            history: Default::default(),
+        })
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.initialized.get()
+    }
+
+    /// Returns the current output, or `None` if it was never set.
+    pub fn value(&self) -> Option<u32> {
+        self.value.get()
+    }
+
+    /// Returns the values set since the last call, in order.
+    pub fn take_history(&self) -> Vec<u32> {
+        self.history.take()
+    }
+}
+
+impl crate::fake::SyscallDriver for Dac {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        0
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => crate::command_return::success(),
+            INITIALIZE => {
+                self.initialized.set(true);
+                crate::command_return::success()
+            }
+            SET_VALUE => {
+                if !self.initialized.get() {
+                    return crate::command_return::failure(ErrorCode::Off);
+                }
+                if argument0 > Self::MAX_VALUE {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.value.set(Some(argument0));
+                self.history.borrow_mut().push(argument0);
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const DRIVER_NUM: u32 = 0x6;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const INITIALIZE: u32 = 1;
+const SET_VALUE: u32 = 2;
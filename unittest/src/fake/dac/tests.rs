@@ -0,0 +1,48 @@
+use crate::fake;
+use fake::dac::*;
+use libtock_platform::ErrorCode;
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let dac = Dac::new();
+    assert!(dac.command(DRIVER_CHECK, 1, 2).is_success());
+    assert_eq!(dac.value(), None);
+
+    assert_eq!(
+        dac.command(SET_VALUE, 512, 0).get_failure(),
+        Some(ErrorCode::Off)
+    );
+    assert!(!dac.is_initialized());
+    assert!(dac.command(INITIALIZE, 0, 0).is_success());
+    assert!(dac.is_initialized());
+    assert!(dac.command(SET_VALUE, 512, 0).is_success());
+    assert_eq!(dac.value(), Some(512));
+    assert!(dac.command(SET_VALUE, Dac::MAX_VALUE, 0).is_success());
+    assert_eq!(
+        dac.command(SET_VALUE, Dac::MAX_VALUE + 1, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(dac.value(), Some(Dac::MAX_VALUE));
+    assert_eq!(dac.take_history(), [512, Dac::MAX_VALUE]);
+    assert_eq!(dac.take_history(), []);
+    assert_eq!(
+        dac.command(3, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies Dac works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let dac = Dac::new();
+    kernel.add_driver(&dac);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, INITIALIZE, 0, 0).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, SET_VALUE, 100, 0).is_success());
+    assert_eq!(dac.value(), Some(100));
+}
@@ -0,0 +1,133 @@
+//! Fake implementation of the date and time (RTC) API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/90007_date_time.md
+//!
+//! Like the real API, `Rtc` reports the date and time with an upcall. Its
+//! clock does not advance on its own; tests set it with `set_date_time`.
+
+use core::cell::Cell;
+use libtock_platform::{CommandReturn, ErrorCode};
+
+use crate::upcall;
+
+/// A date and time, as the driver encodes it.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct RtcDateTime {
+    pub year: u16,
+    /// From 1 to 12.
+    pub month: u8,
+    pub day: u8,
+    /// From 0 (Sunday) to 6 (Saturday).
+    pub day_of_week: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl RtcDateTime {
+    fn is_valid(&self) -> bool {
+        (1..=12).contains(&self.month)
+            && (1..=31).contains(&self.day)
+            && self.day_of_week < 7
+            && self.hour < 24
+            && self.minute < 60
+            && self.second < 60
+    }
+
+    fn encode(&self) -> (u32, u32) {
+        let date = (self.year as u32) << 9 | (self.month as u32) << 5 | self.day as u32;
+        let time = (self.day_of_week as u32) << 17
+            | (self.hour as u32) << 12
+            | (self.minute as u32) << 6
+            | self.second as u32;
+        (date, time)
+    }
+
+    fn decode(date: u32, time: u32) -> RtcDateTime {
+        RtcDateTime {
+            year: (date >> 9) as u16,
+            month: (date >> 5 & 0xf) as u8,
+            day: (date & 0x1f) as u8,
+            day_of_week: (time >> 17 & 0x7) as u8,
+            hour: (time >> 12 & 0x1f) as u8,
+            minute: (time >> 6 & 0x3f) as u8,
+            second: (time & 0x3f) as u8,
+        }
+    }
+}
+
+pub struct Rtc {
+    date_time: Cell<RtcDateTime>,
+}
+
+impl Rtc {
+    /// Creates a clock set to 2000-01-01 00:00:00, a Saturday.
+    pub fn new() -> std::rc::Rc<Rtc> {
+        std::rc::Rc::new(Rtc {
+            date_time: Cell::new(RtcDateTime {
+                year: 2000,
+                month: 1,
+                day: 1,
+                day_of_week: 6,
+                hour: 0,
+                minute: 0,
+                second: 0,
+            }),
+        })
+    }
+
+    pub fn date_time(&self) -> RtcDateTime {
+        self.date_time.get()
+    }
+
+    pub fn set_date_time(&self, date_time: RtcDateTime) {
+        self.date_time.set(date_time);
+    }
+}
+
+impl crate::fake::SyscallDriver for Rtc {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => {}
+            GET_DATE_TIME => {
+                let (date, time) = self.date_time.get().encode();
+                upcall::schedule(DRIVER_NUM, SUBSCRIBE_DONE, (0, date, time))
+                    .expect("Unable to schedule upcall {}");
+            }
+            SET_DATE_TIME => {
+                let date_time = RtcDateTime::decode(argument0, argument1);
+                if !date_time.is_valid() {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.date_time.set(date_time);
+                upcall::schedule(DRIVER_NUM, SUBSCRIBE_DONE, (0, 0, 0))
+                    .expect("Unable to schedule upcall {}");
+            }
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        }
+        crate::command_return::success()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const DRIVER_NUM: u32 = 0x90007;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const GET_DATE_TIME: u32 = 1;
+const SET_DATE_TIME: u32 = 2;
+
+const SUBSCRIBE_DONE: u32 = 0;
@@ -0,0 +1,65 @@
+use crate::fake;
+use fake::date_time::*;
+use libtock_platform::{share, DefaultConfig, ErrorCode, YieldNoWaitReturn};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let kernel = fake::Kernel::new();
+    let rtc = Rtc::new();
+    kernel.add_driver(&rtc);
+    assert!(rtc.command(DRIVER_CHECK, 1, 2).is_success());
+    assert!(rtc.command(GET_DATE_TIME, 0, 0).is_success());
+
+    let date_time = RtcDateTime {
+        year: 2024,
+        month: 2,
+        day: 29,
+        day_of_week: 4,
+        hour: 12,
+        minute: 30,
+        second: 59,
+    };
+    let (date, time) = date_time.encode();
+    assert_eq!(date, 2024 << 9 | 2 << 5 | 29);
+    assert_eq!(time, 4 << 17 | 12 << 12 | 30 << 6 | 59);
+    assert!(rtc.command(SET_DATE_TIME, date, time).is_success());
+    assert_eq!(rtc.date_time(), date_time);
+    assert_eq!(
+        rtc.command(SET_DATE_TIME, date, 24 << 12).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        rtc.command(SET_DATE_TIME, 2024 << 9 | 13 << 5 | 1, 0)
+            .get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(rtc.date_time(), date_time);
+    assert_eq!(
+        rtc.command(3, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies Rtc works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let rtc = Rtc::new();
+    kernel.add_driver(&rtc);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+
+    let called = core::cell::Cell::new(Option::<(u32, u32, u32)>::None);
+    share::scope(|subscribe| {
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_DONE>(
+            subscribe, &called,
+        )
+        .unwrap();
+        assert!(fake::Syscalls::command(DRIVER_NUM, GET_DATE_TIME, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((0, 2000 << 9 | 1 << 5 | 1, 6 << 17)));
+    });
+}
@@ -0,0 +1,186 @@
+//! Fake implementation of the HMAC API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/40003_hmac.md
+//!
+//! `Hmac` computes real HMACs on the host, so tests can verify results computed
+//! through the driver end-to-end.
+
+use core::cell::RefCell;
+use hmac::Mac;
+use libtock_platform::{CommandReturn, ErrorCode};
+
+use crate::upcall;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+
+pub struct Hmac {
+    mac: RefCell<Option<HmacState>>,
+
+    key: RefCell<RoAllowBuffer>,
+    data: RefCell<RoAllowBuffer>,
+    dest: RefCell<RwAllowBuffer>,
+}
+
+// An HMAC in progress. Like the capsule, the fake reads the key when it
+// computes the HMAC, so the message is buffered until `finish`.
+struct HmacState {
+    algorithm: Algorithm,
+    message: Vec<u8>,
+}
+
+#[derive(Clone, Copy)]
+enum Algorithm {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl Hmac {
+    pub fn new() -> std::rc::Rc<Hmac> {
+        std::rc::Rc::new(Hmac {
+            mac: Default::default(),
+            key: Default::default(),
+            data: Default::default(),
+            dest: Default::default(),
+        })
+    }
+
+    /// Returns the output length of the HMAC in progress, or `None` if no HMAC
+    /// has been started.
+    pub fn output_len(&self) -> Option<usize> {
+        self.mac.borrow().as_ref().map(|mac| match mac.algorithm {
+            Algorithm::Sha256 => 32,
+            Algorithm::Sha384 => 48,
+            Algorithm::Sha512 => 64,
+        })
+    }
+
+    fn start(&self, algorithm: u32) -> Result<(), ErrorCode> {
+        let algorithm = match algorithm {
+            0 => Algorithm::Sha256,
+            1 => Algorithm::Sha384,
+            2 => Algorithm::Sha512,
+            _ => return Err(ErrorCode::Invalid),
+        };
+        self.mac.replace(Some(HmacState {
+            algorithm,
+            message: Vec::new(),
+        }));
+        Ok(())
+    }
+
+    fn update(&self) -> Result<(), ErrorCode> {
+        let data = self.data.borrow();
+        match self.mac.borrow_mut().as_mut() {
+            None => return Err(ErrorCode::Invalid),
+            Some(mac) => mac.message.extend_from_slice(&data),
+        }
+        Ok(())
+    }
+
+    // Writes the HMAC and ends it; a new HMAC must be started. The key is read
+    // here, and an empty key is treated as not shared, failing with `Invalid`.
+    fn finish(&self) -> Result<(), ErrorCode> {
+        let len = self.output_len().ok_or(ErrorCode::Invalid)?;
+        let mut dest = self.dest.borrow_mut();
+        if dest.len() < len {
+            return Err(ErrorCode::Size);
+        }
+        let key = self.key.borrow();
+        if key.is_empty() {
+            return Err(ErrorCode::Invalid);
+        }
+        let mac = self.mac.take().ok_or(ErrorCode::Invalid)?;
+        // HMAC accepts keys of any length.
+        match mac.algorithm {
+            Algorithm::Sha256 => {
+                let mut hmac = hmac::Hmac::<sha2::Sha256>::new_from_slice(&key).unwrap();
+                hmac.update(&mac.message);
+                dest[..len].copy_from_slice(&hmac.finalize().into_bytes());
+            }
+            Algorithm::Sha384 => {
+                let mut hmac = hmac::Hmac::<sha2::Sha384>::new_from_slice(&key).unwrap();
+                hmac.update(&mac.message);
+                dest[..len].copy_from_slice(&hmac.finalize().into_bytes());
+            }
+            Algorithm::Sha512 => {
+                let mut hmac = hmac::Hmac::<sha2::Sha512>::new_from_slice(&key).unwrap();
+                hmac.update(&mac.message);
+                dest[..len].copy_from_slice(&hmac.finalize().into_bytes());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl crate::fake::SyscallDriver for Hmac {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        match buffer_num {
+            ALLOW_KEY => Ok(self.key.replace(buffer)),
+            ALLOW_DATA => Ok(self.data.replace(buffer)),
+            _ => Err((buffer, ErrorCode::Invalid)),
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_DEST {
+            Ok(self.dest.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        let result = match command_num {
+            SET_ALGORITHM => {
+                return match self.start(argument0) {
+                    Ok(()) => crate::command_return::success(),
+                    Err(error) => crate::command_return::failure(error),
+                }
+            }
+            UPDATE => self.update(),
+            FINISH => self.finish(),
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        };
+        let status = match result {
+            Ok(()) => 0,
+            Err(error) => error as u32,
+        };
+        upcall::schedule(DRIVER_NUM, SUBSCRIBE_DONE, (status, 0, 0))
+            .expect("Unable to schedule upcall {}");
+        crate::command_return::success()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const DRIVER_NUM: u32 = 0x40003;
+
+// Command numbers. The capsule has no separate driver check command.
+const SET_ALGORITHM: u32 = 0;
+const UPDATE: u32 = 2;
+const FINISH: u32 = 3;
+
+const SUBSCRIBE_DONE: u32 = 0;
+const ALLOW_KEY: u32 = 0;
+const ALLOW_DATA: u32 = 1;
+const ALLOW_DEST: u32 = 2;
@@ -0,0 +1,124 @@
+use crate::fake;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+use fake::hmac::*;
+use hmac::Mac;
+use libtock_platform::YieldNoWaitReturn;
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let hmac = Hmac::new();
+    assert!(hmac
+        .allow_readonly(ALLOW_KEY, RoAllowBuffer::default())
+        .is_ok());
+    assert!(hmac
+        .allow_readonly(ALLOW_DATA, RoAllowBuffer::default())
+        .is_ok());
+    assert!(hmac.allow_readonly(2, RoAllowBuffer::default()).is_err());
+    assert!(hmac
+        .allow_readwrite(ALLOW_DEST, RwAllowBuffer::default())
+        .is_ok());
+    assert!(hmac.allow_readwrite(0, RwAllowBuffer::default()).is_err());
+
+    assert_eq!(hmac.output_len(), None);
+    assert_eq!(
+        hmac.command(SET_ALGORITHM, 3, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert!(hmac.command(SET_ALGORITHM, 2, 0).is_success());
+    assert_eq!(hmac.output_len(), Some(64));
+    assert_eq!(
+        hmac.command(6, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies Hmac works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let hmac = Hmac::new();
+    kernel.add_driver(&hmac);
+
+    let mut mac = [0; 32];
+    let called = core::cell::Cell::new(Option::<(u32,)>::None);
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_KEY>,
+            AllowRo<_, DRIVER_NUM, ALLOW_DATA>,
+            AllowRw<_, DRIVER_NUM, ALLOW_DEST>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_DONE>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_key, allow_data, allow_dest, subscribe) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_KEY>(allow_key, b"key")
+            .unwrap();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_DATA>(allow_data, b"data")
+            .unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_DEST>(allow_dest, &mut mac)
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_DONE>(
+            subscribe, &called,
+        )
+        .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, SET_ALGORITHM, 0, 0).is_success());
+        assert!(fake::Syscalls::command(DRIVER_NUM, UPDATE, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((0,)));
+        assert!(fake::Syscalls::command(DRIVER_NUM, FINISH, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((0,)));
+
+        // The HMAC ended, so it must be restarted before more data is added.
+        assert!(fake::Syscalls::command(DRIVER_NUM, UPDATE, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((ErrorCode::Invalid as u32,)));
+    });
+    let mut expected = hmac::Hmac::<sha2::Sha256>::new_from_slice(b"key").unwrap();
+    expected.update(b"data");
+    assert_eq!(mac[..], expected.finalize().into_bytes()[..]);
+}
+
+// The key is read when the HMAC is finished, so it must still be shared then.
+#[test]
+fn key_unshared_before_finish() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let hmac = Hmac::new();
+    kernel.add_driver(&hmac);
+
+    let mut mac = [0; 32];
+    let called = core::cell::Cell::new(Option::<(u32,)>::None);
+    share::scope::<AllowRo<_, DRIVER_NUM, ALLOW_KEY>, _, _>(|allow_key| {
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_KEY>(allow_key, b"key")
+            .unwrap();
+        assert!(fake::Syscalls::command(DRIVER_NUM, SET_ALGORITHM, 0, 0).is_success());
+    });
+    share::scope::<
+        (
+            AllowRw<_, DRIVER_NUM, ALLOW_DEST>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_DONE>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_dest, subscribe) = handle.split();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_DEST>(allow_dest, &mut mac)
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_DONE>(
+            subscribe, &called,
+        )
+        .unwrap();
+        assert!(fake::Syscalls::command(DRIVER_NUM, FINISH, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((ErrorCode::Invalid as u32,)));
+    });
+    assert_eq!(mac, [0; 32]);
+}
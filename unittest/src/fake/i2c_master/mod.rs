@@ -0,0 +1,159 @@
+//! Fake implementation of the I2C master API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/20003_i2c_master.md
+//!
+//! `I2cMaster` emulates a bus with register-mapped devices attached to it.
+//! Devices are added with `add_device`, and behave like most I2C sensors do: the
+//! first byte written to a device selects a register, further written bytes
+//! are stored starting at that register, and reads return register contents
+//! starting at the selected register. The register pointer auto-increments
+//! and wraps around at the end of the register map.
+//!
+//! Transfers to an address with no device attached fail with `NoAck`.
+
+use core::cell::RefCell;
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::collections::HashMap;
+
+use crate::upcall;
+use crate::RwAllowBuffer;
+
+pub struct I2cMaster {
+    buffer: RefCell<RwAllowBuffer>,
+    devices: RefCell<HashMap<u8, Device>>,
+}
+
+struct Device {
+    registers: Vec<u8>,
+    pointer: usize,
+}
+
+impl Device {
+    fn write(&mut self, bytes: &[u8]) {
+        if let Some((&register, data)) = bytes.split_first() {
+            self.pointer = register as usize;
+            for &byte in data {
+                if let Some(register) = self.next_register() {
+                    *register = byte;
+                }
+            }
+        }
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) {
+        for byte in buffer {
+            *byte = self.next_register().map_or(0xff, |register| *register);
+        }
+    }
+
+    fn next_register(&mut self) -> Option<&mut u8> {
+        if self.registers.is_empty() {
+            return None;
+        }
+        let index = self.pointer % self.registers.len();
+        self.pointer = index + 1;
+        self.registers.get_mut(index)
+    }
+}
+
+impl I2cMaster {
+    pub fn new() -> std::rc::Rc<I2cMaster> {
+        std::rc::Rc::new(I2cMaster {
+            buffer: Default::default(),
+            devices: Default::default(),
+        })
+    }
+
+    /// Attaches a device at `address`, with its registers initialized to
+    /// `registers`. Replaces any device previously attached at `address`.
+    pub fn add_device(&self, address: u8, registers: &[u8]) {
+        self.devices.borrow_mut().insert(
+            address,
+            Device {
+                registers: Vec::from(registers),
+                pointer: 0,
+            },
+        );
+    }
+
+    /// Returns the current register contents of the device at `address`.
+    pub fn get_registers(&self, address: u8) -> Option<Vec<u8>> {
+        self.devices
+            .borrow()
+            .get(&address)
+            .map(|device| device.registers.clone())
+    }
+
+    fn transfer(&self, command_num: u32, address: u8, write_len: usize, read_len: usize) {
+        let mut buffer = self.buffer.borrow_mut();
+        let status = match self.devices.borrow_mut().get_mut(&address) {
+            None => ErrorCode::NoAck as u32,
+            Some(device) => {
+                device.write(&buffer[..write_len]);
+                device.read(&mut buffer[..read_len]);
+                0
+            }
+        };
+        upcall::schedule(DRIVER_NUM, SUBSCRIBE_COMPLETE, (command_num, status, 0))
+            .expect("Unable to schedule upcall {}");
+    }
+}
+
+impl crate::fake::SyscallDriver for I2cMaster {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_BUFFER {
+            Ok(self.buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        let (address, write_len, read_len) = match command_num {
+            DRIVER_CHECK => return crate::command_return::success(),
+            WRITE => (argument0 as u8, argument1 as usize, 0),
+            READ => (argument0 as u8, 0, argument1 as usize),
+            WRITE_READ => (
+                argument0 as u8,
+                (argument0 >> 8) as usize,
+                argument1 as usize,
+            ),
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        };
+        let buffer_len = self.buffer.borrow().len();
+        if write_len > buffer_len || read_len > buffer_len {
+            return crate::command_return::failure(ErrorCode::Size);
+        }
+        self.transfer(command_num, address, write_len, read_len);
+        crate::command_return::success()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const DRIVER_NUM: u32 = 0x20003;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const WRITE: u32 = 1;
+const READ: u32 = 2;
+const WRITE_READ: u32 = 3;
+
+const SUBSCRIBE_COMPLETE: u32 = 0;
+const ALLOW_BUFFER: u32 = 1;
@@ -0,0 +1,79 @@
+use crate::fake;
+use crate::RwAllowBuffer;
+use fake::i2c_master::*;
+use libtock_platform::{share, AllowRw, DefaultConfig, ErrorCode, Subscribe, YieldNoWaitReturn};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let i2c = I2cMaster::new();
+    assert!(i2c.command(DRIVER_CHECK, 1, 2).is_success());
+    assert!(i2c
+        .allow_readwrite(ALLOW_BUFFER, RwAllowBuffer::default())
+        .is_ok());
+    assert!(i2c.allow_readwrite(2, RwAllowBuffer::default()).is_err());
+
+    // The zero-length buffer cannot hold any transfer.
+    assert_eq!(
+        i2c.command(WRITE, 0x10, 1).get_failure(),
+        Some(ErrorCode::Size)
+    );
+    assert_eq!(
+        i2c.command(READ, 0x10, 1).get_failure(),
+        Some(ErrorCode::Size)
+    );
+    assert_eq!(
+        i2c.command(4, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies I2cMaster works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let i2c = I2cMaster::new();
+    kernel.add_driver(&i2c);
+    i2c.add_device(0x1d, &[0x10, 0x11, 0x12, 0x13]);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+
+    let mut buf = [2, 0xaa, 0xbb];
+    let called = core::cell::Cell::new(Option::<(u32, u32)>::None);
+    share::scope::<
+        (
+            AllowRw<_, DRIVER_NUM, ALLOW_BUFFER>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_COMPLETE>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_rw, subscribe) = handle.split();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_BUFFER>(allow_rw, &mut buf)
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_COMPLETE>(
+            subscribe, &called,
+        )
+        .unwrap();
+
+        // Write two bytes starting at register 2.
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE, 0x1d, 3).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((WRITE, 0)));
+
+        // Nothing is attached at 0x1e.
+        assert!(fake::Syscalls::command(DRIVER_NUM, READ, 0x1e, 1).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((READ, ErrorCode::NoAck as u32)));
+
+        // Select register 2 again, then read 3 registers, wrapping around.
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE_READ, 1 << 8 | 0x1d, 3).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((WRITE_READ, 0)));
+    });
+    assert_eq!(buf, [0xaa, 0xbb, 0x10]);
+    assert_eq!(i2c.get_registers(0x1d), Some(vec![0x10, 0x11, 0xaa, 0xbb]));
+    assert_eq!(i2c.get_registers(0x1e), None);
+}
@@ -0,0 +1,371 @@
+//! Fake implementation of the IEEE 802.15.4 API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/30001_ieee802154.md
+//!
+//! `Ieee802154` is the app's radio. Tests connect `Ieee802154Peer`s to it,
+//! which stand in for other devices: frames the app transmits are delivered to
+//! the peers they are addressed to, and peers send frames to the app with
+//! `Ieee802154::send_from`. Frames are only exchanged when the radio is on and
+//! the peer shares its PAN ID and channel. Frames sent to the app are queued
+//! until it shares a receive buffer with room for them.
+
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use crate::upcall;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+
+pub struct Ieee802154 {
+    on: Cell<bool>,
+    pan: Cell<u16>,
+    short_address: Cell<u16>,
+    long_address: Cell<u64>,
+    channel: Cell<u8>,
+    tx_power: Cell<i8>,
+    commits: Cell<usize>,
+    sequence_number: Cell<u8>,
+    peers: RefCell<Vec<Rc<Ieee802154Peer>>>,
+    pending: RefCell<VecDeque<PendingFrame>>,
+
+    write: RefCell<RoAllowBuffer>,
+    read: RefCell<RwAllowBuffer>,
+}
+
+impl Ieee802154 {
+    /// Creates a radio that is off, on channel 26, with PAN ID and short
+    /// address 0xffff.
+    pub fn new() -> Rc<Ieee802154> {
+        Rc::new(Ieee802154 {
+            on: Cell::new(false),
+            pan: Cell::new(BROADCAST),
+            short_address: Cell::new(BROADCAST),
+            long_address: Cell::new(0),
+            channel: Cell::new(26),
+            tx_power: Cell::new(0),
+            commits: Cell::new(0),
+            sequence_number: Cell::new(0),
+            peers: Default::default(),
+            pending: Default::default(),
+            write: Default::default(),
+            read: Default::default(),
+        })
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on.get()
+    }
+
+    pub fn tx_power(&self) -> i8 {
+        self.tx_power.get()
+    }
+
+    /// Returns the number of times the configuration was committed.
+    pub fn commits(&self) -> usize {
+        self.commits.get()
+    }
+
+    /// Lets `peer` exchange frames with the app.
+    pub fn connect(&self, peer: &Rc<Ieee802154Peer>) {
+        self.peers.borrow_mut().push(peer.clone());
+    }
+
+    /// Sends a data frame from `peer` to `destination` (0xffff to broadcast).
+    /// Returns `false` if the app's radio would not receive the frame.
+    ///
+    /// Unlike a real radio, which drops frames it has no room for, the frame
+    /// waits until the app shares a receive buffer with a free slot.
+    pub fn send_from(&self, peer: &Ieee802154Peer, destination: u16, payload: &[u8]) -> bool {
+        if !self.on.get()
+            || peer.pan != self.pan.get()
+            || peer.channel != self.channel.get()
+            || (destination != BROADCAST && destination != self.short_address.get())
+        {
+            return false;
+        }
+        let sequence_number = peer.sequence_number.get();
+        peer.sequence_number.set(sequence_number.wrapping_add(1));
+        let header = mac_header(
+            sequence_number,
+            peer.pan,
+            destination,
+            &peer.short_address.to_le_bytes(),
+        );
+        if header.len() + payload.len() + FCS_LEN > MAX_FRAME_LEN {
+            return false;
+        }
+        let upcall_args = (
+            (peer.pan as u32) << 16 | peer.pan as u32,
+            destination as u32,
+            peer.short_address as u32,
+        );
+        self.pending.borrow_mut().push_back(PendingFrame {
+            header,
+            payload: payload.to_vec(),
+            upcall_args,
+        });
+        self.deliver();
+        true
+    }
+
+    // Moves pending frames into free slots of the shared ring buffer.
+    fn deliver(&self) {
+        let mut buffer = self.read.borrow_mut();
+        let slots = buffer.len().saturating_sub(RING_META_LEN) / RX_FRAME_SLOT_LEN;
+        if slots == 0 {
+            return;
+        }
+        loop {
+            let (read, write) = (buffer[0] as usize, buffer[1] as usize % slots);
+            let next = (write + 1) % slots;
+            if next == read {
+                return;
+            }
+            let frame = match self.pending.borrow_mut().pop_front() {
+                Some(frame) => frame,
+                None => return,
+            };
+            let (header, payload) = (&frame.header, &frame.payload);
+            let slot =
+                &mut buffer[RING_META_LEN + write * RX_FRAME_SLOT_LEN..][..RX_FRAME_SLOT_LEN];
+            slot[..FRAME_META_LEN].copy_from_slice(&[header.len() as u8, payload.len() as u8, 0]);
+            let data = &mut slot[FRAME_META_LEN..];
+            data[..header.len()].copy_from_slice(header);
+            data[header.len()..header.len() + payload.len()].copy_from_slice(payload);
+            buffer[1] = next as u8;
+            upcall::schedule(DRIVER_NUM, SUBSCRIBE_FRAME_RECEIVED, frame.upcall_args)
+                .expect("Unable to schedule upcall {}");
+        }
+    }
+
+    // Transmits the shared payload to `destination`, and returns whether it
+    // was acknowledged.
+    fn transmit(&self, destination: u16) -> Result<bool, ErrorCode> {
+        if !self.on.get() {
+            return Err(ErrorCode::Off);
+        }
+        let source = match self.short_address.get() {
+            BROADCAST => self.long_address.get().to_le_bytes().to_vec(),
+            short_address => short_address.to_le_bytes().to_vec(),
+        };
+        let sequence_number = self.sequence_number.get();
+        let header = mac_header(sequence_number, self.pan.get(), destination, &source);
+        let payload = self.write.borrow().to_vec();
+        if header.len() + payload.len() + FCS_LEN > MAX_FRAME_LEN {
+            return Err(ErrorCode::Size);
+        }
+        self.sequence_number.set(sequence_number.wrapping_add(1));
+
+        let mut acked = false;
+        for peer in self.peers.borrow().iter() {
+            if peer.pan != self.pan.get()
+                || peer.channel != self.channel.get()
+                || (destination != BROADCAST && destination != peer.short_address)
+            {
+                continue;
+            }
+            peer.received.borrow_mut().push(PeerFrame {
+                header: header.clone(),
+                payload: payload.clone(),
+            });
+            acked |= destination != BROADCAST && peer.acknowledge.get();
+        }
+        Ok(acked)
+    }
+}
+
+// A frame sent to the app that is not in its receive buffer yet.
+struct PendingFrame {
+    header: Vec<u8>,
+    payload: Vec<u8>,
+    upcall_args: (u32, u32, u32),
+}
+
+/// A device that exchanges frames with the app's radio.
+pub struct Ieee802154Peer {
+    pan: u16,
+    short_address: u16,
+    channel: u8,
+    acknowledge: Cell<bool>,
+    sequence_number: Cell<u8>,
+    received: RefCell<Vec<PeerFrame>>,
+}
+
+/// A frame received by an `Ieee802154Peer`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PeerFrame {
+    pub header: Vec<u8>,
+    pub payload: Vec<u8>,
+}
+
+impl Ieee802154Peer {
+    /// Creates a peer that acknowledges the frames addressed to it.
+    pub fn new(pan: u16, short_address: u16, channel: u8) -> Rc<Ieee802154Peer> {
+        Rc::new(Ieee802154Peer {
+            pan,
+            short_address,
+            channel,
+            acknowledge: Cell::new(true),
+            sequence_number: Cell::new(0),
+            received: Default::default(),
+        })
+    }
+
+    /// Sets whether the peer acknowledges the frames addressed to it.
+    pub fn set_acknowledge(&self, acknowledge: bool) {
+        self.acknowledge.set(acknowledge);
+    }
+
+    /// Returns the frames received since the last call, in order.
+    pub fn take_received(&self) -> Vec<PeerFrame> {
+        self.received.take()
+    }
+}
+
+impl crate::fake::SyscallDriver for Ieee802154 {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        2
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_WRITE {
+            Ok(self.write.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_READ {
+            let previous = self.read.replace(buffer);
+            self.deliver();
+            Ok(previous)
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => {}
+            IS_ON => {
+                if !self.on.get() {
+                    return crate::command_return::failure(ErrorCode::Off);
+                }
+            }
+            SET_SHORT_ADDRESS => self.short_address.set(argument0 as u16),
+            SET_LONG_ADDRESS => self
+                .long_address
+                .set((argument1 as u64) << 32 | argument0 as u64),
+            SET_PAN => self.pan.set(argument0 as u16),
+            SET_CHANNEL => match argument0 {
+                11..=26 => self.channel.set(argument0 as u8),
+                _ => return crate::command_return::failure(ErrorCode::Invalid),
+            },
+            SET_TX_POWER => self.tx_power.set(argument0 as i8),
+            COMMIT_CONFIG => self.commits.set(self.commits.get() + 1),
+            GET_SHORT_ADDRESS => {
+                return crate::command_return::success_u32(self.short_address.get().into())
+            }
+            GET_LONG_ADDRESS => return crate::command_return::success_u64(self.long_address.get()),
+            GET_PAN => return crate::command_return::success_u32(self.pan.get().into()),
+            GET_CHANNEL => return crate::command_return::success_u32(self.channel.get().into()),
+            GET_TX_POWER => return crate::command_return::success_u32(self.tx_power.get() as u32),
+            TRANSMIT => match self.transmit(argument0 as u16) {
+                Ok(acked) => {
+                    upcall::schedule(
+                        DRIVER_NUM,
+                        SUBSCRIBE_FRAME_TRANSMITTED,
+                        (0, acked as u32, 0),
+                    )
+                    .expect("Unable to schedule upcall {}");
+                }
+                Err(error) => return crate::command_return::failure(error),
+            },
+            TURN_ON => self.on.set(true),
+            TURN_OFF => self.on.set(false),
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        }
+        crate::command_return::success()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+// Builds the MAC header of a data frame with a short destination address and
+// PAN ID compression. `source` is a short (2 byte) or long (8 byte) address.
+fn mac_header(sequence_number: u8, pan: u16, destination: u16, source: &[u8]) -> Vec<u8> {
+    let mut frame_control = FRAME_TYPE_DATA | PAN_ID_COMPRESSION | FRAME_VERSION_2006;
+    frame_control |= SHORT_ADDRESS_MODE << 10;
+    frame_control |= match source.len() {
+        2 => SHORT_ADDRESS_MODE << 14,
+        _ => LONG_ADDRESS_MODE << 14,
+    };
+    if destination != BROADCAST {
+        frame_control |= ACK_REQUEST;
+    }
+    let mut header = frame_control.to_le_bytes().to_vec();
+    header.push(sequence_number);
+    header.extend_from_slice(&pan.to_le_bytes());
+    header.extend_from_slice(&destination.to_le_bytes());
+    header.extend_from_slice(source);
+    header
+}
+
+const BROADCAST: u16 = 0xffff;
+
+const MAX_FRAME_LEN: usize = 127;
+const FCS_LEN: usize = 2;
+const RING_META_LEN: usize = 2;
+const FRAME_META_LEN: usize = 3;
+const RX_FRAME_SLOT_LEN: usize = FRAME_META_LEN + MAX_FRAME_LEN;
+
+// Frame control field bits.
+const FRAME_TYPE_DATA: u16 = 0b001;
+const ACK_REQUEST: u16 = 1 << 5;
+const PAN_ID_COMPRESSION: u16 = 1 << 6;
+const FRAME_VERSION_2006: u16 = 1 << 12;
+const SHORT_ADDRESS_MODE: u16 = 0b10;
+const LONG_ADDRESS_MODE: u16 = 0b11;
+
+const DRIVER_NUM: u32 = 0x30001;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const IS_ON: u32 = 1;
+const SET_SHORT_ADDRESS: u32 = 2;
+const SET_LONG_ADDRESS: u32 = 3;
+const SET_PAN: u32 = 4;
+const SET_CHANNEL: u32 = 5;
+const SET_TX_POWER: u32 = 6;
+const COMMIT_CONFIG: u32 = 7;
+const GET_SHORT_ADDRESS: u32 = 8;
+const GET_LONG_ADDRESS: u32 = 9;
+const GET_PAN: u32 = 10;
+const GET_CHANNEL: u32 = 11;
+const GET_TX_POWER: u32 = 12;
+const TRANSMIT: u32 = 26;
+const TURN_ON: u32 = 27;
+const TURN_OFF: u32 = 28;
+
+const SUBSCRIBE_FRAME_RECEIVED: u32 = 0;
+const SUBSCRIBE_FRAME_TRANSMITTED: u32 = 1;
+const ALLOW_WRITE: u32 = 0;
+const ALLOW_READ: u32 = 0;
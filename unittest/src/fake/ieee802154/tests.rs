@@ -0,0 +1,135 @@
+use crate::fake;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+use fake::ieee802154::*;
+use libtock_platform::{
+    share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe, YieldNoWaitReturn,
+};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let radio = Ieee802154::new();
+    assert!(radio.command(DRIVER_CHECK, 1, 2).is_success());
+    assert!(radio
+        .allow_readonly(ALLOW_WRITE, RoAllowBuffer::default())
+        .is_ok());
+    assert!(radio.allow_readonly(1, RoAllowBuffer::default()).is_err());
+    assert!(radio
+        .allow_readwrite(ALLOW_READ, RwAllowBuffer::default())
+        .is_ok());
+    assert!(radio.allow_readwrite(1, RwAllowBuffer::default()).is_err());
+
+    assert_eq!(
+        radio.command(IS_ON, 0, 0).get_failure(),
+        Some(ErrorCode::Off)
+    );
+    assert_eq!(
+        radio.command(TRANSMIT, 1, 0).get_failure(),
+        Some(ErrorCode::Off)
+    );
+    assert!(radio.command(TURN_ON, 0, 0).is_success());
+    assert!(radio.command(IS_ON, 0, 0).is_success());
+
+    assert!(radio.command(SET_LONG_ADDRESS, 0x5678, 0x1234).is_success());
+    assert_eq!(
+        radio.command(GET_LONG_ADDRESS, 0, 0).get_success_u64(),
+        Some(0x1234_0000_5678)
+    );
+    assert!(radio.command(SET_TX_POWER, -4i32 as u32, 0).is_success());
+    assert_eq!(radio.tx_power(), -4);
+    assert_eq!(
+        radio.command(SET_CHANNEL, 27, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert!(radio.command(COMMIT_CONFIG, 0, 0).is_success());
+    assert_eq!(radio.commits(), 1);
+    assert_eq!(
+        radio.command(13, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+#[test]
+fn mac_header_layout() {
+    assert_eq!(
+        mac_header(7, 0xabcd, 0x0002, &[0x01, 0x00]),
+        [0x61, 0x98, 7, 0xcd, 0xab, 0x02, 0x00, 0x01, 0x00]
+    );
+    assert_eq!(
+        mac_header(0, 0xabcd, BROADCAST, &[1, 2, 3, 4, 5, 6, 7, 8]),
+        [0x41, 0xd8, 0, 0xcd, 0xab, 0xff, 0xff, 1, 2, 3, 4, 5, 6, 7, 8]
+    );
+}
+
+// Integration test that verifies Ieee802154 works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let radio = Ieee802154::new();
+    kernel.add_driver(&radio);
+    let peer = Ieee802154Peer::new(BROADCAST, 2, 26);
+    radio.connect(&peer);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, SET_SHORT_ADDRESS, 1, 0).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, TURN_ON, 0, 0).is_success());
+
+    let received = core::cell::Cell::new(Option::<(u32, u32, u32)>::None);
+    let transmitted = core::cell::Cell::new(Option::<(u32, u32)>::None);
+    let mut buffer = [0; RING_META_LEN + 2 * RX_FRAME_SLOT_LEN];
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_WRITE>,
+            AllowRw<_, DRIVER_NUM, ALLOW_READ>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_FRAME_RECEIVED>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_FRAME_TRANSMITTED>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_ro, allow_rw, subscribe_received, subscribe_transmitted) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_WRITE>(allow_ro, b"ping")
+            .unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_READ>(allow_rw, &mut buffer)
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_FRAME_RECEIVED>(
+            subscribe_received,
+            &received,
+        )
+        .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_FRAME_TRANSMITTED>(
+            subscribe_transmitted,
+            &transmitted,
+        )
+        .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, TRANSMIT, 2, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(transmitted.get(), Some((0, 1)));
+        assert_eq!(
+            peer.take_received(),
+            [PeerFrame {
+                header: mac_header(0, BROADCAST, 2, &[1, 0]),
+                payload: b"ping".to_vec(),
+            }]
+        );
+
+        // The ring buffer holds one frame, so the second one stays queued.
+        assert!(radio.send_from(&peer, 1, b"pong"));
+        assert!(radio.send_from(&peer, BROADCAST, b"pong"));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(received.get(), Some((0xffff_ffff, 1, 2)));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        assert!(!radio.send_from(&peer, 3, b"pong"));
+        assert_eq!(radio.pending.borrow().len(), 1);
+    });
+    assert_eq!(&buffer[..RING_META_LEN], [0, 1]);
+    let slot = &buffer[RING_META_LEN..][..RX_FRAME_SLOT_LEN];
+    assert_eq!(slot[..FRAME_META_LEN], [9, 4, 0]);
+    assert_eq!(
+        slot[FRAME_META_LEN..][..13],
+        *b"\x61\x98\x00\xff\xff\x01\x00\x02\x00pong"
+    );
+}
@@ -0,0 +1,226 @@
+//! Fake implementation of the IPC API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/10000_ipc.md
+//!
+//! `Ipc` is the app's view of the IPC hub. Tests add `IpcProcess`es to it,
+//! which stand in for other processes: the app discovers them by package
+//! name and notifies them, and they notify the app, as a service or as a
+//! client. As in the kernel, the buffer passed with a notification is the one
+//! the client shared with the service. Host pointers do not fit in upcall
+//! arguments, so the fake passes the buffer's length with a null address;
+//! tests access shared buffers through `Ipc` and `IpcProcess` instead.
+
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::upcall;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+
+pub struct Ipc {
+    processes: RefCell<Vec<Rc<IpcProcess>>>,
+    next_id: Cell<u32>,
+
+    search: RefCell<RoAllowBuffer>,
+    // Buffers shared with services, by process ID.
+    shared: RefCell<HashMap<u32, RwAllowBuffer>>,
+}
+
+/// A notification the app sent to an `IpcProcess`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum IpcNotification {
+    /// The app notified the process's service, as a client.
+    Service,
+    /// The app notified the process, as a client of the app's service.
+    Client,
+}
+
+impl Ipc {
+    pub fn new() -> Rc<Ipc> {
+        Rc::new(Ipc {
+            processes: Default::default(),
+            next_id: Cell::new(1),
+            search: Default::default(),
+            shared: Default::default(),
+        })
+    }
+
+    /// Adds a process with package name `name`, with the next free process
+    /// ID.
+    pub fn add_process(&self, name: &str) -> Rc<IpcProcess> {
+        let id = self.next_id.get();
+        assert!(id <= MAX_PROCESSES, "Too many IPC processes");
+        self.next_id.set(id + 1);
+        let process = Rc::new(IpcProcess {
+            id,
+            name: name.into(),
+            buffer: Default::default(),
+            notifications: Default::default(),
+        });
+        self.processes.borrow_mut().push(process.clone());
+        process
+    }
+
+    /// Returns the contents of the buffer the app shared with `service`.
+    pub fn shared_with(&self, service: &IpcProcess) -> Vec<u8> {
+        match self.shared.borrow().get(&service.id) {
+            Some(buffer) => buffer.to_vec(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Writes `bytes` at the start of the buffer the app shared with
+    /// `service`, as the service would.
+    pub fn write_shared_with(&self, service: &IpcProcess, bytes: &[u8]) {
+        let mut shared = self.shared.borrow_mut();
+        let buffer = shared.get_mut(&service.id).expect("No buffer shared");
+        buffer[..bytes.len()].copy_from_slice(bytes);
+    }
+
+    /// Has `client` notify the app's service, passing the buffer the client
+    /// shared with the app.
+    pub fn notify_service_from(&self, client: &IpcProcess) {
+        let len = client.buffer.borrow().len() as u32;
+        upcall::schedule(DRIVER_NUM, SERVICE_UPCALL, (client.id, len, 0))
+            .expect("Unable to schedule upcall {}");
+    }
+
+    /// Has `service` notify the app as its client, passing the buffer the app
+    /// shared with the service.
+    pub fn notify_client_from(&self, service: &IpcProcess) {
+        let len = self
+            .shared
+            .borrow()
+            .get(&service.id)
+            .map_or(0, |buffer| buffer.len() as u32);
+        upcall::schedule(DRIVER_NUM, service.id, (service.id, len, 0))
+            .expect("Unable to schedule upcall {}");
+    }
+
+    fn process(&self, id: u32) -> Option<Rc<IpcProcess>> {
+        self.processes
+            .borrow()
+            .iter()
+            .find(|process| process.id == id)
+            .cloned()
+    }
+}
+
+/// A process the app communicates with.
+pub struct IpcProcess {
+    id: u32,
+    name: String,
+    // The buffer this process shares with the app's service.
+    buffer: RefCell<Vec<u8>>,
+    notifications: RefCell<Vec<IpcNotification>>,
+}
+
+impl IpcProcess {
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Sets the buffer this process shares with the app's service.
+    pub fn share(&self, buffer: &[u8]) {
+        self.buffer.replace(buffer.to_vec());
+    }
+
+    /// Returns the contents of the buffer this process shares with the app's
+    /// service.
+    pub fn buffer(&self) -> Vec<u8> {
+        self.buffer.borrow().clone()
+    }
+
+    /// Returns the notifications received since the last call, in order.
+    pub fn take_notifications(&self) -> Vec<IpcNotification> {
+        self.notifications.take()
+    }
+}
+
+impl crate::fake::SyscallDriver for Ipc {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        MAX_PROCESSES + 1
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_SEARCH {
+            Ok(self.search.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == 0 || buffer_num > MAX_PROCESSES {
+            return Err((buffer, ErrorCode::Invalid));
+        }
+        let previous = self.shared.borrow_mut().insert(buffer_num, buffer);
+        Ok(previous.unwrap_or_default())
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => {}
+            DISCOVER => {
+                let search = self.search.borrow();
+                return match self
+                    .processes
+                    .borrow()
+                    .iter()
+                    .find(|process| process.name.as_bytes() == &search[..])
+                {
+                    Some(process) => crate::command_return::success_u32(process.id),
+                    None => crate::command_return::failure(ErrorCode::Invalid),
+                };
+            }
+            NOTIFY_SERVICE | NOTIFY_CLIENT => {
+                let process = match self.process(argument0) {
+                    Some(process) => process,
+                    None => return crate::command_return::failure(ErrorCode::Invalid),
+                };
+                process.notifications.borrow_mut().push(match command_num {
+                    NOTIFY_SERVICE => IpcNotification::Service,
+                    _ => IpcNotification::Client,
+                });
+            }
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        }
+        crate::command_return::success()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const MAX_PROCESSES: u32 = 8;
+
+const DRIVER_NUM: u32 = 0x10000;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const DISCOVER: u32 = 1;
+const NOTIFY_SERVICE: u32 = 2;
+const NOTIFY_CLIENT: u32 = 3;
+
+const SERVICE_UPCALL: u32 = 0;
+const ALLOW_SEARCH: u32 = 0;
@@ -0,0 +1,108 @@
+use crate::fake;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+use fake::ipc::*;
+use libtock_platform::{
+    share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe, YieldNoWaitReturn,
+};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let ipc = Ipc::new();
+    let service = ipc.add_process("org.tock.service");
+    assert_eq!(service.id(), 1);
+    assert_eq!(service.name(), "org.tock.service");
+    assert_eq!(ipc.add_process("org.tock.other").id(), 2);
+    assert!(ipc.command(DRIVER_CHECK, 1, 2).is_success());
+    assert!(ipc
+        .allow_readonly(ALLOW_SEARCH, RoAllowBuffer::default())
+        .is_ok());
+    assert!(ipc.allow_readonly(1, RoAllowBuffer::default()).is_err());
+    assert!(ipc.allow_readwrite(0, RwAllowBuffer::default()).is_err());
+    assert!(ipc.allow_readwrite(1, RwAllowBuffer::default()).is_ok());
+    assert!(ipc
+        .allow_readwrite(MAX_PROCESSES + 1, RwAllowBuffer::default())
+        .is_err());
+
+    assert_eq!(
+        ipc.command(DISCOVER, 0, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert!(ipc.command(NOTIFY_SERVICE, 1, 0).is_success());
+    assert!(ipc.command(NOTIFY_CLIENT, 1, 0).is_success());
+    assert_eq!(
+        service.take_notifications(),
+        [IpcNotification::Service, IpcNotification::Client]
+    );
+    assert_eq!(
+        ipc.command(NOTIFY_SERVICE, 3, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        ipc.command(4, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies Ipc works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let ipc = Ipc::new();
+    kernel.add_driver(&ipc);
+    let service = ipc.add_process("org.tock.service");
+    let client = ipc.add_process("org.tock.client");
+    client.share(&[0; 4]);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+
+    let service_called = core::cell::Cell::new(Option::<(u32, u32, u32)>::None);
+    let client_called = core::cell::Cell::new(Option::<(u32, u32, u32)>::None);
+    let mut buffer = [1, 2, 3];
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_SEARCH>,
+            AllowRw<_, DRIVER_NUM, 1>,
+            Subscribe<_, DRIVER_NUM, SERVICE_UPCALL>,
+            Subscribe<_, DRIVER_NUM, 1>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_ro, allow_rw, subscribe_service, subscribe_client) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_SEARCH>(
+            allow_ro,
+            b"org.tock.service",
+        )
+        .unwrap();
+        assert_eq!(
+            fake::Syscalls::command(DRIVER_NUM, DISCOVER, 0, 0).get_success_u32(),
+            Some(1)
+        );
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, 1>(allow_rw, &mut buffer).unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SERVICE_UPCALL>(
+            subscribe_service,
+            &service_called,
+        )
+        .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 1>(
+            subscribe_client,
+            &client_called,
+        )
+        .unwrap();
+
+        assert_eq!(ipc.shared_with(&service), [1, 2, 3]);
+        ipc.write_shared_with(&service, &[7, 8]);
+        ipc.notify_client_from(&service);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(client_called.get(), Some((1, 3, 0)));
+
+        ipc.notify_service_from(&client);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(service_called.get(), Some((2, 4, 0)));
+    });
+    assert_eq!(buffer, [7, 8, 3]);
+    assert_eq!(ipc.shared_with(&service), []);
+}
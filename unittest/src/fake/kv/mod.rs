@@ -0,0 +1,173 @@
+//! Fake implementation of the key-value storage API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/50003_key_value.md
+//!
+//! `KeyValue` keeps its entries in memory. Like a real store, it has a limited
+//! capacity: the total length of all keys and values may not exceed the
+//! capacity passed to `new`, and operations that would exceed it fail with
+//! `NoMem`. Like the kernel, it reports missing keys (and, for `add`, existing
+//! keys) as `NoSupport` in the completion upcall.
+
+use core::cell::RefCell;
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::collections::BTreeMap;
+
+use crate::upcall;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+
+pub struct KeyValue {
+    capacity: usize,
+    entries: RefCell<BTreeMap<Vec<u8>, Vec<u8>>>,
+
+    key: RefCell<RoAllowBuffer>,
+    input: RefCell<RoAllowBuffer>,
+    output: RefCell<RwAllowBuffer>,
+}
+
+impl KeyValue {
+    pub fn new(capacity: usize) -> std::rc::Rc<KeyValue> {
+        std::rc::Rc::new(KeyValue {
+            capacity,
+            entries: Default::default(),
+            key: Default::default(),
+            input: Default::default(),
+            output: Default::default(),
+        })
+    }
+
+    /// Returns the value stored under `key`.
+    pub fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.entries.borrow().get(key).cloned()
+    }
+
+    /// Stores `value` under `key`, bypassing the capacity check. Used to set
+    /// up the store's initial contents.
+    pub fn insert(&self, key: &[u8], value: &[u8]) {
+        self.entries
+            .borrow_mut()
+            .insert(Vec::from(key), Vec::from(value));
+    }
+
+    /// Returns the number of bytes used by keys and values.
+    pub fn used(&self) -> usize {
+        self.entries
+            .borrow()
+            .iter()
+            .map(|(key, value)| key.len() + value.len())
+            .sum()
+    }
+
+    // Performs an operation, returning the value length or the error to report
+    // in the completion upcall.
+    fn operation(&self, command_num: u32, key: &[u8], input: &[u8]) -> Result<u32, ErrorCode> {
+        let exists = self.entries.borrow().contains_key(key);
+        match command_num {
+            GET => {
+                let entries = self.entries.borrow();
+                let value = entries.get(key).ok_or(ErrorCode::NoSupport)?;
+                let mut output = self.output.borrow_mut();
+                if value.len() > output.len() {
+                    return Err(ErrorCode::Size);
+                }
+                output[..value.len()].copy_from_slice(value);
+                Ok(value.len() as u32)
+            }
+            SET => self.store(key, input),
+            ADD if exists => Err(ErrorCode::NoSupport),
+            ADD => self.store(key, input),
+            UPDATE if !exists => Err(ErrorCode::NoSupport),
+            UPDATE => self.store(key, input),
+            DELETE => {
+                self.entries
+                    .borrow_mut()
+                    .remove(key)
+                    .ok_or(ErrorCode::NoSupport)?;
+                Ok(0)
+            }
+            _ => Err(ErrorCode::NoSupport),
+        }
+    }
+
+    fn store(&self, key: &[u8], value: &[u8]) -> Result<u32, ErrorCode> {
+        let replaced = self.get(key).map_or(0, |old| key.len() + old.len());
+        if self.used() - replaced + key.len() + value.len() > self.capacity {
+            return Err(ErrorCode::NoMem);
+        }
+        self.insert(key, value);
+        Ok(0)
+    }
+}
+
+impl crate::fake::SyscallDriver for KeyValue {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        match buffer_num {
+            ALLOW_KEY => Ok(self.key.replace(buffer)),
+            ALLOW_INPUT => Ok(self.input.replace(buffer)),
+            _ => Err((buffer, ErrorCode::Invalid)),
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_OUTPUT {
+            Ok(self.output.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_num: u32, _argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => return crate::command_return::success(),
+            GET | SET | DELETE | ADD | UPDATE => {}
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        }
+        let key = self.key.borrow();
+        if key.is_empty() {
+            return crate::command_return::failure(ErrorCode::Invalid);
+        }
+        let args = match self.operation(command_num, &key, &self.input.borrow()) {
+            Ok(len) => (0, len, 0),
+            Err(error) => (error as u32, 0, 0),
+        };
+        upcall::schedule(DRIVER_NUM, SUBSCRIBE_COMPLETE, args)
+            .expect("Unable to schedule upcall {}");
+        crate::command_return::success()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const DRIVER_NUM: u32 = 0x50003;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const GET: u32 = 1;
+const SET: u32 = 2;
+const DELETE: u32 = 3;
+const ADD: u32 = 4;
+const UPDATE: u32 = 5;
+
+const SUBSCRIBE_COMPLETE: u32 = 0;
+const ALLOW_KEY: u32 = 0;
+const ALLOW_INPUT: u32 = 1;
+const ALLOW_OUTPUT: u32 = 0;
@@ -0,0 +1,87 @@
+use crate::fake;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+use fake::kv::*;
+use libtock_platform::{share, AllowRo, DefaultConfig, ErrorCode, Subscribe, YieldNoWaitReturn};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let kv = KeyValue::new(16);
+    assert!(kv.command(DRIVER_CHECK, 1, 2).is_success());
+    assert!(kv
+        .allow_readonly(ALLOW_KEY, RoAllowBuffer::default())
+        .is_ok());
+    assert!(kv
+        .allow_readonly(ALLOW_INPUT, RoAllowBuffer::default())
+        .is_ok());
+    assert!(kv.allow_readonly(2, RoAllowBuffer::default()).is_err());
+    assert!(kv
+        .allow_readwrite(ALLOW_OUTPUT, RwAllowBuffer::default())
+        .is_ok());
+    assert!(kv.allow_readwrite(1, RwAllowBuffer::default()).is_err());
+
+    // No key has been shared.
+    assert_eq!(
+        kv.command(GET, 0, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        kv.command(6, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+#[test]
+fn capacity() {
+    let kv = KeyValue::new(9);
+    assert_eq!(kv.store(b"ab", b"cd"), Ok(0));
+    assert_eq!(kv.store(b"ef", b"ghi"), Ok(0));
+    assert_eq!(kv.used(), 9);
+    // Replacing a value only needs room for the difference.
+    assert_eq!(kv.store(b"ab", b"c"), Ok(0));
+    assert_eq!(kv.store(b"ij", b""), Err(ErrorCode::NoMem));
+    assert_eq!(kv.get(b"ab"), Some(b"c".to_vec()));
+    assert_eq!(kv.get(b"ij"), None);
+}
+
+// Integration test that verifies KeyValue works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let kv = KeyValue::new(16);
+    kernel.add_driver(&kv);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+
+    let called = core::cell::Cell::new(Option::<(u32, u32)>::None);
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_KEY>,
+            AllowRo<_, DRIVER_NUM, ALLOW_INPUT>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_COMPLETE>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_key, allow_input, subscribe) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_KEY>(allow_key, b"key")
+            .unwrap();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_INPUT>(allow_input, b"value")
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_COMPLETE>(
+            subscribe, &called,
+        )
+        .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, ADD, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((0, 0)));
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, ADD, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((ErrorCode::NoSupport as u32, 0)));
+    });
+    assert_eq!(kv.get(b"key"), Some(b"value".to_vec()));
+}
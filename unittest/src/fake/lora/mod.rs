@@ -0,0 +1,593 @@
+//! Fake implementation of the LoRa PHY passthrough drivers. The kernel passes
+//! the SPI bus an SX1262 radio is on through as driver 0x30003, which has the
+//! SPI controller API, and the radio's control lines as driver 0x30004, which
+//! has the GPIO API. Both are documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/20001_spi_controller.md
+//! https://github.com/tock/tock/blob/master/doc/syscalls/00004_gpio.md
+//!
+//! `Lora` simulates the radio behind them; tests add both `Lora::spi` and
+//! `Lora::gpio` to the kernel. The radio understands the SX1262 commands the
+//! driver uses. Like the real radio, it raises BUSY after each command, until
+//! the app next reads BUSY, and panics if it is sent a command while busy or
+//! held in reset. Its interrupts raise DIO1.
+//!
+//! The radio records the packets it transmits, which tests retrieve with
+//! `take_transmitted`, and receives packets tests send with `inject` once it
+//! listens. In loopback mode, each packet it transmits is also received back,
+//! with `LOOPBACK_RSSI` and `LOOPBACK_SNR`.
+
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::collections::{HashMap, VecDeque};
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+use crate::fake::{GpioMode, InterruptEdge, PullMode};
+use crate::upcall;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+
+pub struct Lora {
+    settings: Cell<Settings>,
+    registers: RefCell<HashMap<u16, u8>>,
+    buffer: RefCell<[u8; 256]>,
+    irq: Cell<u16>,
+    busy: Cell<bool>,
+    receiving: Cell<bool>,
+    resets: Cell<u32>,
+    // The last packet received, as reported by GetRxBufferStatus and
+    // GetPacketStatus.
+    rx_len: Cell<u8>,
+    packet_status: Cell<(u8, u8)>,
+
+    loopback: Cell<bool>,
+    transmitted: RefCell<Vec<Vec<u8>>>,
+    pending: RefCell<VecDeque<Received>>,
+
+    pins: [Cell<Pin>; NUM_PINS],
+}
+
+impl Lora {
+    pub const LOOPBACK_RSSI: i16 = -40;
+    pub const LOOPBACK_SNR: i8 = 10;
+
+    pub fn new() -> Rc<Lora> {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const INPUT: Cell<Pin> = Cell::new(Pin {
+            mode: GpioMode::Input(PullMode::PullNone),
+            value: false,
+            interrupt: None,
+        });
+        let lora = Lora {
+            settings: Default::default(),
+            registers: Default::default(),
+            buffer: RefCell::new([0; 256]),
+            irq: Cell::new(0),
+            busy: Cell::new(false),
+            receiving: Cell::new(false),
+            resets: Cell::new(0),
+            rx_len: Cell::new(0),
+            packet_status: Cell::new((0, 0)),
+            loopback: Cell::new(false),
+            transmitted: Default::default(),
+            pending: Default::default(),
+            pins: [INPUT; NUM_PINS],
+        };
+        // The reset line is pulled up, so the radio starts out running.
+        lora.pins[RESET as usize].set(Pin {
+            value: true,
+            ..lora.pins[RESET as usize].get()
+        });
+        Rc::new(lora)
+    }
+
+    /// Returns the SPI passthrough driver, to add to the kernel.
+    pub fn spi(self: &Rc<Self>) -> Rc<LoraSpi> {
+        Rc::new(LoraSpi {
+            radio: self.clone(),
+            write_buffer: Default::default(),
+            read_buffer: Default::default(),
+        })
+    }
+
+    /// Returns the GPIO passthrough driver, to add to the kernel.
+    pub fn gpio(self: &Rc<Self>) -> Rc<LoraGpio> {
+        Rc::new(LoraGpio {
+            radio: self.clone(),
+        })
+    }
+
+    /// Returns how many times the radio has been reset through its reset
+    /// line.
+    pub fn reset_count(&self) -> u32 {
+        self.resets.get()
+    }
+
+    /// Returns `true` once the radio has been switched to LoRa packets.
+    pub fn is_lora(&self) -> bool {
+        self.settings.get().packet_type == PACKET_TYPE_LORA
+    }
+
+    /// Returns the value last written to `address` since the radio was reset.
+    pub fn register(&self, address: u16) -> Option<u8> {
+        self.registers.borrow().get(&address).copied()
+    }
+
+    /// Returns the carrier frequency, rounded to the nearest hertz.
+    pub fn frequency_hz(&self) -> u32 {
+        let steps = self.settings.get().frequency_steps as u64;
+        ((steps * XTAL_HZ + (1 << 24)) >> 25) as u32
+    }
+
+    pub fn spreading_factor(&self) -> u8 {
+        self.settings.get().spreading_factor
+    }
+
+    /// Returns the bandwidth, in kHz, or 0 if it is not one the fake knows.
+    pub fn bandwidth_khz(&self) -> u32 {
+        match self.settings.get().bandwidth {
+            BANDWIDTH_125_KHZ => 125,
+            BANDWIDTH_250_KHZ => 250,
+            BANDWIDTH_500_KHZ => 500,
+            _ => 0,
+        }
+    }
+
+    pub fn low_data_rate_optimize(&self) -> bool {
+        self.settings.get().low_data_rate_optimize
+    }
+
+    /// Returns `true` while the radio is listening for a packet.
+    pub fn is_receiving(&self) -> bool {
+        self.receiving.get()
+    }
+
+    /// Sets whether the packets the radio transmits are received back.
+    pub fn set_loopback(&self, loopback: bool) {
+        self.loopback.set(loopback);
+    }
+
+    /// Returns the packets transmitted since the last call, in order.
+    pub fn take_transmitted(&self) -> Vec<Vec<u8>> {
+        self.transmitted.take()
+    }
+
+    /// Sends `payload` to the radio, received with signal strength `rssi`
+    /// (in dBm) and signal-to-noise ratio `snr` (in dB). The packet waits
+    /// until the radio listens.
+    pub fn inject(&self, payload: &[u8], rssi: i16, snr: i8) {
+        self.pending.borrow_mut().push_back(Received {
+            payload: payload.to_vec(),
+            rssi,
+            snr,
+            crc_ok: true,
+        });
+        self.deliver();
+    }
+
+    /// Like `inject`, but the packet fails its CRC check.
+    pub fn inject_corrupted(&self, payload: &[u8]) {
+        self.pending.borrow_mut().push_back(Received {
+            payload: payload.to_vec(),
+            rssi: Self::LOOPBACK_RSSI,
+            snr: Self::LOOPBACK_SNR,
+            crc_ok: false,
+        });
+        self.deliver();
+    }
+
+    // Performs the command in `written`, returning the bytes clocked back.
+    fn transfer(&self, written: &[u8]) -> Vec<u8> {
+        assert!(
+            self.pins[RESET as usize].get().value,
+            "SPI transfer while the radio is held in reset"
+        );
+        assert!(!self.busy.get(), "SPI transfer while the radio is busy");
+        self.busy.set(true);
+
+        let mut response = vec![0; written.len()];
+        let (&opcode, params) = match written.split_first() {
+            Some(command) => command,
+            None => return response,
+        };
+        let mut settings = self.settings.get();
+        match (opcode, params) {
+            (SET_STANDBY, [_]) => self.receiving.set(false),
+            (SET_PACKET_TYPE, &[packet_type]) => settings.packet_type = packet_type,
+            (SET_RF_FREQUENCY, &[b3, b2, b1, b0]) => {
+                settings.frequency_steps = u32::from_be_bytes([b3, b2, b1, b0])
+            }
+            (SET_MODULATION_PARAMS, &[spreading_factor, bandwidth, _, ldro, ..]) => {
+                settings.spreading_factor = spreading_factor;
+                settings.bandwidth = bandwidth;
+                settings.low_data_rate_optimize = ldro != 0;
+            }
+            (SET_PACKET_PARAMS, &[_, _, _, payload_len, ..]) => settings.payload_len = payload_len,
+            (SET_BUFFER_BASE_ADDRESS, &[tx_base, rx_base]) => {
+                settings.tx_base = tx_base;
+                settings.rx_base = rx_base;
+            }
+            (SET_DIO_IRQ_PARAMS, &[enable_hi, enable_lo, dio1_hi, dio1_lo, ..]) => {
+                settings.irq_enable = u16::from_be_bytes([enable_hi, enable_lo]);
+                settings.dio1_mask = u16::from_be_bytes([dio1_hi, dio1_lo]);
+            }
+            (WRITE_REGISTER, &[address_hi, address_lo, ref data @ ..]) => {
+                let address = u16::from_be_bytes([address_hi, address_lo]);
+                let mut registers = self.registers.borrow_mut();
+                for (i, &byte) in data.iter().enumerate() {
+                    registers.insert(address.wrapping_add(i as u16), byte);
+                }
+            }
+            (WRITE_BUFFER, &[offset, ref data @ ..]) => {
+                let mut buffer = self.buffer.borrow_mut();
+                for (i, &byte) in data.iter().enumerate() {
+                    buffer[offset.wrapping_add(i as u8) as usize] = byte;
+                }
+            }
+            (READ_BUFFER, &[offset, _, ..]) => {
+                let buffer = self.buffer.borrow();
+                for (i, byte) in response[3..].iter_mut().enumerate() {
+                    *byte = buffer[offset.wrapping_add(i as u8) as usize];
+                }
+            }
+            (GET_IRQ_STATUS, [_, _, _]) => {
+                response[2..].copy_from_slice(&self.irq.get().to_be_bytes())
+            }
+            (CLEAR_IRQ_STATUS, &[mask_hi, mask_lo]) => {
+                self.irq
+                    .set(self.irq.get() & !u16::from_be_bytes([mask_hi, mask_lo]));
+                self.set_input(DIO1, self.irq.get() & settings.dio1_mask != 0);
+            }
+            (GET_RX_BUFFER_STATUS, [_, _, _]) => {
+                response[2] = self.rx_len.get();
+                response[3] = settings.rx_base;
+            }
+            (GET_PACKET_STATUS, [_, _, ..]) => {
+                let (rssi, snr) = self.packet_status.get();
+                response[2] = rssi;
+                response[3] = snr;
+            }
+            (SET_TX, [_, _, _]) => {
+                self.receiving.set(false);
+                let buffer = self.buffer.borrow();
+                let payload: Vec<u8> = (0..settings.payload_len)
+                    .map(|i| buffer[settings.tx_base.wrapping_add(i) as usize])
+                    .collect();
+                drop(buffer);
+                self.transmitted.borrow_mut().push(payload.clone());
+                if self.loopback.get() {
+                    self.pending.borrow_mut().push_back(Received {
+                        payload,
+                        rssi: Self::LOOPBACK_RSSI,
+                        snr: Self::LOOPBACK_SNR,
+                        crc_ok: true,
+                    });
+                }
+                self.raise_irq(IRQ_TX_DONE);
+            }
+            (SET_RX, [_, _, _]) => {
+                self.receiving.set(true);
+                self.deliver();
+            }
+            _ => panic!("Unsupported SX1262 command {:02x?}", written),
+        }
+        self.settings.set(settings);
+        response
+    }
+
+    // Receives the next pending packet, if the radio is listening.
+    fn deliver(&self) {
+        if !self.receiving.get() {
+            return;
+        }
+        let received = match self.pending.borrow_mut().pop_front() {
+            Some(received) => received,
+            None => return,
+        };
+        let settings = self.settings.get();
+        let mut buffer = self.buffer.borrow_mut();
+        for (i, &byte) in received.payload.iter().enumerate() {
+            buffer[settings.rx_base.wrapping_add(i as u8) as usize] = byte;
+        }
+        drop(buffer);
+        self.rx_len.set(received.payload.len() as u8);
+        self.packet_status.set((
+            (-2 * received.rssi).clamp(0, 255) as u8,
+            (4 * received.snr as i16).clamp(-128, 127) as i8 as u8,
+        ));
+        self.receiving.set(false);
+        self.raise_irq(match received.crc_ok {
+            true => IRQ_RX_DONE,
+            false => IRQ_RX_DONE | IRQ_CRC_ERR,
+        });
+    }
+
+    // Sets the interrupts in `irq` that are enabled, raising DIO1 if one of
+    // them is routed to it.
+    fn raise_irq(&self, irq: u16) {
+        let settings = self.settings.get();
+        self.irq.set(self.irq.get() | (irq & settings.irq_enable));
+        self.set_input(DIO1, self.irq.get() & settings.dio1_mask != 0);
+    }
+
+    fn reset(&self) {
+        self.resets.set(self.resets.get() + 1);
+        self.settings.set(Default::default());
+        self.registers.borrow_mut().clear();
+        self.irq.set(0);
+        self.receiving.set(false);
+        // The radio is busy while it starts up.
+        self.busy.set(true);
+    }
+
+    // Drives one of the radio's outputs, scheduling an upcall if the app is
+    // waiting for that edge.
+    fn set_input(&self, pin: u32, value: bool) {
+        let state = self.pins[pin as usize].get();
+        self.pins[pin as usize].set(Pin { value, ..state });
+        let edge = match (state.value, value) {
+            (false, true) => InterruptEdge::Rising,
+            (true, false) => InterruptEdge::Falling,
+            _ => return,
+        };
+        if state.interrupt == Some(edge) || state.interrupt == Some(InterruptEdge::Either) {
+            upcall::schedule(GPIO_DRIVER_NUM, SUBSCRIBE_INTERRUPT, (pin, value as u32, 0))
+                .expect("Unable to schedule upcall {}");
+        }
+    }
+
+    fn gpio_command(&self, command_num: u32, pin: u32, argument1: u32) -> CommandReturn {
+        if command_num == COUNT {
+            return crate::command_return::success_u32(NUM_PINS as u32);
+        }
+        let state = match self.pins.get(pin as usize) {
+            Some(state) => state.get(),
+            None => return crate::command_return::failure(ErrorCode::Invalid),
+        };
+        let set_state = |state| self.pins[pin as usize].set(state);
+        match command_num {
+            ENABLE_OUTPUT => set_state(Pin {
+                mode: GpioMode::Output,
+                ..state
+            }),
+            SET | CLEAR | TOGGLE => {
+                if state.mode != GpioMode::Output {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                let value = match command_num {
+                    SET => true,
+                    CLEAR => false,
+                    _ => !state.value,
+                };
+                set_state(Pin { value, ..state });
+                if pin == RESET && !state.value && value {
+                    self.reset();
+                }
+            }
+            ENABLE_INPUT => match PullMode::try_from(argument1) {
+                Ok(pull) => set_state(Pin {
+                    mode: GpioMode::Input(pull),
+                    ..state
+                }),
+                Err(error) => return crate::command_return::failure(error),
+            },
+            READ_INPUT => {
+                if !matches!(state.mode, GpioMode::Input(_)) {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                if pin == BUSY {
+                    return crate::command_return::success_u32(self.busy.replace(false) as u32);
+                }
+                return crate::command_return::success_u32(state.value as u32);
+            }
+            ENABLE_INTERRUPTS => match InterruptEdge::try_from(argument1) {
+                Ok(edge) => set_state(Pin {
+                    interrupt: Some(edge),
+                    ..state
+                }),
+                Err(error) => return crate::command_return::failure(error),
+            },
+            DISABLE_INTERRUPTS => set_state(Pin {
+                interrupt: None,
+                ..state
+            }),
+            DISABLE => set_state(Pin {
+                mode: GpioMode::Disable,
+                interrupt: None,
+                ..state
+            }),
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        }
+        crate::command_return::success()
+    }
+}
+
+/// The SPI passthrough, which carries commands to the radio.
+pub struct LoraSpi {
+    radio: Rc<Lora>,
+    write_buffer: RefCell<RoAllowBuffer>,
+    read_buffer: RefCell<RwAllowBuffer>,
+}
+
+impl LoraSpi {
+    fn read_write(&self, len: usize) -> CommandReturn {
+        let write_buffer = self.write_buffer.borrow();
+        let mut read_buffer = self.read_buffer.borrow_mut();
+        // A transfer without a read buffer discards the received bytes.
+        if len > write_buffer.len() || (!read_buffer.is_empty() && len > read_buffer.len()) {
+            return crate::command_return::failure(ErrorCode::Size);
+        }
+        let response = self.radio.transfer(&write_buffer[..len]);
+        if !read_buffer.is_empty() {
+            read_buffer[..len].copy_from_slice(&response);
+        }
+        upcall::schedule(SPI_DRIVER_NUM, SUBSCRIBE_COMPLETE, (len as u32, 0, 0))
+            .expect("Unable to schedule upcall {}");
+        crate::command_return::success()
+    }
+}
+
+impl crate::fake::SyscallDriver for LoraSpi {
+    fn id(&self) -> u32 {
+        SPI_DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_WRITE {
+            Ok(self.write_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_READ {
+            Ok(self.read_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => crate::command_return::success(),
+            READ_WRITE => self.read_write(argument0 as usize),
+            // The radio is the only peripheral on the bus.
+            SET_CHIP_SELECT if argument0 == 0 => crate::command_return::success(),
+            SET_CHIP_SELECT => crate::command_return::failure(ErrorCode::Invalid),
+            GET_CHIP_SELECT => crate::command_return::success_u32(0),
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+/// The GPIO passthrough, which controls the radio's reset line and reads its
+/// BUSY and DIO1 lines.
+pub struct LoraGpio {
+    radio: Rc<Lora>,
+}
+
+impl crate::fake::SyscallDriver for LoraGpio {
+    fn id(&self) -> u32 {
+        GPIO_DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        self.radio.gpio_command(command_num, argument0, argument1)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+// The radio's settings, which a reset restores to their defaults.
+#[derive(Copy, Clone, Default)]
+struct Settings {
+    packet_type: u8,
+    frequency_steps: u32,
+    spreading_factor: u8,
+    bandwidth: u8,
+    low_data_rate_optimize: bool,
+    payload_len: u8,
+    tx_base: u8,
+    rx_base: u8,
+    irq_enable: u16,
+    dio1_mask: u16,
+}
+
+#[derive(Copy, Clone)]
+struct Pin {
+    mode: GpioMode,
+    value: bool,
+    interrupt: Option<InterruptEdge>,
+}
+
+struct Received {
+    payload: Vec<u8>,
+    rssi: i16,
+    snr: i8,
+    crc_ok: bool,
+}
+
+const XTAL_HZ: u64 = 32_000_000;
+const PACKET_TYPE_LORA: u8 = 0x01;
+const BANDWIDTH_125_KHZ: u8 = 0x04;
+const BANDWIDTH_250_KHZ: u8 = 0x05;
+const BANDWIDTH_500_KHZ: u8 = 0x06;
+
+// SX1262 opcodes
+const SET_STANDBY: u8 = 0x80;
+const SET_PACKET_TYPE: u8 = 0x8a;
+const SET_RF_FREQUENCY: u8 = 0x86;
+const SET_MODULATION_PARAMS: u8 = 0x8b;
+const SET_PACKET_PARAMS: u8 = 0x8c;
+const SET_BUFFER_BASE_ADDRESS: u8 = 0x8f;
+const SET_DIO_IRQ_PARAMS: u8 = 0x08;
+const SET_TX: u8 = 0x83;
+const SET_RX: u8 = 0x82;
+const WRITE_REGISTER: u8 = 0x0d;
+const WRITE_BUFFER: u8 = 0x0e;
+const READ_BUFFER: u8 = 0x1e;
+const GET_IRQ_STATUS: u8 = 0x12;
+const CLEAR_IRQ_STATUS: u8 = 0x02;
+const GET_RX_BUFFER_STATUS: u8 = 0x13;
+const GET_PACKET_STATUS: u8 = 0x14;
+
+// SX1262 interrupt flags
+const IRQ_TX_DONE: u16 = 1 << 0;
+const IRQ_RX_DONE: u16 = 1 << 1;
+const IRQ_CRC_ERR: u16 = 1 << 6;
+
+const SPI_DRIVER_NUM: u32 = 0x30003;
+
+// SPI command numbers
+const DRIVER_CHECK: u32 = 0;
+const READ_WRITE: u32 = 2;
+const SET_CHIP_SELECT: u32 = 3;
+const GET_CHIP_SELECT: u32 = 4;
+
+const SUBSCRIBE_COMPLETE: u32 = 0;
+const ALLOW_WRITE: u32 = 0;
+const ALLOW_READ: u32 = 0;
+
+const GPIO_DRIVER_NUM: u32 = 0x30004;
+
+// The radio's control lines. Line 0 is its chip select, which the SPI
+// passthrough drives.
+const NUM_PINS: usize = 5;
+const BUSY: u32 = 1;
+const DIO1: u32 = 2;
+const RESET: u32 = 4;
+
+// GPIO command numbers
+const COUNT: u32 = 0;
+const ENABLE_OUTPUT: u32 = 1;
+const SET: u32 = 2;
+const CLEAR: u32 = 3;
+const TOGGLE: u32 = 4;
+const ENABLE_INPUT: u32 = 5;
+const READ_INPUT: u32 = 6;
+const ENABLE_INTERRUPTS: u32 = 7;
+const DISABLE_INTERRUPTS: u32 = 8;
+const DISABLE: u32 = 9;
+
+const SUBSCRIBE_INTERRUPT: u32 = 0;
@@ -0,0 +1,132 @@
+use crate::fake;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+use fake::lora::*;
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let kernel = fake::Kernel::new();
+    let lora = Lora::new();
+    let spi = lora.spi();
+    let gpio = lora.gpio();
+    kernel.add_driver(&spi);
+    kernel.add_driver(&gpio);
+
+    assert!(spi.command(DRIVER_CHECK, 1, 2).is_success());
+    assert!(spi
+        .allow_readonly(ALLOW_WRITE, RoAllowBuffer::default())
+        .is_ok());
+    assert!(spi.allow_readonly(1, RoAllowBuffer::default()).is_err());
+    assert!(spi
+        .allow_readwrite(ALLOW_READ, RwAllowBuffer::default())
+        .is_ok());
+    assert!(spi.allow_readwrite(1, RwAllowBuffer::default()).is_err());
+    assert!(spi.command(SET_CHIP_SELECT, 0, 0).is_success());
+    assert_eq!(
+        spi.command(SET_CHIP_SELECT, 1, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        spi.command(GET_CHIP_SELECT, 0, 0).get_success_u32(),
+        Some(0)
+    );
+    assert_eq!(
+        spi.command(READ_WRITE, 1, 0).get_failure(),
+        Some(ErrorCode::Size)
+    );
+    assert_eq!(
+        spi.command(5, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+
+    assert_eq!(gpio.command(COUNT, 0, 0).get_success_u32(), Some(5));
+    assert_eq!(
+        gpio.command(SET, RESET, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert!(gpio.command(ENABLE_OUTPUT, RESET, 0).is_success());
+    assert!(gpio.command(CLEAR, RESET, 0).is_success());
+    assert_eq!(lora.reset_count(), 0);
+    assert!(gpio.command(SET, RESET, 0).is_success());
+    assert_eq!(lora.reset_count(), 1);
+    // The radio is busy while it starts up, until the app sees it is.
+    assert_eq!(gpio.command(READ_INPUT, BUSY, 0).get_success_u32(), Some(1));
+    assert_eq!(gpio.command(READ_INPUT, BUSY, 0).get_success_u32(), Some(0));
+    assert_eq!(gpio.command(READ_INPUT, DIO1, 0).get_success_u32(), Some(0));
+    assert_eq!(
+        gpio.command(READ_INPUT, RESET, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert!(gpio.command(ENABLE_INTERRUPTS, DIO1, 1).is_success());
+    assert_eq!(
+        gpio.command(ENABLE_INTERRUPTS, DIO1, 3).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert!(gpio.command(DISABLE_INTERRUPTS, DIO1, 0).is_success());
+    assert_eq!(
+        gpio.command(READ_INPUT, 5, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        gpio.command(10, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies Lora works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let lora = Lora::new();
+    kernel.add_driver(&lora.spi());
+    kernel.add_driver(&lora.gpio());
+    assert!(fake::Syscalls::command(SPI_DRIVER_NUM, DRIVER_CHECK, 0, 0).is_success());
+
+    // Sets 868 MHz, then reads back the interrupt status.
+    let transfer = |write: &[u8], read: &mut [u8]| {
+        let called = core::cell::Cell::new(Option::<(u32, u32)>::None);
+        share::scope::<
+            (
+                AllowRo<_, SPI_DRIVER_NUM, ALLOW_WRITE>,
+                AllowRw<_, SPI_DRIVER_NUM, ALLOW_READ>,
+                Subscribe<_, SPI_DRIVER_NUM, SUBSCRIBE_COMPLETE>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, allow_rw, subscribe) = handle.split();
+            fake::Syscalls::allow_ro::<DefaultConfig, SPI_DRIVER_NUM, ALLOW_WRITE>(allow_ro, write)
+                .unwrap();
+            fake::Syscalls::allow_rw::<DefaultConfig, SPI_DRIVER_NUM, ALLOW_READ>(allow_rw, read)
+                .unwrap();
+            fake::Syscalls::subscribe::<_, _, DefaultConfig, SPI_DRIVER_NUM, SUBSCRIBE_COMPLETE>(
+                subscribe, &called,
+            )
+            .unwrap();
+            assert!(
+                fake::Syscalls::command(SPI_DRIVER_NUM, READ_WRITE, write.len() as u32, 0)
+                    .is_success()
+            );
+            fake::Syscalls::yield_wait();
+            assert_eq!(called.get(), Some((write.len() as u32, 0)));
+        });
+    };
+    let mut read = [0; 5];
+    transfer(&[SET_RF_FREQUENCY, 0x36, 0x40, 0x00, 0x00], &mut read);
+    assert_eq!(lora.frequency_hz(), 868_000_000);
+    assert_eq!(
+        fake::Syscalls::command(GPIO_DRIVER_NUM, READ_INPUT, BUSY, 0).get_success_u32(),
+        Some(1)
+    );
+    assert_eq!(
+        fake::Syscalls::command(GPIO_DRIVER_NUM, READ_INPUT, BUSY, 0).get_success_u32(),
+        Some(0)
+    );
+    let mut read = [0xff; 4];
+    transfer(&[GET_IRQ_STATUS, 0, 0, 0], &mut read);
+    assert_eq!(read, [0; 4]);
+}
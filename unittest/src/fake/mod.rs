@@ -9,25 +9,79 @@
 //! `use libtock_unittest::fake` and refer to the type with the `fake::` prefix
 //! (e.g. `fake::Console`).
 
+mod aes;
 mod alarm;
+mod analog_comparator;
+mod app_flash;
+mod ble;
 mod buttons;
+mod buzzer;
+mod can;
 mod console;
+mod crc;
+mod dac;
+mod date_time;
 mod gpio;
+mod hmac;
+mod i2c_master;
+mod ieee802154;
+mod ipc;
 mod kernel;
+mod kv;
 mod leds;
+mod lora;
 mod low_level_debug;
+mod nonvolatile_storage;
+mod proximity;
+mod pwm;
+mod screen;
+pub mod serial;
+mod servo;
+mod sha;
+mod sound_pressure;
+mod spi_controller;
 mod syscall_driver;
 mod syscalls;
+mod text_screen;
+mod touch;
+mod udp;
 
+pub use self::aes::Aes;
+pub use self::crc::Crc;
+pub use self::hmac::Hmac;
 pub use alarm::Alarm;
+pub use analog_comparator::AnalogComparator;
+pub use app_flash::AppFlash;
+pub use ble::{Advertising, Ble};
 pub use buttons::Buttons;
+pub use buzzer::{Buzzer, Tone};
+pub use can::{Can, CanBus, CanFrame, CanId, CanMode, CanNode};
 pub use console::Console;
+pub use dac::Dac;
+pub use date_time::{Rtc, RtcDateTime};
 pub use gpio::{Gpio, GpioMode, InterruptEdge, PullMode};
+pub use i2c_master::I2cMaster;
+pub use ieee802154::{Ieee802154, Ieee802154Peer, PeerFrame};
+pub use ipc::{Ipc, IpcNotification, IpcProcess};
 pub use kernel::Kernel;
+pub use kv::KeyValue;
 pub use leds::Leds;
+pub use lora::{Lora, LoraGpio, LoraSpi};
 pub use low_level_debug::{LowLevelDebug, Message};
+pub use nonvolatile_storage::NonvolatileStorage;
+pub use proximity::Proximity;
+pub use pwm::{Pwm, PwmOutput};
+pub use screen::Screen;
+pub use serial::Serial;
+pub use servo::Servo;
+pub use sha::Sha;
+pub use sound_pressure::SoundPressure;
+pub use spi_controller::{SpiController, Transfer};
 pub use syscall_driver::SyscallDriver;
 pub use syscalls::Syscalls;
+pub use text_screen::TextScreen;
+pub use touch::{Gesture, Touch, TouchEvent, TouchStatus};
+pub use udp::{Datagram, Endpoint, Udp, UdpNetwork, UdpSocket};
 
 #[cfg(test)]
 mod kernel_tests;
@@ -0,0 +1,149 @@
+//! Fake implementation of the nonvolatile storage API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/50001_nonvolatile_storage.md
+//!
+//! `NonvolatileStorage` keeps the process' storage region in a `Vec<u8>`,
+//! which starts out erased (all `0xff`). It can simulate power loss in the
+//! middle of a write: after `lose_power_after(n)`, the next write only stores
+//! its first `n` bytes, and its completion upcall reports the shortened length.
+//! Tests can then check what the application finds in storage after a reboot.
+
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+
+use crate::upcall;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+
+pub struct NonvolatileStorage {
+    storage: RefCell<Vec<u8>>,
+    power_loss: Cell<Option<usize>>,
+
+    read_buffer: RefCell<RwAllowBuffer>,
+    write_buffer: RefCell<RoAllowBuffer>,
+}
+
+impl NonvolatileStorage {
+    pub fn new(size: usize) -> std::rc::Rc<NonvolatileStorage> {
+        std::rc::Rc::new(NonvolatileStorage {
+            storage: RefCell::new(vec![0xff; size]),
+            power_loss: Cell::new(None),
+            read_buffer: Default::default(),
+            write_buffer: Default::default(),
+        })
+    }
+
+    /// Returns the contents of the storage region.
+    pub fn contents(&self) -> Vec<u8> {
+        self.storage.borrow().clone()
+    }
+
+    /// Overwrites part of the storage region, starting at `offset`.
+    pub fn set_contents(&self, offset: usize, data: &[u8]) {
+        self.storage.borrow_mut()[offset..offset + data.len()].copy_from_slice(data);
+    }
+
+    /// Interrupts the next write after `bytes` bytes have been stored.
+    pub fn lose_power_after(&self, bytes: usize) {
+        self.power_loss.set(Some(bytes));
+    }
+
+    // Validates the range of a read or write against the storage region and
+    // the shared buffer.
+    fn check_range(&self, offset: u32, len: u32, buffer_len: usize) -> Result<(), ErrorCode> {
+        let (offset, len) = (offset as usize, len as usize);
+        if offset + len > self.storage.borrow().len() {
+            return Err(ErrorCode::Invalid);
+        }
+        if len > buffer_len {
+            return Err(ErrorCode::Size);
+        }
+        Ok(())
+    }
+}
+
+impl crate::fake::SyscallDriver for NonvolatileStorage {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        2
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_WRITE {
+            Ok(self.write_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_READ {
+            Ok(self.read_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => crate::command_return::success(),
+            GET_SIZE => crate::command_return::success_u32(self.storage.borrow().len() as u32),
+            READ => {
+                let mut buffer = self.read_buffer.borrow_mut();
+                if let Err(error) = self.check_range(argument0, argument1, buffer.len()) {
+                    return crate::command_return::failure(error);
+                }
+                let (offset, len) = (argument0 as usize, argument1 as usize);
+                buffer[..len].copy_from_slice(&self.storage.borrow()[offset..offset + len]);
+                upcall::schedule(DRIVER_NUM, SUBSCRIBE_READ_DONE, (argument1, 0, 0))
+                    .expect("Unable to schedule upcall {}");
+                crate::command_return::success()
+            }
+            WRITE => {
+                let buffer = self.write_buffer.borrow();
+                if let Err(error) = self.check_range(argument0, argument1, buffer.len()) {
+                    return crate::command_return::failure(error);
+                }
+                let offset = argument0 as usize;
+                let len = match self.power_loss.take() {
+                    Some(bytes) => bytes.min(argument1 as usize),
+                    None => argument1 as usize,
+                };
+                self.set_contents(offset, &buffer[..len]);
+                upcall::schedule(DRIVER_NUM, SUBSCRIBE_WRITE_DONE, (len as u32, 0, 0))
+                    .expect("Unable to schedule upcall {}");
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const DRIVER_NUM: u32 = 0x50001;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const GET_SIZE: u32 = 1;
+const READ: u32 = 2;
+const WRITE: u32 = 3;
+
+const SUBSCRIBE_READ_DONE: u32 = 0;
+const SUBSCRIBE_WRITE_DONE: u32 = 1;
+const ALLOW_WRITE: u32 = 0;
+const ALLOW_READ: u32 = 0;
@@ -0,0 +1,79 @@
+use crate::fake;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+use fake::nonvolatile_storage::*;
+use libtock_platform::{share, AllowRo, DefaultConfig, ErrorCode, Subscribe, YieldNoWaitReturn};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let storage = NonvolatileStorage::new(16);
+    assert!(storage.command(DRIVER_CHECK, 1, 2).is_success());
+    assert_eq!(storage.command(GET_SIZE, 0, 0).get_success_u32(), Some(16));
+    assert!(storage
+        .allow_readonly(ALLOW_WRITE, RoAllowBuffer::default())
+        .is_ok());
+    assert!(storage.allow_readonly(1, RoAllowBuffer::default()).is_err());
+    assert!(storage
+        .allow_readwrite(ALLOW_READ, RwAllowBuffer::default())
+        .is_ok());
+    assert!(storage
+        .allow_readwrite(1, RwAllowBuffer::default())
+        .is_err());
+
+    // Outside the storage region.
+    assert_eq!(
+        storage.command(READ, 12, 8).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    // Larger than the shared buffer.
+    assert_eq!(
+        storage.command(WRITE, 0, 4).get_failure(),
+        Some(ErrorCode::Size)
+    );
+    assert_eq!(
+        storage.command(4, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+    assert_eq!(storage.contents(), [0xff; 16]);
+}
+
+// Integration test that verifies NonvolatileStorage works with fake::Kernel
+// and libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let storage = NonvolatileStorage::new(8);
+    kernel.add_driver(&storage);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+
+    let called = core::cell::Cell::new(Option::<(u32,)>::None);
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_WRITE>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_WRITE_DONE>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_ro, subscribe) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_WRITE>(allow_ro, b"abcd")
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_WRITE_DONE>(
+            subscribe, &called,
+        )
+        .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE, 2, 4).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((4,)));
+
+        // Power is lost two bytes into the write.
+        storage.lose_power_after(2);
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE, 0, 4).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((2,)));
+    });
+    assert_eq!(storage.contents(), *b"ababcd\xff\xff");
+}
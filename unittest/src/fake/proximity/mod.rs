@@ -0,0 +1,123 @@
+//! Fake implementation of the proximity API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/60005_proximity.md
+//!
+//! Tests feed readings to the fake with `set_value`. A plain reading completes
+//! with the next value; a reading on interrupt completes only with a value
+//! outside its thresholds, as the sensor would report a threshold crossing.
+//! `set_value_sync` answers the next plain reading as soon as it starts.
+
+use core::cell::Cell;
+use libtock_platform::{CommandReturn, ErrorCode};
+
+use crate::upcall;
+
+pub struct Proximity {
+    pending: Cell<Option<Reading>>,
+    upcall_on_command: Cell<Option<u8>>,
+}
+
+#[derive(Copy, Clone)]
+enum Reading {
+    Now,
+    Outside { lower: u8, upper: u8 },
+}
+
+impl Proximity {
+    pub fn new() -> std::rc::Rc<Proximity> {
+        std::rc::Rc::new(Proximity {
+            pending: Cell::new(None),
+            upcall_on_command: Cell::new(None),
+        })
+    }
+
+    /// Returns `true` while a reading is pending.
+    pub fn is_busy(&self) -> bool {
+        self.pending.get().is_some()
+    }
+
+    /// Returns the `(lower, upper)` thresholds of the pending reading on
+    /// interrupt, if there is one.
+    pub fn thresholds(&self) -> Option<(u8, u8)> {
+        match self.pending.get() {
+            Some(Reading::Outside { lower, upper }) => Some((lower, upper)),
+            _ => None,
+        }
+    }
+
+    /// Feeds `proximity` to the sensor, completing the pending reading if it
+    /// accepts the value. Returns `true` if it did.
+    pub fn set_value(&self, proximity: u8) -> bool {
+        let complete = match self.pending.get() {
+            None => false,
+            Some(Reading::Now) => true,
+            Some(Reading::Outside { lower, upper }) => proximity < lower || proximity > upper,
+        };
+        if complete {
+            self.pending.set(None);
+            upcall::schedule(DRIVER_NUM, SUBSCRIBE_READING, (proximity as u32, 0, 0))
+                .expect("Unable to schedule upcall {}");
+        }
+        complete
+    }
+
+    /// Completes the next plain reading with `proximity` as soon as it starts.
+    pub fn set_value_sync(&self, proximity: u8) {
+        self.upcall_on_command.set(Some(proximity));
+    }
+
+    fn start(&self, reading: Reading) -> CommandReturn {
+        if self.is_busy() {
+            return crate::command_return::failure(ErrorCode::Busy);
+        }
+        self.pending.set(Some(reading));
+        if let Reading::Now = reading {
+            if let Some(proximity) = self.upcall_on_command.take() {
+                self.set_value(proximity);
+            }
+        }
+        crate::command_return::success()
+    }
+}
+
+impl crate::fake::SyscallDriver for Proximity {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => crate::command_return::success(),
+            READ => self.start(Reading::Now),
+            READ_ON_INTERRUPT => {
+                if argument0 > argument1 || argument1 > u8::MAX as u32 {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.start(Reading::Outside {
+                    lower: argument0 as u8,
+                    upper: argument1 as u8,
+                })
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const DRIVER_NUM: u32 = 0x60005;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const READ: u32 = 1;
+const READ_ON_INTERRUPT: u32 = 2;
+
+const SUBSCRIBE_READING: u32 = 0;
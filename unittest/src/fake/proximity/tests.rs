@@ -0,0 +1,73 @@
+use crate::fake;
+use fake::proximity::*;
+use libtock_platform::{share, DefaultConfig, ErrorCode, YieldNoWaitReturn};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let kernel = fake::Kernel::new();
+    let proximity = Proximity::new();
+    kernel.add_driver(&proximity);
+    assert!(proximity.command(DRIVER_CHECK, 1, 2).is_success());
+
+    assert!(!proximity.set_value(10));
+    assert!(proximity.command(READ, 0, 0).is_success());
+    assert!(proximity.is_busy());
+    assert_eq!(proximity.thresholds(), None);
+    assert_eq!(
+        proximity.command(READ, 0, 0).get_failure(),
+        Some(ErrorCode::Busy)
+    );
+    assert!(proximity.set_value(10));
+    assert!(!proximity.is_busy());
+
+    assert_eq!(
+        proximity.command(READ_ON_INTERRUPT, 200, 100).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        proximity.command(READ_ON_INTERRUPT, 100, 256).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert!(proximity.command(READ_ON_INTERRUPT, 100, 200).is_success());
+    assert_eq!(proximity.thresholds(), Some((100, 200)));
+    assert!(!proximity.set_value(100));
+    assert!(!proximity.set_value(200));
+    assert!(proximity.set_value(201));
+    assert!(!proximity.is_busy());
+
+    proximity.set_value_sync(42);
+    assert!(proximity.command(READ, 0, 0).is_success());
+    assert!(!proximity.is_busy());
+    assert_eq!(
+        proximity.command(3, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies Proximity works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let proximity = Proximity::new();
+    kernel.add_driver(&proximity);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+
+    let reading = core::cell::Cell::new(None);
+    share::scope(|subscribe| {
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_READING>(
+            subscribe, &reading,
+        )
+        .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, READ_ON_INTERRUPT, 20, 80).is_success());
+        proximity.set_value(50);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        proximity.set_value(15);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(reading.get(), Some((15,)));
+    });
+}
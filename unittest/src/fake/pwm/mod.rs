@@ -0,0 +1,124 @@
+//! Fake implementation of the PWM API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/00010_pwm.md
+//!
+//! Like the real API, `Pwm` controls a set of PWM pins. It records the output
+//! of each pin, which tests retrieve with `output`, and every change to it,
+//! which tests retrieve with `take_history`.
+
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+
+/// The output of a running PWM pin.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PwmOutput {
+    pub frequency_hz: u32,
+    /// Out of `MAX_DUTY_CYCLE`.
+    pub duty_cycle: u16,
+}
+
+pub struct Pwm<const PINS: usize> {
+    outputs: [Cell<Option<PwmOutput>>; PINS],
+    history: RefCell<Vec<(u16, Option<PwmOutput>)>>,
+}
+
+impl<const PINS: usize> Pwm<PINS> {
+    /// The highest frequency each pin can output.
+    pub const MAX_FREQUENCY_HZ: u32 = 1_000_000;
+    /// The duty cycle of an output that is always high.
+    pub const MAX_DUTY_CYCLE: u16 = 10_000;
+
+    pub fn new() -> std::rc::Rc<Pwm<PINS>> {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const STOPPED: Cell<Option<PwmOutput>> = Cell::new(None);
+        std::rc::Rc::new(Pwm {
+            outputs: [STOPPED; PINS],
+            history: Default::default(),
+        })
+    }
+
+    /// Returns the output of `pin`, or `None` if it is stopped.
+    pub fn output(&self, pin: u16) -> Option<PwmOutput> {
+        self.outputs.get(pin as usize).and_then(Cell::get)
+    }
+
+    /// Returns the outputs set since the last call, in order, with the pin
+    /// they were set on. Stopping a pin sets its output to `None`.
+    pub fn take_history(&self) -> Vec<(u16, Option<PwmOutput>)> {
+        self.history.take()
+    }
+
+    fn set_output(&self, pin: u16, output: Option<PwmOutput>) {
+        self.outputs[pin as usize].set(output);
+        self.history.borrow_mut().push((pin, output));
+    }
+}
+
+impl<const PINS: usize> crate::fake::SyscallDriver for Pwm<PINS> {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        0
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => {}
+            START => {
+                let (pin, duty_cycle) = (argument0 as u16, (argument0 >> 16) as u16);
+                if pin as usize >= PINS {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                if argument1 == 0
+                    || argument1 > Self::MAX_FREQUENCY_HZ
+                    || duty_cycle > Self::MAX_DUTY_CYCLE
+                {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.set_output(
+                    pin,
+                    Some(PwmOutput {
+                        frequency_hz: argument1,
+                        duty_cycle,
+                    }),
+                );
+            }
+            STOP => {
+                if argument0 as usize >= PINS {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.set_output(argument0 as u16, None);
+            }
+            GET_MAX_FREQUENCY => {
+                if argument0 as usize >= PINS {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                return crate::command_return::success_u32(Self::MAX_FREQUENCY_HZ);
+            }
+            GET_MAX_DUTY_CYCLE => {
+                return crate::command_return::success_u32(Self::MAX_DUTY_CYCLE.into())
+            }
+            COUNT => return crate::command_return::success_u32(PINS as u32),
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        }
+        crate::command_return::success()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const DRIVER_NUM: u32 = 0x10;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const START: u32 = 1;
+const STOP: u32 = 2;
+const GET_MAX_FREQUENCY: u32 = 3;
+const GET_MAX_DUTY_CYCLE: u32 = 4;
+const COUNT: u32 = 5;
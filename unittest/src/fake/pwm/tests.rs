@@ -0,0 +1,86 @@
+use crate::fake;
+use fake::pwm::*;
+use libtock_platform::ErrorCode;
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let pwm = Pwm::<2>::new();
+    assert!(pwm.command(DRIVER_CHECK, 1, 2).is_success());
+    assert_eq!(pwm.command(COUNT, 0, 0).get_success_u32(), Some(2));
+    assert_eq!(
+        pwm.command(GET_MAX_FREQUENCY, 1, 0).get_success_u32(),
+        Some(1_000_000)
+    );
+    assert_eq!(
+        pwm.command(GET_MAX_FREQUENCY, 2, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        pwm.command(GET_MAX_DUTY_CYCLE, 0, 0).get_success_u32(),
+        Some(10_000)
+    );
+
+    assert!(pwm.command(START, 2500 << 16 | 1, 50).is_success());
+    assert_eq!(
+        pwm.output(1),
+        Some(PwmOutput {
+            frequency_hz: 50,
+            duty_cycle: 2500
+        })
+    );
+    assert_eq!(pwm.output(0), None);
+    assert_eq!(
+        pwm.command(START, 2, 50).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        pwm.command(START, 10_001 << 16, 50).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        pwm.command(START, 0, 1_000_001).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert!(pwm.command(STOP, 1, 0).is_success());
+    assert_eq!(pwm.output(1), None);
+    assert_eq!(
+        pwm.take_history(),
+        [
+            (
+                1,
+                Some(PwmOutput {
+                    frequency_hz: 50,
+                    duty_cycle: 2500
+                })
+            ),
+            (1, None)
+        ]
+    );
+    assert_eq!(
+        pwm.command(6, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies Pwm works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let pwm = Pwm::<2>::new();
+    kernel.add_driver(&pwm);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, START, 100 << 16, 1000).is_success());
+    assert_eq!(
+        pwm.output(0),
+        Some(PwmOutput {
+            frequency_hz: 1000,
+            duty_cycle: 100
+        })
+    );
+    assert!(fake::Syscalls::command(DRIVER_NUM, STOP, 0, 0).is_success());
+    assert_eq!(pwm.output(0), None);
+}
@@ -0,0 +1,267 @@
+//! Fake implementation of the Screen API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/90001_screen.md
+//!
+//! `Screen` keeps the screen contents in an in-memory framebuffer, which tests
+//! can snapshot with `framebuffer` or `pixel`. It supports a single resolution
+//! and the RGB565 (the default) and RGB888 pixel formats. Changing the pixel
+//! format or the rotation clears the framebuffer.
+
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+
+use crate::upcall;
+use crate::RoAllowBuffer;
+
+pub struct Screen {
+    width: u32,
+    height: u32,
+    powered: Cell<bool>,
+    brightness: Cell<u32>,
+    rotation: Cell<u32>,
+    pixel_format: Cell<u32>,
+    frame: Cell<Frame>,
+    framebuffer: RefCell<Vec<u8>>,
+
+    buffer: RefCell<RoAllowBuffer>,
+}
+
+#[derive(Copy, Clone, Default)]
+struct Frame {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+impl Screen {
+    /// Creates a screen of `width` by `height` pixels (when not rotated).
+    pub fn new(width: u32, height: u32) -> std::rc::Rc<Screen> {
+        std::rc::Rc::new(Screen {
+            width,
+            height,
+            powered: Cell::new(false),
+            brightness: Cell::new(0),
+            rotation: Cell::new(0),
+            pixel_format: Cell::new(PIXEL_FORMAT_RGB565),
+            frame: Default::default(),
+            framebuffer: RefCell::new(vec![0; (width * height * 2) as usize]),
+            buffer: Default::default(),
+        })
+    }
+
+    /// Returns the current resolution, which depends on the rotation.
+    pub fn resolution(&self) -> (u32, u32) {
+        match self.rotation.get() % 2 {
+            0 => (self.width, self.height),
+            _ => (self.height, self.width),
+        }
+    }
+
+    /// Returns a copy of the screen contents, row by row, in the current pixel
+    /// format.
+    pub fn framebuffer(&self) -> Vec<u8> {
+        self.framebuffer.borrow().clone()
+    }
+
+    /// Returns the bytes of the pixel at (`x`, `y`), in the current pixel
+    /// format.
+    pub fn pixel(&self, x: u32, y: u32) -> Vec<u8> {
+        let bytes_per_pixel = self.bytes_per_pixel();
+        let offset = (y * self.resolution().0 + x) as usize * bytes_per_pixel;
+        self.framebuffer.borrow()[offset..offset + bytes_per_pixel].to_vec()
+    }
+
+    pub fn is_powered(&self) -> bool {
+        self.powered.get()
+    }
+
+    pub fn brightness(&self) -> u32 {
+        self.brightness.get()
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        match self.pixel_format.get() {
+            PIXEL_FORMAT_RGB565 => 2,
+            _ => 3,
+        }
+    }
+
+    fn clear(&self) {
+        let len = (self.width * self.height) as usize * self.bytes_per_pixel();
+        self.framebuffer.replace(vec![0; len]);
+    }
+
+    fn set_frame(&self, position: u32, size: u32) -> Result<(), ErrorCode> {
+        let frame = Frame {
+            x: position >> 16,
+            y: position & 0xffff,
+            width: size >> 16,
+            height: size & 0xffff,
+        };
+        let (width, height) = self.resolution();
+        if frame.x + frame.width > width || frame.y + frame.height > height {
+            return Err(ErrorCode::Invalid);
+        }
+        self.frame.set(frame);
+        Ok(())
+    }
+
+    // Copies `pixels` into the current frame, row by row. If `repeat` is set,
+    // `pixels` holds a single pixel that fills the whole frame.
+    fn draw(&self, pixels: &[u8], repeat: bool) -> Result<(), ErrorCode> {
+        let bytes_per_pixel = self.bytes_per_pixel();
+        let frame = self.frame.get();
+        let frame_len = (frame.width * frame.height) as usize;
+        let count = match repeat {
+            true => frame_len,
+            false => pixels.len() / bytes_per_pixel,
+        };
+        if count > frame_len {
+            return Err(ErrorCode::Size);
+        }
+        let screen_width = self.resolution().0;
+        let mut framebuffer = self.framebuffer.borrow_mut();
+        let mut source = pixels.chunks_exact(bytes_per_pixel).cycle();
+        for i in 0..count as u32 {
+            let x = frame.x + i % frame.width;
+            let y = frame.y + i / frame.width;
+            let offset = (y * screen_width + x) as usize * bytes_per_pixel;
+            framebuffer[offset..offset + bytes_per_pixel]
+                .copy_from_slice(source.next().expect("Empty pixel source"));
+        }
+        Ok(())
+    }
+}
+
+impl crate::fake::SyscallDriver for Screen {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_BUFFER {
+            Ok(self.buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        // Commands that complete with an upcall return early.
+        let result = match command_num {
+            DRIVER_CHECK => return crate::command_return::success(),
+            SET_POWER => {
+                self.powered.set(argument0 != 0);
+                Ok(())
+            }
+            SET_BRIGHTNESS => {
+                self.brightness.set(argument0);
+                Ok(())
+            }
+            RESOLUTION_MODES => return crate::command_return::success_u32(1),
+            RESOLUTION_MODE => {
+                return match argument0 {
+                    0 => crate::command_return::success_2_u32(self.width, self.height),
+                    _ => crate::command_return::failure(ErrorCode::Invalid),
+                }
+            }
+            PIXEL_FORMAT_MODES => return crate::command_return::success_u32(2),
+            PIXEL_FORMAT_MODE => {
+                return match argument0 {
+                    0 => crate::command_return::success_u32(PIXEL_FORMAT_RGB565),
+                    1 => crate::command_return::success_u32(PIXEL_FORMAT_RGB888),
+                    _ => crate::command_return::failure(ErrorCode::Invalid),
+                }
+            }
+            GET_ROTATION => return crate::command_return::success_u32(self.rotation.get()),
+            SET_ROTATION => match argument0 {
+                0..=3 => {
+                    self.rotation.set(argument0);
+                    self.clear();
+                    Ok(())
+                }
+                _ => Err(ErrorCode::Invalid),
+            },
+            GET_RESOLUTION => {
+                let (width, height) = self.resolution();
+                return crate::command_return::success_2_u32(width, height);
+            }
+            SET_RESOLUTION => match (argument0, argument1) == self.resolution() {
+                true => Ok(()),
+                false => Err(ErrorCode::NoSupport),
+            },
+            GET_PIXEL_FORMAT => return crate::command_return::success_u32(self.pixel_format.get()),
+            SET_PIXEL_FORMAT => match argument0 {
+                PIXEL_FORMAT_RGB565 | PIXEL_FORMAT_RGB888 => {
+                    self.pixel_format.set(argument0);
+                    self.clear();
+                    Ok(())
+                }
+                _ => Err(ErrorCode::NoSupport),
+            },
+            SET_FRAME => self.set_frame(argument0, argument1),
+            WRITE => {
+                let buffer = self.buffer.borrow();
+                match buffer.get(..argument0 as usize) {
+                    Some(data) => self.draw(data, false),
+                    None => Err(ErrorCode::Size),
+                }
+            }
+            FILL => {
+                let buffer = self.buffer.borrow();
+                match buffer.get(..self.bytes_per_pixel()) {
+                    Some(color) => self.draw(color, true),
+                    None => Err(ErrorCode::Size),
+                }
+            }
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        };
+        if let Err(error) = result {
+            return crate::command_return::failure(error);
+        }
+        upcall::schedule(DRIVER_NUM, SUBSCRIBE_DONE, (0, 0, 0))
+            .expect("Unable to schedule upcall {}");
+        crate::command_return::success()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const PIXEL_FORMAT_RGB565: u32 = 2;
+const PIXEL_FORMAT_RGB888: u32 = 3;
+
+const DRIVER_NUM: u32 = 0x90001;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const SET_POWER: u32 = 2;
+const SET_BRIGHTNESS: u32 = 3;
+const RESOLUTION_MODES: u32 = 11;
+const RESOLUTION_MODE: u32 = 12;
+const PIXEL_FORMAT_MODES: u32 = 13;
+const PIXEL_FORMAT_MODE: u32 = 14;
+const GET_ROTATION: u32 = 21;
+const SET_ROTATION: u32 = 22;
+const GET_RESOLUTION: u32 = 23;
+const SET_RESOLUTION: u32 = 24;
+const GET_PIXEL_FORMAT: u32 = 25;
+const SET_PIXEL_FORMAT: u32 = 26;
+const SET_FRAME: u32 = 100;
+const WRITE: u32 = 200;
+const FILL: u32 = 300;
+
+const SUBSCRIBE_DONE: u32 = 0;
+const ALLOW_BUFFER: u32 = 0;
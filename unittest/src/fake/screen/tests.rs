@@ -0,0 +1,121 @@
+use crate::fake;
+use crate::RoAllowBuffer;
+use fake::screen::*;
+use libtock_platform::{share, AllowRo, DefaultConfig, ErrorCode, Subscribe, YieldNoWaitReturn};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let kernel = fake::Kernel::new();
+    let screen = Screen::new(4, 2);
+    kernel.add_driver(&screen);
+    assert!(screen.command(DRIVER_CHECK, 1, 2).is_success());
+    assert!(screen
+        .allow_readonly(ALLOW_BUFFER, RoAllowBuffer::default())
+        .is_ok());
+    assert!(screen.allow_readonly(1, RoAllowBuffer::default()).is_err());
+
+    assert!(screen.command(SET_POWER, 1, 0).is_success());
+    assert!(screen.is_powered());
+    assert!(screen.command(SET_BRIGHTNESS, 50, 0).is_success());
+    assert_eq!(screen.brightness(), 50);
+
+    assert_eq!(
+        screen.command(RESOLUTION_MODE, 0, 0).get_success_2_u32(),
+        Some((4, 2))
+    );
+    assert_eq!(
+        screen.command(PIXEL_FORMAT_MODE, 1, 0).get_success_u32(),
+        Some(PIXEL_FORMAT_RGB888)
+    );
+    assert_eq!(
+        screen.command(SET_PIXEL_FORMAT, 4, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+    assert!(screen.command(SET_ROTATION, 1, 0).is_success());
+    assert_eq!(
+        screen.command(GET_RESOLUTION, 0, 0).get_success_2_u32(),
+        Some((2, 4))
+    );
+    assert!(screen.command(SET_RESOLUTION, 2, 4).is_success());
+    assert_eq!(
+        screen.command(SET_RESOLUTION, 4, 2).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+
+    // The frame must be on the screen.
+    assert!(screen
+        .command(SET_FRAME, 1 << 16 | 3, 1 << 16 | 1)
+        .is_success());
+    assert_eq!(
+        screen
+            .command(SET_FRAME, 1 << 16 | 3, 1 << 16 | 2)
+            .get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    // Nothing is shared.
+    assert_eq!(
+        screen.command(WRITE, 2, 0).get_failure(),
+        Some(ErrorCode::Size)
+    );
+    assert_eq!(
+        screen.command(FILL, 0, 0).get_failure(),
+        Some(ErrorCode::Size)
+    );
+    assert_eq!(
+        screen.command(1, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies Screen works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let screen = Screen::new(3, 2);
+    kernel.add_driver(&screen);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+
+    let called = core::cell::Cell::new(Option::<(u32,)>::None);
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_BUFFER>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_DONE>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_ro, subscribe) = handle.split();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_DONE>(
+            subscribe, &called,
+        )
+        .unwrap();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_BUFFER>(
+            allow_ro,
+            &[1, 2, 3, 4, 5, 6],
+        )
+        .unwrap();
+
+        // Fill the screen with the first pixel, then write the rest into the
+        // bottom right corner.
+        assert!(fake::Syscalls::command(DRIVER_NUM, SET_FRAME, 0, 3 << 16 | 2).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.take(), Some((0,)));
+        assert!(fake::Syscalls::command(DRIVER_NUM, FILL, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.take(), Some((0,)));
+        assert!(
+            fake::Syscalls::command(DRIVER_NUM, SET_FRAME, 1 << 16 | 1, 2 << 16 | 1).is_success()
+        );
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE, 6, 0).is_failure());
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE, 4, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.take(), Some((0,)));
+    });
+    assert_eq!(screen.framebuffer(), [1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 3, 4]);
+    assert_eq!(screen.pixel(2, 1), [3, 4]);
+}
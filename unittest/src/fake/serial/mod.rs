@@ -0,0 +1,129 @@
+//! Fake implementation of a serial driver with the Console API, documented
+//! here: https://github.com/tock/tock/blob/master/doc/syscalls/00001_console.md
+//!
+//! `Serial` implements the driver with number `DRIVER_NUM`, so tests can add
+//! several, such as a console and a UART. Like the real API, `Serial` stores
+//! each message written to it. The resulting byte stream can be retrieved via
+//! `take_bytes` for use in unit tests.
+
+use core::cell::{Cell, RefCell};
+use core::cmp;
+use libtock_platform::{CommandReturn, ErrorCode};
+
+use crate::upcall;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+
+pub struct Serial<const DRIVER_NUM: u32> {
+    messages: Cell<Vec<u8>>,
+    buffer: Cell<RoAllowBuffer>,
+
+    read_buffer: RefCell<RwAllowBuffer>,
+    /// To be returned on read
+    input: Cell<Vec<u8>>,
+}
+
+impl<const DRIVER_NUM: u32> Serial<DRIVER_NUM> {
+    pub fn new() -> std::rc::Rc<Serial<DRIVER_NUM>> {
+        Self::new_with_input(b"")
+    }
+
+    pub fn new_with_input(inputs: &[u8]) -> std::rc::Rc<Serial<DRIVER_NUM>> {
+        std::rc::Rc::new(Serial {
+            messages: Default::default(),
+            buffer: Default::default(),
+            read_buffer: Default::default(),
+            input: Cell::new(Vec::from(inputs)),
+        })
+    }
+
+    /// Returns the bytes that have been submitted so far,
+    /// and clears them.
+    pub fn take_bytes(&self) -> Vec<u8> {
+        self.messages.take()
+    }
+}
+
+impl<const DRIVER_NUM: u32> crate::fake::SyscallDriver for Serial<DRIVER_NUM> {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        3
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_WRITE {
+            Ok(self.buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_READ {
+            Ok(self.read_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => {}
+            WRITE => {
+                let mut bytes = self.messages.take();
+                let buffer = self.buffer.take();
+                let size = cmp::min(buffer.len(), argument0 as usize);
+                bytes.extend_from_slice(&(*buffer)[..size]);
+                self.buffer.set(buffer);
+                self.messages.set(bytes);
+                upcall::schedule(DRIVER_NUM, SUBSCRIBE_WRITE, (size as u32, 0, 0))
+                    .expect("Unable to schedule upcall {}");
+            }
+            READ => {
+                let count_wanted = argument0 as usize;
+                let bytes = self.input.take();
+                let count_wanted = cmp::min(count_wanted, bytes.len());
+                let to_send = &bytes[..count_wanted];
+                let to_keep = &bytes[count_wanted..];
+                self.input.set(Vec::from(to_keep));
+
+                let count_available = to_send.len();
+                self.read_buffer.borrow_mut()[..count_wanted].copy_from_slice(to_send);
+                upcall::schedule(DRIVER_NUM, SUBSCRIBE_READ, (0, count_available as u32, 0))
+                    .expect("Unable to schedule upcall {}");
+            }
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        }
+        crate::command_return::success()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+// The syscall numbers are public so the tests of drivers built on the serial
+// driver, such as the console, can check their system calls against them.
+
+// Command numbers
+pub const DRIVER_CHECK: u32 = 0;
+pub const WRITE: u32 = 1;
+pub const READ: u32 = 2;
+//const ABORT: u32 = 3;
+pub const SUBSCRIBE_WRITE: u32 = 1;
+pub const SUBSCRIBE_READ: u32 = 2;
+pub const ALLOW_WRITE: u32 = 1;
+pub const ALLOW_READ: u32 = 1;
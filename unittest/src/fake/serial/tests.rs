@@ -0,0 +1,32 @@
+use crate::fake;
+use fake::serial::*;
+use libtock_platform::share;
+use libtock_platform::DefaultConfig;
+
+const UART: u32 = 0x90010;
+
+// Integration test that verifies several Serial drivers work side by side
+// with fake::Kernel and libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let console = fake::Console::new();
+    let uart = Serial::<UART>::new_with_input(b"OK");
+    kernel.add_driver(&console);
+    kernel.add_driver(&uart);
+    assert!(fake::Syscalls::command(UART, DRIVER_CHECK, 1, 2).is_success());
+    share::scope(|allow_ro| {
+        fake::Syscalls::allow_ro::<DefaultConfig, UART, ALLOW_WRITE>(allow_ro, b"AT").unwrap();
+        assert!(fake::Syscalls::command(UART, WRITE, 2, 0).is_success());
+    });
+    assert_eq!(uart.take_bytes(), b"AT");
+    assert_eq!(console.take_bytes(), b"");
+
+    let mut buf = [0; 2];
+    share::scope(|allow_rw| {
+        fake::Syscalls::allow_rw::<DefaultConfig, UART, ALLOW_READ>(allow_rw, &mut buf).unwrap();
+        assert!(fake::Syscalls::command(UART, READ, 2, 0).is_success());
+    });
+    assert_eq!(buf, *b"OK");
+}
@@ -0,0 +1,88 @@
+//! Fake implementation of the servo API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/90009_servo.md
+//!
+//! Like the real API, `Servo` controls a set of servomotors. It records the
+//! angle of each servo, which tests retrieve with `angle`, and every angle
+//! set, which tests retrieve with `take_history`.
+
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+
+pub struct Servo<const SERVOS: usize> {
+    angles: [Cell<Option<u16>>; SERVOS],
+    history: RefCell<Vec<(u32, u16)>>,
+}
+
+impl<const SERVOS: usize> Servo<SERVOS> {
+    pub fn new() -> std::rc::Rc<Servo<SERVOS>> {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const UNKNOWN: Cell<Option<u16>> = Cell::new(None);
+        std::rc::Rc::new(Servo {
+            angles: [UNKNOWN; SERVOS],
+            history: Default::default(),
+        })
+    }
+
+    /// Returns the angle of `servo`, or `None` if it was never set.
+    pub fn angle(&self, servo: u32) -> Option<u16> {
+        self.angles.get(servo as usize).and_then(Cell::get)
+    }
+
+    /// Returns the angles set since the last call, in order, with the servo
+    /// they were set on.
+    pub fn take_history(&self) -> Vec<(u32, u16)> {
+        self.history.take()
+    }
+}
+
+impl<const SERVOS: usize> crate::fake::SyscallDriver for Servo<SERVOS> {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        0
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        if matches!(command_num, SET_ANGLE | GET_ANGLE) && argument0 as usize >= SERVOS {
+            return crate::command_return::failure(ErrorCode::NoDevice);
+        }
+        match command_num {
+            DRIVER_CHECK => crate::command_return::success(),
+            SET_ANGLE => {
+                if argument1 > MAX_ANGLE {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.angles[argument0 as usize].set(Some(argument1 as u16));
+                self.history
+                    .borrow_mut()
+                    .push((argument0, argument1 as u16));
+                crate::command_return::success()
+            }
+            GET_ANGLE => match self.angles[argument0 as usize].get() {
+                Some(angle) => crate::command_return::success_u32(angle.into()),
+                None => crate::command_return::failure(ErrorCode::Fail),
+            },
+            COUNT => crate::command_return::success_u32(SERVOS as u32),
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const MAX_ANGLE: u32 = 180;
+
+const DRIVER_NUM: u32 = 0x90009;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const SET_ANGLE: u32 = 1;
+const GET_ANGLE: u32 = 2;
+const COUNT: u32 = 3;
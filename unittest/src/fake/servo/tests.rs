@@ -0,0 +1,50 @@
+use crate::fake;
+use fake::servo::*;
+use libtock_platform::ErrorCode;
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let servo = Servo::<2>::new();
+    assert!(servo.command(DRIVER_CHECK, 1, 2).is_success());
+    assert_eq!(servo.command(COUNT, 0, 0).get_success_u32(), Some(2));
+    assert_eq!(
+        servo.command(GET_ANGLE, 0, 0).get_failure(),
+        Some(ErrorCode::Fail)
+    );
+    assert!(servo.command(SET_ANGLE, 0, 45).is_success());
+    assert!(servo.command(SET_ANGLE, 1, 180).is_success());
+    assert_eq!(servo.command(GET_ANGLE, 0, 0).get_success_u32(), Some(45));
+    assert_eq!(servo.angle(1), Some(180));
+    assert_eq!(
+        servo.command(SET_ANGLE, 0, 181).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        servo.command(SET_ANGLE, 2, 0).get_failure(),
+        Some(ErrorCode::NoDevice)
+    );
+    assert_eq!(servo.take_history(), [(0, 45), (1, 180)]);
+    assert_eq!(
+        servo.command(4, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies Servo works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let servo = Servo::<2>::new();
+    kernel.add_driver(&servo);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, SET_ANGLE, 1, 90).is_success());
+    assert_eq!(servo.angle(1), Some(90));
+    assert_eq!(
+        fake::Syscalls::command(DRIVER_NUM, GET_ANGLE, 1, 0).get_success_u32(),
+        Some(90)
+    );
+}
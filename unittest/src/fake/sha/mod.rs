@@ -0,0 +1,153 @@
+//! Fake implementation of the SHA API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/40005_sha.md
+//!
+//! `Sha` computes real SHA-2 digests on the host, so tests can cross-check
+//! digests computed through the driver against known values.
+
+use core::cell::RefCell;
+use libtock_platform::{CommandReturn, ErrorCode};
+use sha2::Digest;
+
+use crate::upcall;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+
+pub struct Sha {
+    hash: RefCell<Option<Hash>>,
+
+    data: RefCell<RoAllowBuffer>,
+    digest: RefCell<RwAllowBuffer>,
+}
+
+// A hash in progress.
+enum Hash {
+    Sha256(sha2::Sha256),
+    Sha384(sha2::Sha384),
+    Sha512(sha2::Sha512),
+}
+
+impl Sha {
+    pub fn new() -> std::rc::Rc<Sha> {
+        std::rc::Rc::new(Sha {
+            hash: Default::default(),
+            data: Default::default(),
+            digest: Default::default(),
+        })
+    }
+
+    /// Returns the digest length of the selected algorithm, or `None` if no
+    /// algorithm has been selected.
+    pub fn output_len(&self) -> Option<usize> {
+        self.hash.borrow().as_ref().map(|hash| match hash {
+            Hash::Sha256(_) => 32,
+            Hash::Sha384(_) => 48,
+            Hash::Sha512(_) => 64,
+        })
+    }
+
+    fn update(&self) -> Result<(), ErrorCode> {
+        let data = self.data.borrow();
+        match self.hash.borrow_mut().as_mut() {
+            None => return Err(ErrorCode::Invalid),
+            Some(Hash::Sha256(hash)) => hash.update(&**data),
+            Some(Hash::Sha384(hash)) => hash.update(&**data),
+            Some(Hash::Sha512(hash)) => hash.update(&**data),
+        }
+        Ok(())
+    }
+
+    // Writes the digest and resets the hash, keeping the selected algorithm.
+    fn finish(&self) -> Result<(), ErrorCode> {
+        let len = self.output_len().ok_or(ErrorCode::Invalid)?;
+        let mut digest = self.digest.borrow_mut();
+        if digest.len() < len {
+            return Err(ErrorCode::Size);
+        }
+        match self.hash.borrow_mut().as_mut() {
+            None => return Err(ErrorCode::Invalid),
+            Some(Hash::Sha256(hash)) => digest[..len].copy_from_slice(&hash.finalize_reset()),
+            Some(Hash::Sha384(hash)) => digest[..len].copy_from_slice(&hash.finalize_reset()),
+            Some(Hash::Sha512(hash)) => digest[..len].copy_from_slice(&hash.finalize_reset()),
+        }
+        Ok(())
+    }
+}
+
+impl crate::fake::SyscallDriver for Sha {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_DATA {
+            Ok(self.data.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_DIGEST {
+            Ok(self.digest.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        let result = match command_num {
+            SET_ALGORITHM => {
+                let hash = match argument0 {
+                    0 => Hash::Sha256(Default::default()),
+                    1 => Hash::Sha384(Default::default()),
+                    2 => Hash::Sha512(Default::default()),
+                    _ => return crate::command_return::failure(ErrorCode::Invalid),
+                };
+                self.hash.replace(Some(hash));
+                return crate::command_return::success();
+            }
+            UPDATE => self.update(),
+            FINISH => self.finish(),
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        };
+        let status = match result {
+            Ok(()) => 0,
+            Err(error) => error as u32,
+        };
+        upcall::schedule(DRIVER_NUM, SUBSCRIBE_DONE, (status, 0, 0))
+            .expect("Unable to schedule upcall {}");
+        crate::command_return::success()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const DRIVER_NUM: u32 = 0x40005;
+
+// Command numbers. The capsule has no separate driver check command; the
+// driver check is a set_algorithm command.
+#[cfg(test)]
+const DRIVER_CHECK: u32 = 0;
+const SET_ALGORITHM: u32 = 0;
+const UPDATE: u32 = 2;
+const FINISH: u32 = 3;
+
+const SUBSCRIBE_DONE: u32 = 0;
+const ALLOW_DATA: u32 = 1;
+const ALLOW_DIGEST: u32 = 2;
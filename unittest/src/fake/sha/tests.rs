@@ -0,0 +1,79 @@
+use crate::fake;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+use fake::sha::*;
+use libtock_platform::YieldNoWaitReturn;
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let sha = Sha::new();
+    assert!(sha
+        .allow_readonly(ALLOW_DATA, RoAllowBuffer::default())
+        .is_ok());
+    assert!(sha.allow_readonly(0, RoAllowBuffer::default()).is_err());
+    assert!(sha
+        .allow_readwrite(ALLOW_DIGEST, RwAllowBuffer::default())
+        .is_ok());
+    assert!(sha.allow_readwrite(0, RwAllowBuffer::default()).is_err());
+
+    assert_eq!(sha.output_len(), None);
+    // The driver check selects SHA-256.
+    assert!(sha.command(DRIVER_CHECK, 0, 0).is_success());
+    assert_eq!(sha.output_len(), Some(32));
+    assert_eq!(
+        sha.command(SET_ALGORITHM, 3, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert!(sha.command(SET_ALGORITHM, 1, 0).is_success());
+    assert_eq!(sha.output_len(), Some(48));
+    assert_eq!(
+        sha.command(4, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies Sha works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let sha = Sha::new();
+    kernel.add_driver(&sha);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 0, 0).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, SET_ALGORITHM, 0, 0).is_success());
+
+    let mut digest = [0; 32];
+    let called = core::cell::Cell::new(Option::<(u32,)>::None);
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_DATA>,
+            AllowRw<_, DRIVER_NUM, ALLOW_DIGEST>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_DONE>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_ro, allow_rw, subscribe) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_DATA>(allow_ro, b"hello")
+            .unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_DIGEST>(allow_rw, &mut digest)
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_DONE>(
+            subscribe, &called,
+        )
+        .unwrap();
+
+        for _ in 0..2 {
+            assert!(fake::Syscalls::command(DRIVER_NUM, UPDATE, 0, 0).is_success());
+            assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+            assert_eq!(called.get(), Some((0,)));
+        }
+        assert!(fake::Syscalls::command(DRIVER_NUM, FINISH, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(called.get(), Some((0,)));
+    });
+    assert_eq!(digest[..], sha2::Sha256::digest(b"hellohello")[..]);
+}
@@ -0,0 +1,103 @@
+//! Fake implementation of the sound pressure API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/60006_sound_pressure.md
+//!
+//! A reading started by the app stays pending until the test provides a value
+//! with `set_value`. Tests that would rather not step the fake can use
+//! `set_value_sync`, which answers the next reading as soon as it starts.
+
+use core::cell::Cell;
+use libtock_platform::{CommandReturn, ErrorCode};
+
+use crate::upcall;
+
+pub struct SoundPressure {
+    enabled: Cell<bool>,
+    busy: Cell<bool>,
+    upcall_on_command: Cell<Option<u8>>,
+}
+
+impl SoundPressure {
+    pub fn new() -> std::rc::Rc<SoundPressure> {
+        std::rc::Rc::new(SoundPressure {
+            enabled: Cell::new(false),
+            busy: Cell::new(false),
+            upcall_on_command: Cell::new(None),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.get()
+    }
+
+    /// Returns `true` while a reading is pending.
+    pub fn is_busy(&self) -> bool {
+        self.busy.get()
+    }
+
+    /// Completes the pending reading with `decibels`, if there is one.
+    pub fn set_value(&self, decibels: u8) {
+        if self.busy.take() {
+            upcall::schedule(DRIVER_NUM, SUBSCRIBE_READING, (decibels as u32, 0, 0))
+                .expect("Unable to schedule upcall {}");
+        }
+    }
+
+    /// Completes the next reading with `decibels` as soon as it starts.
+    pub fn set_value_sync(&self, decibels: u8) {
+        self.upcall_on_command.set(Some(decibels));
+    }
+}
+
+impl crate::fake::SyscallDriver for SoundPressure {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn command(&self, command_num: u32, _argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => crate::command_return::success(),
+            READ => {
+                if !self.enabled.get() {
+                    return crate::command_return::failure(ErrorCode::Off);
+                }
+                if self.busy.replace(true) {
+                    return crate::command_return::failure(ErrorCode::Busy);
+                }
+                if let Some(decibels) = self.upcall_on_command.take() {
+                    self.set_value(decibels);
+                }
+                crate::command_return::success()
+            }
+            ENABLE => {
+                self.enabled.set(true);
+                crate::command_return::success()
+            }
+            DISABLE => {
+                self.enabled.set(false);
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const DRIVER_NUM: u32 = 0x60006;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const READ: u32 = 1;
+const ENABLE: u32 = 2;
+const DISABLE: u32 = 3;
+
+const SUBSCRIBE_READING: u32 = 0;
@@ -0,0 +1,63 @@
+use crate::fake;
+use fake::sound_pressure::*;
+use libtock_platform::{share, DefaultConfig, ErrorCode, YieldNoWaitReturn};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let kernel = fake::Kernel::new();
+    let sound_pressure = SoundPressure::new();
+    kernel.add_driver(&sound_pressure);
+    assert!(sound_pressure.command(DRIVER_CHECK, 1, 2).is_success());
+
+    assert_eq!(
+        sound_pressure.command(READ, 0, 0).get_failure(),
+        Some(ErrorCode::Off)
+    );
+    assert!(sound_pressure.command(ENABLE, 0, 0).is_success());
+    assert!(sound_pressure.is_enabled());
+    assert!(sound_pressure.command(READ, 0, 0).is_success());
+    assert!(sound_pressure.is_busy());
+    assert_eq!(
+        sound_pressure.command(READ, 0, 0).get_failure(),
+        Some(ErrorCode::Busy)
+    );
+    sound_pressure.set_value(60);
+    assert!(!sound_pressure.is_busy());
+    sound_pressure.set_value_sync(70);
+    assert!(sound_pressure.command(READ, 0, 0).is_success());
+    assert!(!sound_pressure.is_busy());
+    assert!(sound_pressure.command(DISABLE, 0, 0).is_success());
+    assert!(!sound_pressure.is_enabled());
+    assert_eq!(
+        sound_pressure.command(4, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies SoundPressure works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let sound_pressure = SoundPressure::new();
+    kernel.add_driver(&sound_pressure);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, ENABLE, 0, 0).is_success());
+
+    let reading = core::cell::Cell::new(None);
+    share::scope(|subscribe| {
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_READING>(
+            subscribe, &reading,
+        )
+        .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, READ, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        sound_pressure.set_value(55);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(reading.get(), Some((55,)));
+    });
+}
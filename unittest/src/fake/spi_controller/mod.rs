@@ -0,0 +1,194 @@
+//! Fake implementation of the SPI controller API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/20001_spi_controller.md
+//!
+//! `SpiController` records every transfer it performs, which can be retrieved
+//! via `take_transfers`. By default, the bus is in loopback: the bytes clocked
+//! back during a transfer are the bytes that were written. A fake peripheral
+//! response can be queued with `add_response`; queued bytes are clocked back
+//! first, and the bus returns to loopback once they have been consumed.
+
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::collections::VecDeque;
+
+use crate::upcall;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+
+/// A transfer performed by the code under test.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Transfer {
+    pub chip_select: u32,
+    pub written: Vec<u8>,
+}
+
+pub struct SpiController<const NUM_CHIP_SELECTS: usize> {
+    chip_select: Cell<u32>,
+    rate: Cell<u32>,
+    phase: Cell<u32>,
+    polarity: Cell<u32>,
+
+    write_buffer: RefCell<RoAllowBuffer>,
+    read_buffer: RefCell<RwAllowBuffer>,
+    responses: RefCell<VecDeque<u8>>,
+    transfers: RefCell<Vec<Transfer>>,
+}
+
+impl<const NUM_CHIP_SELECTS: usize> SpiController<NUM_CHIP_SELECTS> {
+    pub fn new() -> std::rc::Rc<SpiController<NUM_CHIP_SELECTS>> {
+        std::rc::Rc::new(SpiController {
+            chip_select: Cell::new(0),
+            rate: Cell::new(DEFAULT_RATE),
+            phase: Cell::new(0),
+            polarity: Cell::new(0),
+            write_buffer: Default::default(),
+            read_buffer: Default::default(),
+            responses: Default::default(),
+            transfers: Default::default(),
+        })
+    }
+
+    /// Queues bytes for the fake peripheral to clock back in later transfers.
+    pub fn add_response(&self, bytes: &[u8]) {
+        self.responses.borrow_mut().extend(bytes);
+    }
+
+    /// Returns the transfers performed so far, and clears them.
+    pub fn take_transfers(&self) -> Vec<Transfer> {
+        self.transfers.take()
+    }
+
+    pub fn get_chip_select(&self) -> u32 {
+        self.chip_select.get()
+    }
+
+    pub fn get_rate(&self) -> u32 {
+        self.rate.get()
+    }
+
+    pub fn get_phase(&self) -> u32 {
+        self.phase.get()
+    }
+
+    pub fn get_polarity(&self) -> u32 {
+        self.polarity.get()
+    }
+
+    fn read_write(&self, len: usize) -> CommandReturn {
+        let write_buffer = self.write_buffer.borrow();
+        let mut read_buffer = self.read_buffer.borrow_mut();
+        // A transfer without a read buffer discards the received bytes.
+        if len > write_buffer.len() || (!read_buffer.is_empty() && len > read_buffer.len()) {
+            return crate::command_return::failure(ErrorCode::Size);
+        }
+        let written = &write_buffer[..len];
+        let mut responses = self.responses.borrow_mut();
+        for (i, &byte) in written.iter().enumerate() {
+            let received = responses.pop_front().unwrap_or(byte);
+            if let Some(read) = read_buffer.get_mut(i) {
+                *read = received;
+            }
+        }
+        self.transfers.borrow_mut().push(Transfer {
+            chip_select: self.chip_select.get(),
+            written: Vec::from(written),
+        });
+        upcall::schedule(DRIVER_NUM, SUBSCRIBE_COMPLETE, (len as u32, 0, 0))
+            .expect("Unable to schedule upcall {}");
+        crate::command_return::success()
+    }
+}
+
+impl<const NUM_CHIP_SELECTS: usize> crate::fake::SyscallDriver for SpiController<NUM_CHIP_SELECTS> {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_WRITE {
+            Ok(self.write_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_READ {
+            Ok(self.read_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => crate::command_return::success(),
+            READ_WRITE => self.read_write(argument0 as usize),
+            SET_CHIP_SELECT => {
+                if argument0 >= NUM_CHIP_SELECTS as u32 {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.chip_select.set(argument0);
+                crate::command_return::success()
+            }
+            GET_CHIP_SELECT => crate::command_return::success_u32(self.chip_select.get()),
+            SET_RATE => {
+                if argument0 == 0 {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.rate.set(argument0);
+                crate::command_return::success()
+            }
+            GET_RATE => crate::command_return::success_u32(self.rate.get()),
+            SET_PHASE => {
+                self.phase.set(argument0);
+                crate::command_return::success()
+            }
+            GET_PHASE => crate::command_return::success_u32(self.phase.get()),
+            SET_POLARITY => {
+                self.polarity.set(argument0);
+                crate::command_return::success()
+            }
+            GET_POLARITY => crate::command_return::success_u32(self.polarity.get()),
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const DRIVER_NUM: u32 = 0x20001;
+
+const DEFAULT_RATE: u32 = 1_000_000;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const READ_WRITE: u32 = 2;
+const SET_CHIP_SELECT: u32 = 3;
+const GET_CHIP_SELECT: u32 = 4;
+const SET_RATE: u32 = 5;
+const GET_RATE: u32 = 6;
+const SET_PHASE: u32 = 7;
+const GET_PHASE: u32 = 8;
+const SET_POLARITY: u32 = 9;
+const GET_POLARITY: u32 = 10;
+
+const SUBSCRIBE_COMPLETE: u32 = 0;
+const ALLOW_WRITE: u32 = 0;
+const ALLOW_READ: u32 = 0;
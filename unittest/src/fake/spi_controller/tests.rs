@@ -0,0 +1,86 @@
+use crate::fake;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+use fake::spi_controller::*;
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, ErrorCode, YieldNoWaitReturn};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let spi = SpiController::<2>::new();
+    assert!(spi.command(DRIVER_CHECK, 1, 2).is_success());
+    assert!(spi
+        .allow_readonly(ALLOW_WRITE, RoAllowBuffer::default())
+        .is_ok());
+    assert!(spi.allow_readonly(1, RoAllowBuffer::default()).is_err());
+    assert!(spi
+        .allow_readwrite(ALLOW_READ, RwAllowBuffer::default())
+        .is_ok());
+    assert!(spi.allow_readwrite(1, RwAllowBuffer::default()).is_err());
+
+    assert!(spi.command(SET_CHIP_SELECT, 1, 0).is_success());
+    assert_eq!(
+        spi.command(SET_CHIP_SELECT, 2, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        spi.command(GET_CHIP_SELECT, 0, 0).get_success_u32(),
+        Some(1)
+    );
+    assert_eq!(
+        spi.command(GET_RATE, 0, 0).get_success_u32(),
+        Some(DEFAULT_RATE)
+    );
+    assert!(spi.command(SET_RATE, 400_000, 0).is_success());
+    assert_eq!(spi.get_rate(), 400_000);
+    assert!(spi.command(SET_PHASE, 1, 0).is_success());
+    assert_eq!(spi.command(GET_PHASE, 0, 0).get_success_u32(), Some(1));
+    assert!(spi.command(SET_POLARITY, 1, 0).is_success());
+    assert_eq!(spi.command(GET_POLARITY, 0, 0).get_success_u32(), Some(1));
+
+    // No write buffer has been shared.
+    assert_eq!(
+        spi.command(READ_WRITE, 1, 0).get_failure(),
+        Some(ErrorCode::Size)
+    );
+    assert_eq!(spi.take_transfers(), []);
+}
+
+// Integration test that verifies SpiController works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let spi = SpiController::<1>::new();
+    kernel.add_driver(&spi);
+    spi.add_response(&[0xa5]);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+
+    let mut read = [0; 3];
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_WRITE>,
+            AllowRw<_, DRIVER_NUM, ALLOW_READ>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_ro, allow_rw) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_WRITE>(allow_ro, b"abc")
+            .unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_READ>(allow_rw, &mut read)
+            .unwrap();
+        assert!(fake::Syscalls::command(DRIVER_NUM, READ_WRITE, 3, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+    });
+    // The queued response comes first, then the bus loops back.
+    assert_eq!(read, [0xa5, b'b', b'c']);
+    assert_eq!(
+        spi.take_transfers(),
+        [Transfer {
+            chip_select: 0,
+            written: b"abc".to_vec(),
+        }]
+    );
+}
@@ -0,0 +1,164 @@
+//! Fake implementation of the text screen API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/90003_text_screen.md
+//!
+//! `TextScreen` models the character grid of the display, which tests can
+//! snapshot with `rows`. Like an HD44780 controller, it keeps the text while
+//! the display is off, and text written past the last column continues on the
+//! next row, wrapping from the last row back to the first.
+
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+
+use crate::upcall;
+use crate::RoAllowBuffer;
+
+pub struct TextScreen {
+    columns: u32,
+    rows: u32,
+    grid: RefCell<Vec<u8>>,
+    cursor: Cell<(u32, u32)>,
+    display_on: Cell<bool>,
+    blinking: Cell<bool>,
+    cursor_shown: Cell<bool>,
+
+    buffer: RefCell<RoAllowBuffer>,
+}
+
+impl TextScreen {
+    /// Creates a screen of `columns` by `rows` characters, blank and off.
+    pub fn new(columns: u32, rows: u32) -> std::rc::Rc<TextScreen> {
+        std::rc::Rc::new(TextScreen {
+            columns,
+            rows,
+            grid: RefCell::new(vec![b' '; (columns * rows) as usize]),
+            cursor: Cell::new((0, 0)),
+            display_on: Cell::new(false),
+            blinking: Cell::new(false),
+            cursor_shown: Cell::new(false),
+            buffer: Default::default(),
+        })
+    }
+
+    /// Returns a snapshot of the text on the screen, one string per row.
+    /// Blank characters are spaces.
+    pub fn rows(&self) -> Vec<String> {
+        self.grid
+            .borrow()
+            .chunks(self.columns as usize)
+            .map(|row| String::from_utf8_lossy(row).into_owned())
+            .collect()
+    }
+
+    /// Returns the position of the cursor as `(column, row)`.
+    pub fn cursor(&self) -> (u32, u32) {
+        self.cursor.get()
+    }
+
+    pub fn is_display_on(&self) -> bool {
+        self.display_on.get()
+    }
+
+    pub fn is_blinking(&self) -> bool {
+        self.blinking.get()
+    }
+
+    pub fn is_cursor_shown(&self) -> bool {
+        self.cursor_shown.get()
+    }
+
+    fn write(&self, text: &[u8]) {
+        let mut grid = self.grid.borrow_mut();
+        let (mut column, mut row) = self.cursor.get();
+        for &character in text {
+            grid[(row * self.columns + column) as usize] = character;
+            column += 1;
+            if column == self.columns {
+                column = 0;
+                row = (row + 1) % self.rows;
+            }
+        }
+        self.cursor.set((column, row));
+    }
+}
+
+impl crate::fake::SyscallDriver for TextScreen {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        1
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_BUFFER {
+            Ok(self.buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        // Commands that complete with an upcall return early.
+        match command_num {
+            DRIVER_CHECK => return crate::command_return::success(),
+            GET_SIZE => return crate::command_return::success_2_u32(self.columns, self.rows),
+            WRITE => match self.buffer.borrow().get(..argument0 as usize) {
+                Some(text) => self.write(text),
+                None => return crate::command_return::failure(ErrorCode::Size),
+            },
+            DISPLAY_ON => self.display_on.set(true),
+            DISPLAY_OFF => self.display_on.set(false),
+            BLINK_ON => self.blinking.set(true),
+            BLINK_OFF => self.blinking.set(false),
+            SHOW_CURSOR => self.cursor_shown.set(true),
+            HIDE_CURSOR => self.cursor_shown.set(false),
+            CLEAR => {
+                self.grid.borrow_mut().fill(b' ');
+                self.cursor.set((0, 0));
+            }
+            HOME => self.cursor.set((0, 0)),
+            SET_CURSOR => {
+                if argument0 >= self.columns || argument1 >= self.rows {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.cursor.set((argument0, argument1));
+            }
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        }
+        upcall::schedule(DRIVER_NUM, SUBSCRIBE_DONE, (0, 0, 0))
+            .expect("Unable to schedule upcall {}");
+        crate::command_return::success()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const DRIVER_NUM: u32 = 0x90003;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const GET_SIZE: u32 = 1;
+const WRITE: u32 = 2;
+const DISPLAY_ON: u32 = 3;
+const DISPLAY_OFF: u32 = 4;
+const BLINK_ON: u32 = 5;
+const BLINK_OFF: u32 = 6;
+const SHOW_CURSOR: u32 = 7;
+const HIDE_CURSOR: u32 = 8;
+const CLEAR: u32 = 9;
+const HOME: u32 = 10;
+const SET_CURSOR: u32 = 11;
+
+const SUBSCRIBE_DONE: u32 = 0;
+
+const ALLOW_BUFFER: u32 = 0;
@@ -0,0 +1,99 @@
+use crate::fake;
+use crate::RoAllowBuffer;
+use fake::text_screen::*;
+use libtock_platform::{share, AllowRo, DefaultConfig, ErrorCode, Subscribe, YieldNoWaitReturn};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let kernel = fake::Kernel::new();
+    let screen = TextScreen::new(4, 2);
+    kernel.add_driver(&screen);
+    assert!(screen.command(DRIVER_CHECK, 1, 2).is_success());
+    assert_eq!(
+        screen.command(GET_SIZE, 0, 0).get_success_2_u32(),
+        Some((4, 2))
+    );
+
+    assert!(screen.command(DISPLAY_ON, 0, 0).is_success());
+    assert!(screen.is_display_on());
+    assert!(screen.command(BLINK_ON, 0, 0).is_success());
+    assert!(screen.is_blinking());
+    assert!(screen.command(SHOW_CURSOR, 0, 0).is_success());
+    assert!(screen.is_cursor_shown());
+    assert!(screen.command(DISPLAY_OFF, 0, 0).is_success());
+    assert!(screen.command(BLINK_OFF, 0, 0).is_success());
+    assert!(screen.command(HIDE_CURSOR, 0, 0).is_success());
+    assert!(!screen.is_display_on() && !screen.is_blinking() && !screen.is_cursor_shown());
+
+    assert!(screen.command(SET_CURSOR, 3, 1).is_success());
+    assert_eq!(screen.cursor(), (3, 1));
+    assert_eq!(
+        screen.command(SET_CURSOR, 4, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        screen.command(SET_CURSOR, 0, 2).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert!(screen.command(HOME, 0, 0).is_success());
+    assert_eq!(screen.cursor(), (0, 0));
+
+    assert!(screen
+        .allow_readonly(ALLOW_BUFFER, RoAllowBuffer::default())
+        .is_ok());
+    assert!(screen.allow_readonly(1, RoAllowBuffer::default()).is_err());
+    assert!(screen.command(WRITE, 0, 0).is_success());
+    assert_eq!(
+        screen.command(WRITE, 1, 0).get_failure(),
+        Some(ErrorCode::Size)
+    );
+    assert!(screen.command(CLEAR, 0, 0).is_success());
+    assert_eq!(screen.rows(), ["    ", "    "]);
+    assert_eq!(
+        screen.command(12, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies TextScreen works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let screen = TextScreen::new(4, 2);
+    kernel.add_driver(&screen);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+
+    let called = core::cell::Cell::new(false);
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_BUFFER>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_DONE>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_ro, subscribe) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_BUFFER>(
+            allow_ro,
+            b"Hello, Tock",
+        )
+        .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_DONE>(
+            subscribe, &called,
+        )
+        .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, SET_CURSOR, 2, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE, 11, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert!(called.get());
+    });
+    // The text wraps from the end of the last row back to the first one.
+    assert_eq!(screen.rows(), [" Toc", "klo,"]);
+    assert_eq!(screen.cursor(), (1, 1));
+}
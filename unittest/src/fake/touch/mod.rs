@@ -0,0 +1,227 @@
+//! Fake implementation of the Touch API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/90002_touch.md
+//!
+//! Tests drive `Touch` by injecting synthetic events. Single-touch events and
+//! gestures are delivered immediately (single-touch events only while enabled).
+//! Multi-touch events are queued until multi-touch is enabled and the app asks
+//! for the next one, so the fake never drops them.
+
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::collections::VecDeque;
+
+use crate::upcall;
+use crate::RwAllowBuffer;
+
+pub struct Touch {
+    number_of_touches: u32,
+    single_touch: Cell<bool>,
+    multi_touch: Cell<bool>,
+    // Whether the app is waiting for the next multi-touch event.
+    multi_touch_ready: Cell<bool>,
+    pending: RefCell<VecDeque<Vec<TouchEvent>>>,
+
+    buffer: RefCell<RwAllowBuffer>,
+}
+
+/// A touch to inject. `size` and `pressure` are 0 on panels that do not
+/// measure them.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TouchEvent {
+    pub id: u8,
+    pub status: TouchStatus,
+    pub x: u16,
+    pub y: u16,
+    pub size: u8,
+    pub pressure: u8,
+}
+
+impl TouchEvent {
+    /// A single-touch event of the first finger, without size or pressure.
+    pub fn new(status: TouchStatus, x: u16, y: u16) -> TouchEvent {
+        TouchEvent {
+            id: 0,
+            status,
+            x,
+            y,
+            size: 0,
+            pressure: 0,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TouchStatus {
+    Released = 0,
+    Pressed = 1,
+    Moved = 2,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Gesture {
+    SwipeUp = 0,
+    SwipeDown = 1,
+    SwipeLeft = 2,
+    SwipeRight = 3,
+    ZoomIn = 4,
+    ZoomOut = 5,
+}
+
+impl Touch {
+    /// Creates a panel that tracks up to `number_of_touches` fingers.
+    pub fn new(number_of_touches: u32) -> std::rc::Rc<Touch> {
+        std::rc::Rc::new(Touch {
+            number_of_touches,
+            single_touch: Cell::new(false),
+            multi_touch: Cell::new(false),
+            multi_touch_ready: Cell::new(false),
+            pending: Default::default(),
+            buffer: Default::default(),
+        })
+    }
+
+    /// Reports a single-touch event, if single-touch events are enabled.
+    pub fn inject_touch(&self, event: TouchEvent) {
+        if !self.single_touch.get() {
+            return;
+        }
+        let args = (
+            event.status as u32,
+            (event.x as u32) << 16 | event.y as u32,
+            (event.pressure as u32) << 16 | event.size as u32,
+        );
+        upcall::schedule(DRIVER_NUM, SUBSCRIBE_TOUCH, args).expect("Unable to schedule upcall {}");
+    }
+
+    /// Reports each of `events` as a single-touch event, in order.
+    pub fn inject_sequence(&self, events: &[TouchEvent]) {
+        for &event in events {
+            self.inject_touch(event);
+        }
+    }
+
+    /// Reports a single finger pressing at `from`, moving to `to` in `steps`
+    /// equal steps, and releasing there.
+    pub fn inject_swipe(&self, from: (u16, u16), to: (u16, u16), steps: u16) {
+        let interpolate = |from: u16, to: u16, step: u16| {
+            let delta = (to as i32 - from as i32) * step as i32 / steps as i32;
+            (from as i32 + delta) as u16
+        };
+        self.inject_touch(TouchEvent::new(TouchStatus::Pressed, from.0, from.1));
+        for step in 1..=steps {
+            self.inject_touch(TouchEvent::new(
+                TouchStatus::Moved,
+                interpolate(from.0, to.0, step),
+                interpolate(from.1, to.1, step),
+            ));
+        }
+        self.inject_touch(TouchEvent::new(TouchStatus::Released, to.0, to.1));
+    }
+
+    /// Reports a recognized gesture.
+    pub fn inject_gesture(&self, gesture: Gesture) {
+        upcall::schedule(DRIVER_NUM, SUBSCRIBE_GESTURE, (gesture as u32, 0, 0))
+            .expect("Unable to schedule upcall {}");
+    }
+
+    /// Queues a multi-touch event reporting `touches`.
+    pub fn inject_multi_touch(&self, touches: &[TouchEvent]) {
+        self.pending.borrow_mut().push_back(touches.to_vec());
+        self.deliver_multi_touch();
+    }
+
+    // Delivers the next pending multi-touch event, if the app is waiting for
+    // one.
+    fn deliver_multi_touch(&self) {
+        if !self.multi_touch.get() || !self.multi_touch_ready.get() {
+            return;
+        }
+        let touches = match self.pending.borrow_mut().pop_front() {
+            Some(touches) => touches,
+            None => return,
+        };
+        let mut buffer = self.buffer.borrow_mut();
+        for (record, touch) in buffer.chunks_exact_mut(TOUCH_RECORD_SIZE).zip(&touches) {
+            record[0] = touch.id;
+            record[1] = touch.status as u8;
+            record[2..4].copy_from_slice(&touch.x.to_le_bytes());
+            record[4..6].copy_from_slice(&touch.y.to_le_bytes());
+            record[6] = touch.size;
+            record[7] = touch.pressure;
+        }
+        self.multi_touch_ready.set(false);
+        upcall::schedule(
+            DRIVER_NUM,
+            SUBSCRIBE_MULTI_TOUCH,
+            (touches.len() as u32, 0, 0),
+        )
+        .expect("Unable to schedule upcall {}");
+    }
+}
+
+impl crate::fake::SyscallDriver for Touch {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        3
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_MULTI_TOUCH {
+            Ok(self.buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_num: u32, _argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => {}
+            ENABLE_SINGLE_TOUCH => self.single_touch.set(true),
+            DISABLE_SINGLE_TOUCH => self.single_touch.set(false),
+            MULTI_TOUCH_NEXT => {
+                self.multi_touch_ready.set(true);
+                self.deliver_multi_touch();
+            }
+            ENABLE_MULTI_TOUCH => {
+                self.multi_touch.set(true);
+                self.deliver_multi_touch();
+            }
+            DISABLE_MULTI_TOUCH => self.multi_touch.set(false),
+            NUMBER_OF_TOUCHES => return crate::command_return::success_u32(self.number_of_touches),
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        }
+        crate::command_return::success()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const TOUCH_RECORD_SIZE: usize = 8;
+
+const DRIVER_NUM: u32 = 0x90002;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const ENABLE_SINGLE_TOUCH: u32 = 1;
+const DISABLE_SINGLE_TOUCH: u32 = 2;
+const MULTI_TOUCH_NEXT: u32 = 10;
+const ENABLE_MULTI_TOUCH: u32 = 11;
+const DISABLE_MULTI_TOUCH: u32 = 12;
+const NUMBER_OF_TOUCHES: u32 = 100;
+
+const SUBSCRIBE_TOUCH: u32 = 0;
+const SUBSCRIBE_GESTURE: u32 = 1;
+const SUBSCRIBE_MULTI_TOUCH: u32 = 2;
+const ALLOW_MULTI_TOUCH: u32 = 2;
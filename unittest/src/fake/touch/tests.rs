@@ -0,0 +1,109 @@
+use crate::fake;
+use crate::RwAllowBuffer;
+use fake::touch::*;
+use libtock_platform::{share, AllowRw, DefaultConfig, ErrorCode, Subscribe, YieldNoWaitReturn};
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let touch = Touch::new(2);
+    assert!(touch.command(DRIVER_CHECK, 1, 2).is_success());
+    assert!(touch
+        .allow_readwrite(ALLOW_MULTI_TOUCH, RwAllowBuffer::default())
+        .is_ok());
+    assert!(touch.allow_readwrite(0, RwAllowBuffer::default()).is_err());
+
+    assert_eq!(
+        touch.command(NUMBER_OF_TOUCHES, 0, 0).get_success_u32(),
+        Some(2)
+    );
+    assert!(touch.command(ENABLE_SINGLE_TOUCH, 0, 0).is_success());
+    assert!(touch.single_touch.get());
+    assert!(touch.command(DISABLE_SINGLE_TOUCH, 0, 0).is_success());
+    assert!(!touch.single_touch.get());
+    assert_eq!(
+        touch.command(3, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies Touch works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let touch = Touch::new(2);
+    kernel.add_driver(&touch);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+
+    let single = core::cell::Cell::new(Option::<(u32, u32, u32)>::None);
+    let multi = core::cell::Cell::new(Option::<(u32, u32)>::None);
+    let mut buffer = [0; 12];
+    share::scope::<
+        (
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_TOUCH>,
+            AllowRw<_, DRIVER_NUM, ALLOW_MULTI_TOUCH>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_MULTI_TOUCH>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (subscribe_single, allow_rw, subscribe_multi) = handle.split();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_TOUCH>(
+            subscribe_single,
+            &single,
+        )
+        .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_MULTI_TOUCH>(
+            subscribe_multi,
+            &multi,
+        )
+        .unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_MULTI_TOUCH>(
+            allow_rw,
+            &mut buffer,
+        )
+        .unwrap();
+
+        // Single-touch events are dropped until enabled.
+        touch.inject_touch(TouchEvent::new(TouchStatus::Pressed, 1, 2));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        assert!(fake::Syscalls::command(DRIVER_NUM, ENABLE_SINGLE_TOUCH, 0, 0).is_success());
+        touch.inject_swipe((0, 10), (30, 10), 3);
+        for expected in [
+            (1, 10),
+            (2, 10 << 16 | 10),
+            (2, 20 << 16 | 10),
+            (2, 30 << 16 | 10),
+            (0, 30 << 16 | 10),
+        ] {
+            assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+            assert_eq!(single.take(), Some((expected.0, expected.1, 0)));
+        }
+
+        // Multi-touch events wait until the app asks for them.
+        let touches = [
+            TouchEvent::new(TouchStatus::Pressed, 0x102, 0x304),
+            TouchEvent {
+                id: 1,
+                status: TouchStatus::Moved,
+                x: 5,
+                y: 6,
+                size: 7,
+                pressure: 8,
+            },
+        ];
+        touch.inject_multi_touch(&touches);
+        assert!(fake::Syscalls::command(DRIVER_NUM, ENABLE_MULTI_TOUCH, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        assert!(fake::Syscalls::command(DRIVER_NUM, MULTI_TOUCH_NEXT, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(multi.take(), Some((2, 0)));
+        touch.inject_multi_touch(&touches);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+    });
+    // Only the first touch fits.
+    assert_eq!(buffer, [0, 1, 2, 1, 4, 3, 0, 0, 0, 0, 0, 0]);
+}
@@ -0,0 +1,366 @@
+//! Fake implementation of the UDP API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/30002_udp.md
+//!
+//! `Udp` is the app's network stack, with a fixed set of interface addresses.
+//! Tests connect it to a `UdpNetwork`, on which they open `UdpSocket`s that
+//! stand in for other hosts: datagrams are delivered between the app and the
+//! sockets by destination address and port. Datagrams sent to the app are
+//! queued until it binds and shares a receive buffer; those sent to ports it
+//! did not bind are then dropped.
+
+use core::cell::{Cell, RefCell};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::collections::VecDeque;
+use std::rc::{Rc, Weak};
+
+use crate::upcall;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+
+/// An IPv6 address and UDP port.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Endpoint {
+    pub addr: [u8; 16],
+    pub port: u16,
+}
+
+impl Endpoint {
+    pub fn new(addr: [u8; 16], port: u16) -> Endpoint {
+        Endpoint { addr, port }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Endpoint {
+        let mut addr = [0; 16];
+        addr.copy_from_slice(&bytes[..16]);
+        Endpoint {
+            addr,
+            port: u16::from_le_bytes([bytes[16], bytes[17]]),
+        }
+    }
+
+    fn to_bytes(self) -> [u8; SOCKET_ADDR_LEN] {
+        let mut bytes = [0; SOCKET_ADDR_LEN];
+        bytes[..16].copy_from_slice(&self.addr);
+        bytes[16..].copy_from_slice(&self.port.to_le_bytes());
+        bytes
+    }
+}
+
+/// A datagram exchanged on a `UdpNetwork`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Datagram {
+    pub source: Endpoint,
+    pub destination: Endpoint,
+    pub payload: Vec<u8>,
+}
+
+pub struct Udp {
+    interfaces: Vec<[u8; 16]>,
+    bound: Cell<Option<Endpoint>>,
+    reserved_ports: RefCell<Vec<u16>>,
+    network: RefCell<Option<Rc<UdpNetwork>>>,
+    pending: RefCell<VecDeque<Datagram>>,
+    // Whether a datagram was written into the shared receive buffer.
+    rx_full: Cell<bool>,
+
+    tx: RefCell<RoAllowBuffer>,
+    rx: RefCell<RwAllowBuffer>,
+    config: RefCell<RwAllowBuffer>,
+    rx_config: RefCell<RwAllowBuffer>,
+}
+
+impl Udp {
+    /// Creates a network stack with the given interface addresses.
+    pub fn new(interfaces: &[[u8; 16]]) -> Rc<Udp> {
+        Rc::new(Udp {
+            interfaces: interfaces.to_vec(),
+            bound: Cell::new(None),
+            reserved_ports: Default::default(),
+            network: Default::default(),
+            pending: Default::default(),
+            rx_full: Cell::new(false),
+            tx: Default::default(),
+            rx: Default::default(),
+            config: Default::default(),
+            rx_config: Default::default(),
+        })
+    }
+
+    /// Returns the endpoint the app is bound to, if any.
+    pub fn bound(&self) -> Option<Endpoint> {
+        self.bound.get()
+    }
+
+    /// Marks `port` as bound by another app, so the app cannot bind it.
+    pub fn reserve_port(&self, port: u16) {
+        self.reserved_ports.borrow_mut().push(port);
+    }
+
+    // Returns whether the app is bound to `destination`.
+    fn accepts(&self, destination: Endpoint) -> bool {
+        match self.bound.get() {
+            Some(bound) => {
+                bound.port == destination.port
+                    && (bound.addr == UNSPECIFIED || bound.addr == destination.addr)
+            }
+            None => false,
+        }
+    }
+
+    // Writes the next pending datagram the app is bound to into the receive
+    // buffer, if the app shared empty buffers. Datagrams it is not bound to
+    // are dropped, as nothing listens on their port.
+    fn deliver(&self) {
+        if self.bound.get().is_none() || self.rx_full.get() || self.rx.borrow().is_empty() {
+            return;
+        }
+        let mut rx_config = self.rx_config.borrow_mut();
+        if rx_config.len() < 2 * SOCKET_ADDR_LEN {
+            return;
+        }
+        let datagram = loop {
+            let datagram = match self.pending.borrow_mut().pop_front() {
+                Some(datagram) => datagram,
+                None => return,
+            };
+            if self.accepts(datagram.destination) {
+                break datagram;
+            }
+        };
+        let mut rx = self.rx.borrow_mut();
+        let len = datagram.payload.len().min(rx.len());
+        rx[..len].copy_from_slice(&datagram.payload[..len]);
+        rx_config[..SOCKET_ADDR_LEN].copy_from_slice(&datagram.source.to_bytes());
+        self.rx_full.set(true);
+        upcall::schedule(DRIVER_NUM, SUBSCRIBE_RX, (len as u32, 0, 0))
+            .expect("Unable to schedule upcall {}");
+    }
+
+    fn bind(&self) -> Result<(), ErrorCode> {
+        let rx_config = self.rx_config.borrow();
+        if rx_config.len() < 2 * SOCKET_ADDR_LEN {
+            return Err(ErrorCode::Invalid);
+        }
+        let local = Endpoint::from_bytes(&rx_config[SOCKET_ADDR_LEN..]);
+        if local.port == 0 || (local.addr != UNSPECIFIED && !self.interfaces.contains(&local.addr))
+        {
+            return Err(ErrorCode::Invalid);
+        }
+        if self.reserved_ports.borrow().contains(&local.port) {
+            return Err(ErrorCode::Busy);
+        }
+        self.bound.set(Some(local));
+        Ok(())
+    }
+
+    fn send(&self) -> Result<(), ErrorCode> {
+        let config = self.config.borrow();
+        if config.len() < 2 * SOCKET_ADDR_LEN {
+            return Err(ErrorCode::Invalid);
+        }
+        let source = Endpoint::from_bytes(&config[..SOCKET_ADDR_LEN]);
+        let destination = Endpoint::from_bytes(&config[SOCKET_ADDR_LEN..]);
+        match self.bound.get() {
+            Some(bound) if bound.port == source.port => {}
+            _ => return Err(ErrorCode::Invalid),
+        }
+        if !self.interfaces.contains(&source.addr) || destination.port == 0 {
+            return Err(ErrorCode::Invalid);
+        }
+        let payload = self.tx.borrow().to_vec();
+        if payload.len() > MAX_PAYLOAD_LEN {
+            return Err(ErrorCode::Size);
+        }
+        if let Some(network) = self.network.borrow().as_ref() {
+            network.route(Datagram {
+                source,
+                destination,
+                payload,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A network connecting the app's `Udp` stack to `UdpSocket`s.
+pub struct UdpNetwork {
+    stacks: RefCell<Vec<Weak<Udp>>>,
+    sockets: RefCell<Vec<Rc<UdpSocket>>>,
+}
+
+impl UdpNetwork {
+    pub fn new() -> Rc<UdpNetwork> {
+        Rc::new(UdpNetwork {
+            stacks: Default::default(),
+            sockets: Default::default(),
+        })
+    }
+
+    /// Attaches the app's network stack to the network.
+    pub fn connect(self: &Rc<Self>, udp: &Rc<Udp>) {
+        self.stacks.borrow_mut().push(Rc::downgrade(udp));
+        udp.network.replace(Some(self.clone()));
+    }
+
+    /// Opens a socket bound to `local`.
+    pub fn socket(self: &Rc<Self>, local: Endpoint) -> Rc<UdpSocket> {
+        let socket = Rc::new(UdpSocket {
+            local,
+            network: Rc::downgrade(self),
+            received: Default::default(),
+        });
+        self.sockets.borrow_mut().push(socket.clone());
+        socket
+    }
+
+    // Delivers `datagram` to the sockets and stacks bound to its destination,
+    // and returns whether any was.
+    fn route(&self, datagram: Datagram) -> bool {
+        let mut delivered = false;
+        for socket in self.sockets.borrow().iter() {
+            if socket.local == datagram.destination {
+                socket.received.borrow_mut().push(datagram.clone());
+                delivered = true;
+            }
+        }
+        for udp in self.stacks.borrow().iter().filter_map(Weak::upgrade) {
+            if udp.interfaces.contains(&datagram.destination.addr) {
+                udp.pending.borrow_mut().push_back(datagram.clone());
+                udp.deliver();
+                delivered = true;
+            }
+        }
+        delivered
+    }
+}
+
+/// A socket on a `UdpNetwork`, standing in for another host.
+pub struct UdpSocket {
+    local: Endpoint,
+    network: Weak<UdpNetwork>,
+    received: RefCell<Vec<Datagram>>,
+}
+
+impl UdpSocket {
+    pub fn local(&self) -> Endpoint {
+        self.local
+    }
+
+    /// Sends `payload` to `destination`. Returns `false` if no socket or
+    /// interface on the network has the destination address.
+    ///
+    /// Datagrams sent to the app wait until it binds and shares a receive
+    /// buffer.
+    pub fn send_to(&self, destination: Endpoint, payload: &[u8]) -> bool {
+        match self.network.upgrade() {
+            Some(network) => network.route(Datagram {
+                source: self.local,
+                destination,
+                payload: payload.to_vec(),
+            }),
+            None => false,
+        }
+    }
+
+    /// Returns the datagrams received since the last call, in order.
+    pub fn take_received(&self) -> Vec<Datagram> {
+        self.received.take()
+    }
+}
+
+impl crate::fake::SyscallDriver for Udp {
+    fn id(&self) -> u32 {
+        DRIVER_NUM
+    }
+
+    fn num_upcalls(&self) -> u32 {
+        2
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_TX {
+            Ok(self.tx.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        let previous = match buffer_num {
+            ALLOW_RX => {
+                self.rx_full.set(false);
+                self.rx.replace(buffer)
+            }
+            ALLOW_CONFIG => self.config.replace(buffer),
+            ALLOW_RX_CONFIG => self.rx_config.replace(buffer),
+            _ => return Err((buffer, ErrorCode::Invalid)),
+        };
+        self.deliver();
+        Ok(previous)
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_num {
+            DRIVER_CHECK => {}
+            GET_INTERFACES => {
+                let mut config = self.config.borrow_mut();
+                let count = (argument0 as usize)
+                    .min(self.interfaces.len())
+                    .min(config.len() / 16);
+                for (i, interface) in self.interfaces[..count].iter().enumerate() {
+                    config[16 * i..][..16].copy_from_slice(interface);
+                }
+                return crate::command_return::success_u32(count as u32);
+            }
+            SEND => match self.send() {
+                Ok(()) => upcall::schedule(DRIVER_NUM, SUBSCRIBE_TX, (0, 0, 0))
+                    .expect("Unable to schedule upcall {}"),
+                Err(error) => return crate::command_return::failure(error),
+            },
+            BIND => match self.bind() {
+                Ok(()) => self.deliver(),
+                Err(error) => return crate::command_return::failure(error),
+            },
+            GET_MAX_PAYLOAD_LEN => {
+                return crate::command_return::success_u32(MAX_PAYLOAD_LEN as u32)
+            }
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        }
+        crate::command_return::success()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests;
+
+const UNSPECIFIED: [u8; 16] = [0; 16];
+const SOCKET_ADDR_LEN: usize = 18;
+// The IPv6 minimum MTU, less the IPv6 and UDP headers.
+const MAX_PAYLOAD_LEN: usize = 1280 - 40 - 8;
+
+const DRIVER_NUM: u32 = 0x30002;
+
+// Command numbers
+const DRIVER_CHECK: u32 = 0;
+const GET_INTERFACES: u32 = 1;
+const SEND: u32 = 2;
+const BIND: u32 = 3;
+const GET_MAX_PAYLOAD_LEN: u32 = 4;
+
+const SUBSCRIBE_RX: u32 = 0;
+const SUBSCRIBE_TX: u32 = 1;
+const ALLOW_TX: u32 = 0;
+const ALLOW_RX: u32 = 0;
+const ALLOW_CONFIG: u32 = 1;
+const ALLOW_RX_CONFIG: u32 = 2;
@@ -0,0 +1,183 @@
+use crate::fake;
+use crate::{RoAllowBuffer, RwAllowBuffer};
+use fake::udp::*;
+use libtock_platform::{
+    share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe, YieldNoWaitReturn,
+};
+
+const APP_ADDR: [u8; 16] = [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+const PEER_ADDR: [u8; 16] = [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let udp = Udp::new(&[APP_ADDR]);
+    assert!(udp.command(DRIVER_CHECK, 1, 2).is_success());
+    assert!(udp
+        .allow_readonly(ALLOW_TX, RoAllowBuffer::default())
+        .is_ok());
+    assert!(udp.allow_readonly(1, RoAllowBuffer::default()).is_err());
+    assert!(udp
+        .allow_readwrite(ALLOW_RX, RwAllowBuffer::default())
+        .is_ok());
+    assert!(udp
+        .allow_readwrite(ALLOW_CONFIG, RwAllowBuffer::default())
+        .is_ok());
+    assert!(udp
+        .allow_readwrite(ALLOW_RX_CONFIG, RwAllowBuffer::default())
+        .is_ok());
+    assert!(udp.allow_readwrite(3, RwAllowBuffer::default()).is_err());
+
+    // Without shared configuration buffers, nothing can be sent or bound.
+    assert_eq!(udp.command(GET_INTERFACES, 1, 0).get_success_u32(), Some(0));
+    assert_eq!(
+        udp.command(SEND, 0, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        udp.command(BIND, 0, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        udp.command(GET_MAX_PAYLOAD_LEN, 0, 0).get_success_u32(),
+        Some(1232)
+    );
+    assert_eq!(
+        udp.command(5, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+#[test]
+fn routing() {
+    let network = UdpNetwork::new();
+    let first = network.socket(Endpoint::new(PEER_ADDR, 1000));
+    let second = network.socket(Endpoint::new(PEER_ADDR, 2000));
+    assert!(first.send_to(second.local(), b"hi"));
+    assert!(!first.send_to(Endpoint::new(APP_ADDR, 1000), b"lost"));
+    assert_eq!(first.take_received(), []);
+    assert_eq!(
+        second.take_received(),
+        [Datagram {
+            source: first.local(),
+            destination: second.local(),
+            payload: b"hi".to_vec(),
+        }]
+    );
+}
+
+// Integration test that verifies Udp works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let udp = Udp::new(&[APP_ADDR]);
+    kernel.add_driver(&udp);
+    let network = UdpNetwork::new();
+    network.connect(&udp);
+    let peer = network.socket(Endpoint::new(PEER_ADDR, 2000));
+    let local = Endpoint::new(APP_ADDR, 1000);
+    assert!(fake::Syscalls::command(DRIVER_NUM, DRIVER_CHECK, 1, 2).is_success());
+
+    // Queued until the app binds and shares a buffer; the datagram to another
+    // port is then dropped.
+    assert!(peer.send_to(Endpoint::new(APP_ADDR, 999), b"dropped"));
+    assert!(peer.send_to(local, b"ping"));
+
+    let mut config = [0; 36];
+    config[..18].copy_from_slice(&local.to_bytes());
+    config[18..].copy_from_slice(&peer.local().to_bytes());
+    let mut rx_config = [0; 36];
+    rx_config[18..].copy_from_slice(&local.to_bytes());
+    let mut rx = [0; 8];
+    let sent = core::cell::Cell::new(Option::<(u32,)>::None);
+    let received = core::cell::Cell::new(Option::<(u32,)>::None);
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_TX>,
+            AllowRw<_, DRIVER_NUM, ALLOW_RX>,
+            AllowRw<_, DRIVER_NUM, ALLOW_CONFIG>,
+            AllowRw<_, DRIVER_NUM, ALLOW_RX_CONFIG>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_RX>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_TX>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_tx, allow_rx, allow_config, allow_rx_config, subscribe_rx, subscribe_tx) =
+            handle.split();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_RX>(
+            subscribe_rx,
+            &received,
+        )
+        .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_TX>(
+            subscribe_tx,
+            &sent,
+        )
+        .unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_CONFIG>(
+            allow_config,
+            &mut config,
+        )
+        .unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RX_CONFIG>(
+            allow_rx_config,
+            &mut rx_config,
+        )
+        .unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RX>(allow_rx, &mut rx).unwrap();
+        assert_eq!(
+            fake::Syscalls::command(DRIVER_NUM, GET_INTERFACES, 2, 0).get_success_u32(),
+            Some(1)
+        );
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, BIND, 0, 0).is_success());
+        assert_eq!(udp.bound(), Some(local));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(received.get(), Some((4,)));
+        assert_eq!(udp.pending.borrow().len(), 0);
+
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_TX>(allow_tx, b"pong").unwrap();
+        assert!(fake::Syscalls::command(DRIVER_NUM, SEND, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(sent.get(), Some((0,)));
+    });
+    assert_eq!(config[..16], APP_ADDR);
+    assert_eq!(rx[..4], *b"ping");
+    assert_eq!(rx_config[..18], peer.local().to_bytes());
+    assert_eq!(
+        peer.take_received(),
+        [Datagram {
+            source: local,
+            destination: peer.local(),
+            payload: b"pong".to_vec(),
+        }]
+    );
+}
+
+#[test]
+fn bind_reserved_port() {
+    let kernel = fake::Kernel::new();
+    let udp = Udp::new(&[APP_ADDR]);
+    kernel.add_driver(&udp);
+    udp.reserve_port(1000);
+    let mut rx_config = [0; 36];
+    rx_config[18..].copy_from_slice(&Endpoint::new(UNSPECIFIED, 1000).to_bytes());
+    share::scope::<AllowRw<_, DRIVER_NUM, ALLOW_RX_CONFIG>, _, _>(|handle| {
+        use libtock_platform::Syscalls;
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RX_CONFIG>(
+            handle,
+            &mut rx_config,
+        )
+        .unwrap();
+        assert_eq!(
+            fake::Syscalls::command(DRIVER_NUM, BIND, 0, 0).get_failure(),
+            Some(ErrorCode::Busy)
+        );
+    });
+    assert_eq!(udp.bound(), None);
+}